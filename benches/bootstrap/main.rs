@@ -10,7 +10,7 @@ fn exe_path() -> std::path::PathBuf {
 }
 
 fn host_rust() {
-  let mut sass = Sass::new(exe_path()).unwrap();
+  let sass = Sass::new(exe_path()).unwrap();
   let path = std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR")))
     .join("benches/bootstrap/bootstrap/scss");
   let _ = sass.compile(
@@ -12,7 +12,7 @@ fn main() {
   let path = std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR")))
     .join("examples/abc.scss");
   let now = time::Instant::now();
-  let mut sass = Sass::new(exe_path()).unwrap();
+  let sass = Sass::new(exe_path()).unwrap();
   let res = sass.compile(&path, Options::default()).unwrap();
   dbg!(res.loaded_urls, now.elapsed());
 }
@@ -7,7 +7,7 @@ fn exe_path() -> std::path::PathBuf {
 }
 
 fn main() {
-  let mut sass = Sass::new(exe_path()).unwrap();
+  let sass = Sass::new(exe_path()).unwrap();
   let res = sass
     .compile_string(
       "a {b: c}",
@@ -1,6 +1,8 @@
 use std::{
-  fmt::Debug,
+  collections::HashMap,
+  fmt::{self, Debug},
   path::{Path, PathBuf},
+  time::Duration,
 };
 
 #[cfg(feature = "serde")]
@@ -14,7 +16,8 @@ use crate::{
       CompileResponse,
     },
   },
-  Exception, Result, Url,
+  CancellationToken, Exception, Result, SourceMap, SourceMapOutput, StackFrame,
+  Url, Value,
 };
 
 /// Options that can be passed to [Sass::compile].
@@ -27,6 +30,9 @@ pub struct Options {
   pub alert_ascii: bool,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#alertColor)
   pub alert_color: Option<bool>,
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#functions)
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub functions: HashMap<String, BoxCustomFunction>,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#importers)
   #[cfg_attr(feature = "serde", serde(skip))]
   pub importers: Vec<SassImporter>,
@@ -35,18 +41,36 @@ pub struct Options {
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#logger)
   #[cfg_attr(feature = "serde", serde(skip))]
   pub logger: Option<BoxLogger>,
+  /// An [AsyncLogger], used instead of [Options::logger] by [AsyncEmbedded].
+  ///
+  /// [AsyncEmbedded]: crate::AsyncEmbedded
+  #[cfg(feature = "async")]
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub async_logger: Option<BoxAsyncLogger>,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#quietDeps)
   pub quiet_deps: bool,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#sourceMap)
   pub source_map: bool,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#sourceMapIncludeSources)
   pub source_map_include_sources: bool,
+  /// How the source map is adapted to where it and the CSS are written, and
+  /// whether [CompileResult::css] points to it. Setting its
+  /// [SourceMapOutput::url] generates a source map even if
+  /// [Options::source_map] isn't set.
+  pub source_map_output: SourceMapOutput,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#style)
   pub style: OutputStyle,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#verbose)
   pub verbose: bool,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Options#charset)
   pub charset: bool,
+  /// How long to wait for the compilation before failing it. The compiler is
+  /// restarted once the compilations it's still running are done.
+  pub timeout: Option<Duration>,
+  /// A token for cancelling the compilation from another thread, like
+  /// [Options::timeout].
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub cancellation_token: Option<CancellationToken>,
 }
 
 impl Default for Options {
@@ -54,15 +78,21 @@ impl Default for Options {
     Self {
       alert_ascii: false,
       alert_color: None,
+      functions: HashMap::new(),
       load_paths: Vec::new(),
       importers: Vec::new(),
       logger: None,
+      #[cfg(feature = "async")]
+      async_logger: None,
       quiet_deps: false,
       source_map: false,
       source_map_include_sources: false,
+      source_map_output: SourceMapOutput::default(),
       style: OutputStyle::default(),
       verbose: false,
       charset: true,
+      timeout: None,
+      cancellation_token: None,
     }
   }
 }
@@ -127,6 +157,12 @@ impl OptionsBuilder {
     self
   }
 
+  /// Sets the [Options]'s [source_map_output] field.
+  pub fn source_map_output(mut self, arg: SourceMapOutput) -> Self {
+    self.options.source_map_output = arg;
+    self
+  }
+
   /// Sets the [Options]'s [style] field.
   pub fn style(mut self, arg: impl Into<OutputStyle>) -> Self {
     self.options.style = arg.into();
@@ -145,6 +181,41 @@ impl OptionsBuilder {
     self
   }
 
+  /// Sets the [Options]'s [timeout] field.
+  pub fn timeout(mut self, arg: Duration) -> Self {
+    self.options.timeout = Some(arg);
+    self
+  }
+
+  /// Sets the [Options]'s [cancellation_token] field.
+  pub fn cancellation_token(mut self, arg: CancellationToken) -> Self {
+    self.options.cancellation_token = Some(arg);
+    self
+  }
+
+  /// Adds a [CustomFunction] with the given signature to the [Options]'s [functions] field.
+  pub fn function<F: 'static + CustomFunction>(
+    mut self,
+    signature: impl Into<String>,
+    arg: F,
+  ) -> Self {
+    self
+      .options
+      .functions
+      .insert(signature.into(), Box::new(arg) as Box<dyn CustomFunction>);
+    self
+  }
+
+  /// Sets the [Options]'s [functions] field.
+  pub fn functions(
+    mut self,
+    arg: impl IntoIterator<Item = (impl Into<String>, BoxCustomFunction)>,
+  ) -> Self {
+    self.options.functions =
+      arg.into_iter().map(|(k, v)| (k.into(), v)).collect();
+    self
+  }
+
   /// Sets the [Options]'s [logger] field.
   pub fn logger<L: 'static + Logger>(mut self, arg: L) -> Self {
     self.options.logger = Some(Box::new(arg));
@@ -182,6 +253,36 @@ impl OptionsBuilder {
     ));
     self
   }
+
+  /// Sets the [Options]'s [async_logger] field.
+  #[cfg(feature = "async")]
+  pub fn async_logger<L: 'static + AsyncLogger>(mut self, arg: L) -> Self {
+    self.options.async_logger = Some(Box::new(arg));
+    self
+  }
+
+  /// Adds a [AsyncImporter] to the [Options]'s [importers] field.
+  #[cfg(feature = "async")]
+  pub fn async_importer<I: 'static + AsyncImporter>(mut self, arg: I) -> Self {
+    self
+      .options
+      .importers
+      .push(SassImporter::AsyncImporter(Box::new(arg)));
+    self
+  }
+
+  /// Adds a [AsyncFileImporter] to the [Options]'s [importers] field.
+  #[cfg(feature = "async")]
+  pub fn async_file_importer<I: 'static + AsyncFileImporter>(
+    mut self,
+    arg: I,
+  ) -> Self {
+    self
+      .options
+      .importers
+      .push(SassImporter::AsyncFileImporter(Box::new(arg)));
+    self
+  }
 }
 
 /// Options that can be passed to [Sass::compile_string].
@@ -302,6 +403,12 @@ impl StringOptionsBuilder {
     self
   }
 
+  /// Sets the [StringOptions]'s [source_map_output] field.
+  pub fn source_map_output(mut self, arg: SourceMapOutput) -> Self {
+    self.options.source_map_output = arg;
+    self
+  }
+
   /// Sets the [StringOptions]'s [style] field.
   pub fn style(mut self, arg: impl Into<OutputStyle>) -> Self {
     self.options.style = arg.into();
@@ -320,6 +427,41 @@ impl StringOptionsBuilder {
     self
   }
 
+  /// Sets the [StringOptions]'s [timeout] field.
+  pub fn timeout(mut self, arg: Duration) -> Self {
+    self.options.timeout = Some(arg);
+    self
+  }
+
+  /// Sets the [StringOptions]'s [cancellation_token] field.
+  pub fn cancellation_token(mut self, arg: CancellationToken) -> Self {
+    self.options.cancellation_token = Some(arg);
+    self
+  }
+
+  /// Adds a [CustomFunction] with the given signature to the [StringOptions]'s [functions] field.
+  pub fn function<F: 'static + CustomFunction>(
+    mut self,
+    signature: impl Into<String>,
+    arg: F,
+  ) -> Self {
+    self
+      .options
+      .functions
+      .insert(signature.into(), Box::new(arg) as Box<dyn CustomFunction>);
+    self
+  }
+
+  /// Sets the [StringOptions]'s [functions] field.
+  pub fn functions(
+    mut self,
+    arg: impl IntoIterator<Item = (impl Into<String>, BoxCustomFunction)>,
+  ) -> Self {
+    self.options.functions =
+      arg.into_iter().map(|(k, v)| (k.into(), v)).collect();
+    self
+  }
+
   /// Sets the [StringOptions]'s [logger] field.
   pub fn logger<L: 'static + Logger>(mut self, arg: L) -> Self {
     self.options.logger = Some(Box::new(arg));
@@ -358,6 +500,57 @@ impl StringOptionsBuilder {
       .push(SassImporter::FileImporter(Box::new(arg)));
     self
   }
+
+  /// Sets the [StringOptions]'s [input_importer] field with a [AsyncImporter].
+  #[cfg(feature = "async")]
+  pub fn input_async_importer<I: 'static + AsyncImporter>(
+    mut self,
+    arg: I,
+  ) -> Self {
+    self.input_importer = Some(SassImporter::AsyncImporter(Box::new(arg)));
+    self
+  }
+
+  /// Sets the [StringOptions]'s [input_importer] field with a
+  /// [AsyncFileImporter].
+  #[cfg(feature = "async")]
+  pub fn input_async_file_importer<I: 'static + AsyncFileImporter>(
+    mut self,
+    arg: I,
+  ) -> Self {
+    self.input_importer = Some(SassImporter::AsyncFileImporter(Box::new(arg)));
+    self
+  }
+
+  /// Sets the [StringOptions]'s [async_logger] field.
+  #[cfg(feature = "async")]
+  pub fn async_logger<L: 'static + AsyncLogger>(mut self, arg: L) -> Self {
+    self.options.async_logger = Some(Box::new(arg));
+    self
+  }
+
+  /// Adds a [AsyncImporter] to the [StringOptions]'s [importers] field.
+  #[cfg(feature = "async")]
+  pub fn async_importer<I: 'static + AsyncImporter>(mut self, arg: I) -> Self {
+    self
+      .options
+      .importers
+      .push(SassImporter::AsyncImporter(Box::new(arg)));
+    self
+  }
+
+  /// Adds a [AsyncFileImporter] to the [StringOptions]'s [importers] field.
+  #[cfg(feature = "async")]
+  pub fn async_file_importer<I: 'static + AsyncFileImporter>(
+    mut self,
+    arg: I,
+  ) -> Self {
+    self
+      .options
+      .importers
+      .push(SassImporter::AsyncFileImporter(Box::new(arg)));
+    self
+  }
 }
 
 /// A type alias for [Box<dyn Logger>].
@@ -376,6 +569,27 @@ pub trait Logger: Debug + Send + Sync {
   }
 }
 
+/// A type alias for [Box<dyn AsyncLogger>].
+#[cfg(feature = "async")]
+pub type BoxAsyncLogger = Box<dyn AsyncLogger>;
+
+/// An asynchronous [Logger], which can only be used with [AsyncEmbedded].
+///
+/// [AsyncEmbedded]: crate::AsyncEmbedded
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncLogger: Debug + Send + Sync {
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Logger#warn)
+  async fn warn(&self, _message: &str, options: &LoggerWarnOptions) {
+    eprintln!("{}", options.formatted);
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Logger#debug)
+  async fn debug(&self, _message: &str, options: &LoggerDebugOptions) {
+    eprintln!("{}", options.formatted);
+  }
+}
+
 /// Options for [Logger::warn].
 ///
 /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Logger#warn)
@@ -387,6 +601,28 @@ pub struct LoggerWarnOptions {
   /// The Sass stack trace at the point the warning was issued.
   pub stack: Option<String>,
   pub(crate) formatted: String,
+  /// The working directory of the compiler, if it's not ours.
+  pub(crate) compiler_dir: Option<PathBuf>,
+}
+
+impl LoggerWarnOptions {
+  /// The warning as the compiler formatted it, including the source span and
+  /// the stack trace. Use [Report::warning](crate::Report::warning) to
+  /// render it yourself.
+  pub fn formatted(&self) -> &str {
+    &self.formatted
+  }
+
+  /// The frames of [LoggerWarnOptions::stack].
+  pub fn stack_frames(&self) -> Vec<StackFrame> {
+    self
+      .stack
+      .as_deref()
+      .map(|stack| {
+        StackFrame::parse_trace_in(stack, self.compiler_dir.as_deref())
+      })
+      .unwrap_or_default()
+  }
 }
 
 /// Options for [Logger::debug].
@@ -398,6 +634,42 @@ pub struct LoggerDebugOptions {
   pub(crate) formatted: String,
 }
 
+impl LoggerDebugOptions {
+  /// The debug message as the compiler formatted it, including the source
+  /// span. Use [Report::debug](crate::Report::debug) to render it yourself.
+  pub fn formatted(&self) -> &str {
+    &self.formatted
+  }
+}
+
+/// A type alias for [Box<dyn CustomFunction>].
+pub type BoxCustomFunction = Box<dyn CustomFunction>;
+
+/// A callback that implements a custom Sass function, which is implemented
+/// for any `Fn(&[Value]) -> Result<Value>` closure.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/modules#CustomFunction)
+pub trait CustomFunction: Send + Sync {
+  /// Calls the function with the arguments passed from Sass, in the order
+  /// they appear in the function's signature.
+  fn call(&self, args: &[Value]) -> Result<Value>;
+}
+
+impl<F> CustomFunction for F
+where
+  F: Fn(&[Value]) -> Result<Value> + Send + Sync,
+{
+  fn call(&self, args: &[Value]) -> Result<Value> {
+    self(args)
+  }
+}
+
+impl Debug for dyn CustomFunction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("CustomFunction")
+  }
+}
+
 /// Enum wrapper for [BoxImporter] and [BoxFileImporter].
 #[derive(Debug)]
 pub enum SassImporter {
@@ -405,6 +677,25 @@ pub enum SassImporter {
   Importer(BoxImporter),
   /// A [BoxFileImporter].
   FileImporter(BoxFileImporter),
+  /// A [BoxAsyncImporter].
+  #[cfg(feature = "async")]
+  AsyncImporter(BoxAsyncImporter),
+  /// A [BoxAsyncFileImporter].
+  #[cfg(feature = "async")]
+  AsyncFileImporter(BoxAsyncFileImporter),
+}
+
+impl SassImporter {
+  /// Whether this importer can only be used with [AsyncEmbedded].
+  ///
+  /// [AsyncEmbedded]: crate::AsyncEmbedded
+  pub(crate) fn is_async(&self) -> bool {
+    match self {
+      Self::Importer(_) | Self::FileImporter(_) => false,
+      #[cfg(feature = "async")]
+      Self::AsyncImporter(_) | Self::AsyncFileImporter(_) => true,
+    }
+  }
 }
 
 /// A type alias for [Box<dyn Importer>].
@@ -445,6 +736,46 @@ pub trait FileImporter: Debug + Send + Sync {
   ) -> Result<Option<Url>>;
 }
 
+/// A type alias for [Box<dyn AsyncImporter>].
+#[cfg(feature = "async")]
+pub type BoxAsyncImporter = Box<dyn AsyncImporter>;
+
+/// A type alias for [Box<dyn AsyncFileImporter>].
+#[cfg(feature = "async")]
+pub type BoxAsyncFileImporter = Box<dyn AsyncFileImporter>;
+
+/// An asynchronous [Importer], which can only be used with [AsyncEmbedded].
+///
+/// [AsyncEmbedded]: crate::AsyncEmbedded
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncImporter: Debug + Send + Sync {
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Importer#canonicalize)
+  async fn canonicalize(
+    &self,
+    url: &str,
+    options: &ImporterOptions,
+  ) -> Result<Option<Url>>;
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/Importer#load)
+  async fn load(&self, canonical_url: &Url) -> Result<Option<ImporterResult>>;
+}
+
+/// An asynchronous [FileImporter], which can only be used with
+/// [AsyncEmbedded].
+///
+/// [AsyncEmbedded]: crate::AsyncEmbedded
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncFileImporter: Debug + Send + Sync {
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/FileImporter#findFileUrl)
+  async fn find_file_url(
+    &self,
+    url: &str,
+    options: &ImporterOptions,
+  ) -> Result<Option<Url>>;
+}
+
 /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/ImporterResult)
 pub struct ImporterResult {
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/ImporterResult#contents)
@@ -467,6 +798,13 @@ pub struct CompileResult {
   pub source_map: Option<String>,
 }
 
+impl CompileResult {
+  /// Parses [CompileResult::source_map], if there's one.
+  pub fn parse_source_map(&self) -> Result<Option<SourceMap>> {
+    self.source_map.as_deref().map(SourceMap::parse).transpose()
+  }
+}
+
 impl TryFrom<CompileResponse> for CompileResult {
   type Error = Box<Exception>;
 
@@ -589,11 +927,31 @@ impl From<protocol::SourceSpan> for SourceSpan {
   }
 }
 
+#[cfg(feature = "testing")]
+impl From<SourceSpan> for protocol::SourceSpan {
+  fn from(span: SourceSpan) -> Self {
+    Self {
+      text: span.text,
+      start: Some(span.start.into()),
+      end: Some(span.end.into()),
+      url: span.url.map(|url| url.to_string()).unwrap_or_default(),
+      context: span.context.unwrap_or_default(),
+    }
+  }
+}
+
+/// A single point in a source file.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/SourceLocation)
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
 pub struct SourceLocation {
+  /// The 0-based index of this location within its source file, in terms of
+  /// UTF-16 code units.
   pub offset: usize,
+  /// The 0-based line number of this location.
   pub line: usize,
+  /// The 0-based column number of this location.
   pub column: usize,
 }
 
@@ -606,3 +964,14 @@ impl From<protocol::source_span::SourceLocation> for SourceLocation {
     }
   }
 }
+
+#[cfg(feature = "testing")]
+impl From<SourceLocation> for protocol::source_span::SourceLocation {
+  fn from(location: SourceLocation) -> Self {
+    Self {
+      offset: location.offset as u32,
+      line: location.line as u32,
+      column: location.column as u32,
+    }
+  }
+}
@@ -0,0 +1,19 @@
+//! An asynchronous compiler on top of [tokio](https://tokio.rs).
+//!
+//! It mirrors the synchronous [Embedded](crate::Embedded), but talks to the
+//! compiler over [AsyncTransport]s: messages from the compiler are read and
+//! dispatched by a tokio task instead of a thread, and each request from the
+//! compiler is handled in its own task, so async importers and loggers never
+//! block the compilations running alongside them.
+
+mod channel;
+mod compiler;
+mod connection;
+mod dispatcher;
+mod embedded;
+mod transport;
+
+pub use embedded::AsyncEmbedded;
+#[cfg(unix)]
+pub use transport::AsyncUnixTransport;
+pub use transport::{AsyncChildTransport, AsyncTcpTransport, AsyncTransport};
@@ -0,0 +1,159 @@
+use std::{io, sync::Arc};
+
+use tokio::sync::Mutex;
+
+use crate::{
+  host::Host,
+  restart::Supervisor,
+  stderr::{SharedStderrOptions, StderrOptions},
+  Exception, ExceptionKind, Result, VersionInfo,
+};
+
+use super::{
+  compiler::Compiler,
+  connection::{ConnectedGuard, Connection},
+  dispatcher::Dispatcher,
+  transport::Connector,
+};
+
+#[derive(Debug)]
+pub struct Channel {
+  connector: Connector,
+  state: Mutex<State>,
+  supervisor: Mutex<Supervisor>,
+  stderr: SharedStderrOptions,
+}
+
+#[derive(Debug)]
+struct State {
+  /// The dispatcher of the running compiler, which is replaced with a new
+  /// one once the compiler crashes or reports a protocol error.
+  dispatcher: Arc<Dispatcher>,
+  /// Whether the handshake with the running compiler is still to be done,
+  /// since the first compiler is started outside of an async context.
+  unchecked: bool,
+}
+
+impl Channel {
+  pub fn new(connector: Connector) -> Result<Self> {
+    let stderr = SharedStderrOptions::default();
+    let compiler = Compiler::new(&connector, &stderr)?;
+    let state = Mutex::new(State {
+      dispatcher: Dispatcher::new(compiler),
+      unchecked: connector.handshake(),
+    });
+    Ok(Self {
+      connector,
+      state,
+      supervisor: Mutex::default(),
+      stderr,
+    })
+  }
+
+  /// Starts a compiler, and checks its protocol version if the connector
+  /// asks for a handshake.
+  async fn start(&self) -> Result<Arc<Dispatcher>> {
+    let dispatcher =
+      Dispatcher::new(Compiler::new(&self.connector, &self.stderr)?);
+    if self.connector.handshake() {
+      Self::handshake(&dispatcher).await?;
+    }
+    Ok(dispatcher)
+  }
+
+  /// Checks the protocol version of the compiler of `dispatcher`, and closes
+  /// it if it's incompatible.
+  async fn handshake(dispatcher: &Arc<Dispatcher>) -> Result<()> {
+    let conn = Connection::new(Arc::clone(dispatcher));
+    let checked = match dispatcher.subscribe(conn, Host::default()) {
+      Ok(conn) => match conn.version_request().await {
+        Ok(response) => VersionInfo::try_from(response)
+          .and_then(|version| version.check_compatible()),
+        Err(e) => Err(e),
+      },
+      Err(_) => {
+        let eof = io::ErrorKind::UnexpectedEof.into();
+        Err(Box::new(Exception::io(
+          "The compiler exited unexpectedly",
+          eof,
+        )))
+      }
+    };
+    if checked.is_err() {
+      dispatcher.close();
+    }
+    checked
+  }
+
+  pub fn supervisor(&mut self) -> &mut Supervisor {
+    self.supervisor.get_mut()
+  }
+
+  pub fn set_stderr_options(&self, options: StderrOptions) {
+    *self.stderr.lock() = options;
+  }
+
+  /// The last lines the running compiler wrote to stderr.
+  pub async fn stderr(&self) -> Vec<String> {
+    self.state.lock().await.dispatcher.stderr()
+  }
+
+  pub async fn connect(&self, mut host: Host) -> Result<ConnectedGuard> {
+    host.set_compiler_dir(self.connector.current_dir());
+    let mut state = self.state.lock().await;
+    if state.unchecked {
+      state.unchecked = false;
+      Self::handshake(&state.dispatcher).await?;
+    }
+    loop {
+      if !state.dispatcher.is_closed() {
+        let conn = Connection::new(Arc::clone(&state.dispatcher));
+        match state.dispatcher.subscribe(conn, host) {
+          Ok(conn) => return Ok(conn),
+          // Closed in the meantime.
+          Err(unsubscribed) => host = unsubscribed.1,
+        }
+      }
+      // Gives up once the restart policy runs out of retries.
+      self.restart(&mut state).await?;
+    }
+  }
+
+  /// Replaces the closed dispatcher with one for a new compiler, according
+  /// to the restart policy.
+  async fn restart(&self, state: &mut State) -> Result<()> {
+    if state.dispatcher.is_retired() {
+      // Replaced after an interrupted compilation, rather than crashed.
+      state.dispatcher = self.start().await?;
+      return Ok(());
+    }
+    let mut supervisor = self.supervisor.lock().await;
+    let dispatcher = &state.dispatcher;
+    let status = dispatcher.exit_status();
+    // Compilations that are still running may finish after a protocol error.
+    if dispatcher.load() == 0 {
+      dispatcher.close();
+    }
+    supervisor.crashed(status, dispatcher.has_responded());
+    loop {
+      let (attempt, backoff) = supervisor.next_attempt()?;
+      tokio::time::sleep(backoff).await;
+      match self.start().await {
+        Ok(dispatcher) => {
+          state.dispatcher = dispatcher;
+          supervisor.restarted(attempt);
+          return Ok(());
+        }
+        // Starting the same compiler again wouldn't make it compatible.
+        Err(e) if e.kind() == ExceptionKind::Version => return Err(e),
+        Err(e) => supervisor.restart_failed(attempt, &e),
+      }
+    }
+  }
+}
+
+impl Drop for Channel {
+  fn drop(&mut self) {
+    self.state.get_mut().dispatcher.close();
+  }
+}
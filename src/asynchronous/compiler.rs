@@ -0,0 +1,71 @@
+use std::{io, process::ExitStatus};
+
+use prost::Message;
+
+use crate::{
+  protocol::{InboundMessage, OutboundMessage},
+  stderr::SharedStderrOptions,
+  Exception, ExceptionKind, Result,
+};
+
+use super::transport::{AsyncTransport, Connector};
+
+/// Encodes and decodes the messages sent over an [AsyncTransport].
+#[derive(Debug)]
+pub struct Compiler {
+  transport: Box<dyn AsyncTransport>,
+}
+
+impl Compiler {
+  /// Connects to the compiler, which must be done within a tokio runtime.
+  pub fn new(
+    connector: &Connector,
+    stderr: &SharedStderrOptions,
+  ) -> Result<Self> {
+    let transport = connector.connect(stderr).map_err(|e| {
+      Exception::new(e.to_string())
+        .with_kind(ExceptionKind::Spawn)
+        .set_source(e)
+    })?;
+    Ok(Self { transport })
+  }
+
+  /// The last lines the compiler wrote to stderr.
+  pub fn stderr(&self) -> Vec<String> {
+    self.transport.stderr()
+  }
+
+  /// The last lines the compiler wrote to stderr before it failed, which
+  /// waits briefly for the rest of the output of the exiting process.
+  pub async fn final_stderr(&self) -> Vec<String> {
+    self.transport.final_stderr().await
+  }
+
+  pub async fn write(&self, message: InboundMessage) -> io::Result<()> {
+    self.transport.write(&message.encode_to_vec()).await
+  }
+
+  /// Reads the next message, or returns an error once the transport is
+  /// closed.
+  pub async fn read(&self) -> io::Result<OutboundMessage> {
+    let buf = self.transport.read().await?;
+    OutboundMessage::decode(&buf[..])
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+
+  /// Whether the compiler has exited.
+  pub fn has_exited(&self) -> bool {
+    self.transport.has_exited()
+  }
+
+  /// The exit status of the compiler process, if it has exited.
+  pub fn exit_status(&self) -> Option<ExitStatus> {
+    self.transport.exit_status()
+  }
+
+  /// Closes the transport, e.g. kills the compiler process, which stops the
+  /// dispatcher reading from it.
+  pub fn kill(&self) {
+    self.transport.close();
+  }
+}
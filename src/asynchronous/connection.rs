@@ -0,0 +1,232 @@
+use std::{fmt::Debug, ops::Deref, sync::Arc};
+
+use tokio::sync::{
+  mpsc::{self, UnboundedReceiver, UnboundedSender},
+  Mutex,
+};
+
+use crate::{
+  cancellation::Interrupt,
+  host::Host,
+  protocol::{
+    inbound_message::{self, CompileRequest, VersionRequest},
+    outbound_message::{
+      CanonicalizeRequest, CompileResponse, FileImportRequest,
+      FunctionCallRequest, ImportRequest, LogEvent, VersionResponse,
+    },
+    InboundMessage, ProtocolError,
+  },
+  Exception, Result,
+};
+
+use super::dispatcher::Dispatcher;
+
+enum ProtocolResponse {
+  Compile(CompileResponse),
+  Version(VersionResponse),
+}
+
+type Response = Result<ProtocolResponse>;
+
+#[derive(Debug)]
+pub struct Connected {
+  id: u32,
+  tx: UnboundedSender<Response>,
+  rx: Mutex<UnboundedReceiver<Response>>,
+  host: Host,
+}
+
+#[derive(Debug)]
+pub struct Unconnected;
+
+pub struct Connection<S: Debug> {
+  state: S,
+  dispatcher: Arc<Dispatcher>,
+}
+
+impl<S: Debug> Debug for Connection<S> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.state.fmt(f)
+  }
+}
+
+pub struct ConnectedGuard(pub(crate) Arc<Connection<Connected>>);
+
+impl Drop for ConnectedGuard {
+  fn drop(&mut self) {
+    self.0.disconnect();
+  }
+}
+
+impl Deref for ConnectedGuard {
+  type Target = Arc<Connection<Connected>>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl Connection<Unconnected> {
+  pub fn new(dispatcher: Arc<Dispatcher>) -> Connection<Unconnected> {
+    Self {
+      state: Unconnected,
+      dispatcher,
+    }
+  }
+
+  pub fn connect(self, id: u32, host: Host) -> ConnectedGuard {
+    let (tx, rx) = mpsc::unbounded_channel();
+    ConnectedGuard(Arc::new(Connection {
+      state: Connected {
+        id,
+        tx,
+        rx: Mutex::new(rx),
+        host,
+      },
+      dispatcher: self.dispatcher,
+    }))
+  }
+}
+
+impl Connection<Connected> {
+  fn id(&self) -> u32 {
+    self.state.id
+  }
+
+  /// The host of the compilation.
+  pub fn host(&self) -> &Host {
+    &self.state.host
+  }
+
+  fn disconnect(&self) {
+    self.dispatcher.unsubscribe(&self.id());
+  }
+
+  async fn send_message(&self, inbound_message: InboundMessage) -> Result<()> {
+    self.dispatcher.send_message(inbound_message).await
+  }
+
+  /// Sends a response to a request of the compiler, failing the pending
+  /// request if it can't be written.
+  async fn reply(&self, message: inbound_message::Message) {
+    if let Err(e) = self
+      .send_message(InboundMessage {
+        message: Some(message),
+      })
+      .await
+    {
+      self.response(Err(e));
+    }
+  }
+
+  fn response(&self, response: Response) {
+    // The receiver lives as long as this connection, so sending can't fail.
+    let _ = self.state.tx.send(response);
+  }
+
+  async fn recv(&self) -> Response {
+    self.state.rx.lock().await.recv().await.unwrap()
+  }
+
+  pub fn error(&self, message: ProtocolError) {
+    self.response(Err(Box::new(message.into())));
+  }
+
+  /// Fails the pending request, because the compiler can't be reached.
+  pub fn fail(&self, exception: Exception) {
+    self.response(Err(Box::new(exception)));
+  }
+
+  pub async fn log_event(&self, e: LogEvent) {
+    self.state.host.log_async(e).await;
+  }
+
+  pub async fn canonicalize_request(&self, e: CanonicalizeRequest) {
+    let response = self.state.host.canonicalize_async(&e).await;
+    self
+      .reply(inbound_message::Message::CanonicalizeResponse(response))
+      .await;
+  }
+
+  pub async fn import_request(&self, e: ImportRequest) {
+    let response = self.state.host.import_async(&e).await;
+    self
+      .reply(inbound_message::Message::ImportResponse(response))
+      .await;
+  }
+
+  pub async fn file_import_request(&self, e: FileImportRequest) {
+    let response = self.state.host.file_import_async(&e).await;
+    self
+      .reply(inbound_message::Message::FileImportResponse(response))
+      .await;
+  }
+
+  pub async fn function_call_request(&self, e: FunctionCallRequest) {
+    let response = self.state.host.function_call(&e);
+    self
+      .reply(inbound_message::Message::FunctionCallResponse(response))
+      .await;
+  }
+
+  pub async fn compile_request(
+    &self,
+    mut request: CompileRequest,
+    interrupt: &Interrupt,
+  ) -> Result<CompileResponse> {
+    request.id = self.id();
+    self
+      .send_message(InboundMessage {
+        message: Some(inbound_message::Message::CompileRequest(request)),
+      })
+      .await?;
+    let timeout = async {
+      match interrupt.timeout {
+        Some(timeout) => tokio::time::sleep(timeout).await,
+        None => std::future::pending().await,
+      }
+    };
+    let cancelled = async {
+      match &interrupt.token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+      }
+    };
+    let exception = tokio::select! {
+      response = self.recv() => {
+        return response.map(|response| match response {
+          ProtocolResponse::Compile(response) => response,
+          _ => unreachable!(),
+        });
+      }
+      _ = timeout => interrupt.timed_out(),
+      _ = cancelled => interrupt.cancelled(),
+    };
+    // The compiler may still be running the compilation, e.g. an endless
+    // loop, so it's replaced once the other compilations are done.
+    self.dispatcher.retire();
+    Err(Box::new(exception))
+  }
+
+  pub fn compile_response(&self, response: CompileResponse) {
+    self.response(Ok(ProtocolResponse::Compile(response)));
+  }
+
+  pub async fn version_request(&self) -> Result<VersionResponse> {
+    self
+      .send_message(InboundMessage {
+        message: Some(inbound_message::Message::VersionRequest(
+          VersionRequest { id: self.id() },
+        )),
+      })
+      .await?;
+    self.recv().await.map(|response| match response {
+      ProtocolResponse::Version(response) => response,
+      _ => unreachable!(),
+    })
+  }
+
+  pub fn version_response(&self, response: VersionResponse) {
+    self.response(Ok(ProtocolResponse::Version(response)));
+  }
+}
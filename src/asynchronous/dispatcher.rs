@@ -0,0 +1,223 @@
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::{
+  io,
+  process::ExitStatus,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+};
+
+use crate::{
+  host::Host,
+  protocol::{outbound_message, InboundMessage, OutboundMessage},
+  Exception,
+};
+
+use super::{
+  compiler::Compiler,
+  connection::{Connected, ConnectedGuard, Connection, Unconnected},
+};
+
+#[derive(Debug)]
+pub struct Dispatcher {
+  compiler: Compiler,
+  observers: DashMap<u32, Arc<Connection<Connected>>>,
+  id: Mutex<u32>,
+  /// Whether the compiler has completed a request.
+  responded: AtomicBool,
+  /// Whether the compiler is killed once its compilations are done.
+  retired: AtomicBool,
+}
+
+impl Dispatcher {
+  const PROTOCOL_ERROR_ID: u32 = 0xffffffff; // u32::MAX
+
+  /// Creates a dispatcher and spawns the task that reads from the compiler,
+  /// so it must be called within a tokio runtime.
+  pub fn new(compiler: Compiler) -> Arc<Dispatcher> {
+    let this = Arc::new(Self {
+      compiler,
+      observers: DashMap::new(),
+      id: Mutex::new(0),
+      responded: AtomicBool::new(false),
+      retired: AtomicBool::new(false),
+    });
+    Self::spawn(Arc::clone(&this));
+    this
+  }
+
+  fn spawn(dispatcher: Arc<Dispatcher>) {
+    tokio::spawn(async move {
+      loop {
+        match dispatcher.compiler.read().await {
+          Ok(message) => dispatcher.receive_message(message).await,
+          Err(e) => break dispatcher.fail(e).await,
+        }
+      }
+    });
+  }
+
+  pub fn subscribe(
+    &self,
+    observer: Connection<Unconnected>,
+    host: Host,
+  ) -> Result<ConnectedGuard, Box<(Connection<Unconnected>, Host)>> {
+    let mut id = self.id.lock();
+    if *id == Self::PROTOCOL_ERROR_ID {
+      return Err(Box::new((observer, host)));
+    }
+    let observer = observer.connect(*id, host);
+    self.observers.insert(*id, Arc::clone(&observer.0));
+    *id += 1;
+    Ok(observer)
+  }
+
+  /// Closes the dispatcher after reading from the compiler failed, failing
+  /// every pending connection with `error`.
+  async fn fail(&self, error: io::Error) {
+    // Set while holding the lock, so no connection subscribes after this.
+    *self.id.lock() = Self::PROTOCOL_ERROR_ID;
+    let context = if error.kind() == io::ErrorKind::UnexpectedEof {
+      "The compiler exited unexpectedly"
+    } else {
+      "Failed to read from the compiler"
+    };
+    let stderr = self.compiler.final_stderr().await;
+    for ob in self.observers.iter() {
+      let error = io::Error::new(error.kind(), error.to_string());
+      ob.fail(
+        Exception::io(context, error).with_compiler_stderr(stderr.clone()),
+      );
+    }
+  }
+
+  /// The number of compilations in flight.
+  pub fn load(&self) -> usize {
+    self.observers.len()
+  }
+
+  /// Whether the compiler can no longer be used, because its process has
+  /// exited or it reported a protocol error.
+  pub fn is_closed(&self) -> bool {
+    *self.id.lock() == Self::PROTOCOL_ERROR_ID || self.compiler.has_exited()
+  }
+
+  /// Whether the compiler has completed a request.
+  pub fn has_responded(&self) -> bool {
+    self.responded.load(Ordering::Relaxed)
+  }
+
+  /// The exit status of the compiler process, if it has exited.
+  pub fn exit_status(&self) -> Option<ExitStatus> {
+    self.compiler.exit_status()
+  }
+
+  /// The last lines the compiler wrote to stderr.
+  pub fn stderr(&self) -> Vec<String> {
+    self.compiler.stderr()
+  }
+
+  /// Kills the compiler process.
+  pub fn close(&self) {
+    self.compiler.kill();
+  }
+
+  /// Stops new compilations from using the compiler, and kills it once the
+  /// compilations in flight are done.
+  pub fn retire(&self) {
+    *self.id.lock() = Self::PROTOCOL_ERROR_ID;
+    self.retired.store(true, Ordering::Relaxed);
+    if self.observers.is_empty() {
+      self.close();
+    }
+  }
+
+  /// Whether [Dispatcher::retire] was called.
+  pub fn is_retired(&self) -> bool {
+    self.retired.load(Ordering::Relaxed)
+  }
+
+  pub fn unsubscribe(&self, id: &u32) {
+    self.observers.remove(id);
+    if self.is_retired() && self.observers.is_empty() {
+      self.close();
+    }
+  }
+
+  pub async fn send_message(
+    &self,
+    inbound_message: InboundMessage,
+  ) -> Result<(), Box<Exception>> {
+    match self.compiler.write(inbound_message).await {
+      Ok(()) => Ok(()),
+      Err(e) => Err(Box::new(
+        Exception::io("Failed to write to the compiler", e)
+          .with_compiler_stderr(self.compiler.final_stderr().await),
+      )),
+    }
+  }
+
+  /// Returns the observer with the given ID, without holding a lock on the
+  /// observers across an `.await`.
+  fn observer(&self, id: u32) -> Option<Arc<Connection<Connected>>> {
+    self.observers.get(&id).map(|ob| Arc::clone(&ob))
+  }
+
+  async fn receive_message(&self, outbound_message: OutboundMessage) {
+    let Some(oneof) = outbound_message.message else {
+      return;
+    };
+    match oneof {
+      outbound_message::Message::Error(e) => {
+        *self.id.lock() = Self::PROTOCOL_ERROR_ID;
+        if e.id == Self::PROTOCOL_ERROR_ID {
+          for ob in self.observers.iter() {
+            ob.error(e.clone());
+          }
+        } else if let Some(ob) = self.observer(e.id) {
+          ob.error(e);
+        }
+      }
+      outbound_message::Message::CompileResponse(e) => {
+        self.responded.store(true, Ordering::Relaxed);
+        if let Some(ob) = self.observer(e.id) {
+          ob.compile_response(e);
+        }
+      }
+      outbound_message::Message::VersionResponse(e) => {
+        self.responded.store(true, Ordering::Relaxed);
+        if let Some(ob) = self.observer(e.id) {
+          ob.version_response(e);
+        }
+      }
+      outbound_message::Message::LogEvent(e) => {
+        // Logged before reading the next message to keep events in order.
+        if let Some(ob) = self.observer(e.compilation_id) {
+          ob.log_event(e).await;
+        }
+      }
+      outbound_message::Message::CanonicalizeRequest(e) => {
+        if let Some(ob) = self.observer(e.compilation_id) {
+          tokio::spawn(async move { ob.canonicalize_request(e).await });
+        }
+      }
+      outbound_message::Message::ImportRequest(e) => {
+        if let Some(ob) = self.observer(e.compilation_id) {
+          tokio::spawn(async move { ob.import_request(e).await });
+        }
+      }
+      outbound_message::Message::FileImportRequest(e) => {
+        if let Some(ob) = self.observer(e.compilation_id) {
+          tokio::spawn(async move { ob.file_import_request(e).await });
+        }
+      }
+      outbound_message::Message::FunctionCallRequest(e) => {
+        if let Some(ob) = self.observer(e.compilation_id) {
+          tokio::spawn(async move { ob.function_call_request(e).await });
+        }
+      }
+    }
+  }
+}
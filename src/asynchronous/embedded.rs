@@ -0,0 +1,218 @@
+use std::{ffi::OsStr, io, path::Path, sync::Arc};
+
+use crate::{
+  cancellation::Interrupt,
+  embedded::{compile_request, compile_result, compile_string_request},
+  host::Host,
+  CompileResult, CompilerCommand, CompilerEvent, DiscoveryOptions, Options,
+  RestartPolicy, Result, StderrOptions, StringOptions, Transport, VersionInfo,
+};
+
+use super::{
+  channel::Channel,
+  transport::{AsyncTransport, Connector},
+};
+
+/// The sass-embedded compiler for async rust hosts, which supports
+/// [AsyncImporter], [AsyncFileImporter] and [AsyncLogger] in addition to
+/// their synchronous counterparts.
+///
+/// It must be created within a [tokio](https://tokio.rs) runtime.
+///
+/// [AsyncImporter]: crate::AsyncImporter
+/// [AsyncFileImporter]: crate::AsyncFileImporter
+/// [AsyncLogger]: crate::AsyncLogger
+#[derive(Debug)]
+pub struct AsyncEmbedded {
+  channel: Channel,
+}
+
+impl AsyncEmbedded {
+  /// Creates a sass-embedded compiler and connects with the dart-sass-embedded.
+  ///
+  /// ```no_run
+  /// # async fn run() {
+  /// let sass =
+  ///   sass_embedded::AsyncEmbedded::new("path/to/sass_embedded").unwrap();
+  /// # }
+  /// ```
+  pub fn new(exe_path: impl AsRef<OsStr>) -> Result<Self> {
+    Self::with_command(CompilerCommand::new(exe_path))
+  }
+
+  /// Creates a sass-embedded compiler that's started with `command`, like
+  /// [Embedded::with_command].
+  ///
+  /// With [CompilerCommand::handshake], the first compiler is checked before
+  /// the first request rather than here, since that needs to await it.
+  ///
+  /// [Embedded::with_command]: crate::Embedded::with_command
+  pub fn with_command(command: CompilerCommand) -> Result<Self> {
+    Ok(Self {
+      channel: Channel::new(Connector::command(command))?,
+    })
+  }
+
+  /// Creates a sass-embedded compiler that talks to the compiler over the
+  /// [Transport] that `connect` opens, like [Embedded::with_transport].
+  ///
+  /// Its blocking reads run on a dedicated thread and its writes on tokio's
+  /// blocking pool, so prefer [AsyncEmbedded::with_async_transport] for
+  /// anything but in-memory transports.
+  ///
+  /// [Embedded::with_transport]: crate::Embedded::with_transport
+  pub fn with_transport<T: Transport + 'static>(
+    connect: impl Fn() -> io::Result<T> + Send + Sync + 'static,
+  ) -> Result<Self> {
+    Ok(Self {
+      channel: Channel::new(Connector::blocking(connect))?,
+    })
+  }
+
+  /// Creates a sass-embedded compiler that talks to the compiler over the
+  /// [AsyncTransport] that `connect` opens, every time the compiler is
+  /// (re)started.
+  ///
+  /// ```no_run
+  /// # async fn run() {
+  /// use sass_embedded::{AsyncEmbedded, AsyncTcpTransport};
+  ///
+  /// let sass = AsyncEmbedded::with_async_transport(|| {
+  ///   let stream = std::net::TcpStream::connect("127.0.0.1:7777")?;
+  ///   AsyncTcpTransport::from_std(stream)
+  /// })
+  /// .unwrap();
+  /// # }
+  /// ```
+  pub fn with_async_transport<T: AsyncTransport + 'static>(
+    connect: impl Fn() -> io::Result<T> + Send + Sync + 'static,
+  ) -> Result<Self> {
+    Ok(Self {
+      channel: Channel::new(Connector::custom(connect))?,
+    })
+  }
+
+  /// Creates a sass-embedded compiler with the dart-sass-embedded executable
+  /// found in the default locations, like [Embedded::discover].
+  ///
+  /// [Embedded::discover]: crate::Embedded::discover
+  pub fn discover() -> Result<Self> {
+    Self::discover_with(&DiscoveryOptions::default())
+  }
+
+  /// Creates a sass-embedded compiler with the dart-sass-embedded executable
+  /// found in the locations of `options`, like [Embedded::discover_with].
+  ///
+  /// [Embedded::discover_with]: crate::Embedded::discover_with
+  pub fn discover_with(options: &DiscoveryOptions) -> Result<Self> {
+    Self::new(options.find()?)
+  }
+
+  /// Sets how the compiler is restarted once its process crashes or it
+  /// reports a protocol error, like [Embedded::with_restart_policy].
+  ///
+  /// [Embedded::with_restart_policy]: crate::Embedded::with_restart_policy
+  pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+    self.channel.supervisor().policy = policy;
+    self
+  }
+
+  /// Sets a hook that's called with every [CompilerEvent], like
+  /// [Embedded::on_compiler_event].
+  ///
+  /// [Embedded::on_compiler_event]: crate::Embedded::on_compiler_event
+  pub fn on_compiler_event(
+    mut self,
+    hook: impl Fn(&CompilerEvent) + Send + Sync + 'static,
+  ) -> Self {
+    self.channel.supervisor().on_event = Some(Arc::new(hook));
+    self
+  }
+  /// Sets where the output the compiler writes to stderr goes, like
+  /// [Embedded::with_stderr].
+  ///
+  /// [Embedded::with_stderr]: crate::Embedded::with_stderr
+  pub fn with_stderr(self, options: StderrOptions) -> Self {
+    self.channel.set_stderr_options(options);
+    self
+  }
+
+  /// The last lines the running compiler wrote to stderr.
+  pub async fn stderr(&self) -> Vec<String> {
+    self.channel.stderr().await
+  }
+
+  /// Compiles the Sass file at path to CSS. If it succeeds it returns a [CompileResult],
+  /// and if it fails it throws an [Exception](crate::Exception).
+  ///
+  /// ```no_run
+  /// use sass_embedded::{AsyncEmbedded, Options};
+  ///
+  /// # async fn run() {
+  /// let sass = AsyncEmbedded::new("path/to/sass_embedded").unwrap();
+  /// let res = sass.compile("../styles/a.scss", Options::default()).await.unwrap();
+  /// # }
+  /// ```
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/modules#compileAsync)
+  pub async fn compile(
+    &self,
+    path: impl AsRef<Path>,
+    options: Options,
+  ) -> Result<CompileResult> {
+    let interrupt = Interrupt::new(&options);
+    let output = options.source_map_output.clone();
+    let (request, host) = compile_request(path.as_ref(), options);
+    let conn = self.channel.connect(host).await?;
+    let response = conn.compile_request(request, &interrupt).await?;
+    output.apply(compile_result(response, conn.host())?)
+  }
+
+  /// Compiles a stylesheet whose contents is source to CSS. If it succeeds it returns
+  /// a [CompileResult], and if it fails it throws an [Exception](crate::Exception).
+  ///
+  /// ```no_run
+  /// use sass_embedded::{AsyncEmbedded, StringOptions};
+  ///
+  /// # async fn run() {
+  /// let sass = AsyncEmbedded::new("path/to/sass_embedded").unwrap();
+  /// let res = sass
+  ///   .compile_string("a {b: c}", StringOptions::default())
+  ///   .await
+  ///   .unwrap();
+  /// # }
+  /// ```
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/modules#compileStringAsync)
+  pub async fn compile_string(
+    &self,
+    source: impl Into<String>,
+    options: StringOptions,
+  ) -> Result<CompileResult> {
+    let interrupt = Interrupt::new(&options.common);
+    let output = options.common.source_map_output.clone();
+    let (request, host) = compile_string_request(source.into(), options);
+    let conn = self.channel.connect(host).await?;
+    let response = conn.compile_request(request, &interrupt).await?;
+    output.apply(compile_result(response, conn.host())?)
+  }
+
+  /// Gets the version of the sass-embedded compiler.
+  pub async fn info(&self) -> Result<String> {
+    let conn = self.channel.connect(Host::default()).await?;
+    let response = conn.version_request().await?;
+    Ok(format!(
+      "sass-embedded\t#{}",
+      response.implementation_version
+    ))
+  }
+
+  /// Gets the versions of the compiler and of the embedded protocol it
+  /// speaks, like [Embedded::version].
+  ///
+  /// [Embedded::version]: crate::Embedded::version
+  pub async fn version(&self) -> Result<VersionInfo> {
+    let conn = self.channel.connect(Host::default()).await?;
+    VersionInfo::try_from(conn.version_request().await?)
+  }
+}
@@ -0,0 +1,504 @@
+use std::{
+  fmt,
+  future::Future,
+  io,
+  path::{Path, PathBuf},
+  process::ExitStatus,
+  sync::Arc,
+  thread,
+};
+
+use tokio::{
+  io::{
+    AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+    BufReader,
+  },
+  net::{
+    tcp::{OwnedReadHalf, OwnedWriteHalf},
+    TcpStream, ToSocketAddrs,
+  },
+  process::{Child, ChildStderr, ChildStdin, ChildStdout, Command},
+  sync::{mpsc, watch, Mutex},
+  task::JoinHandle,
+};
+
+use crate::{
+  stderr::{SharedStderrOptions, StderrLog},
+  transport::check_len,
+  varint, CompilerCommand, Transport,
+};
+
+/// A connection to a compiler that carries the messages of the embedded
+/// protocol without blocking the tokio runtime, like a [Transport] does for
+/// the synchronous compiler.
+///
+/// [AsyncEmbedded::with_async_transport] runs compilations over any async
+/// transport, e.g. to share one long-lived compiler daemon between
+/// short-lived build processes.
+///
+/// [AsyncEmbedded::with_async_transport]: crate::AsyncEmbedded::with_async_transport
+#[async_trait::async_trait]
+pub trait AsyncTransport: fmt::Debug + Send + Sync {
+  /// Waits for the next message from the compiler, and returns its encoded
+  /// `OutboundMessage` without the length prefix.
+  ///
+  /// Returns an [io::ErrorKind::UnexpectedEof] error once the compiler is
+  /// gone or [AsyncTransport::close] was called.
+  async fn read(&self) -> io::Result<Vec<u8>>;
+
+  /// Sends an encoded `InboundMessage` to the compiler, prefixed with its
+  /// length.
+  async fn write(&self, message: &[u8]) -> io::Result<()>;
+
+  /// Closes the connection, which makes [AsyncTransport::read] return an
+  /// error.
+  fn close(&self);
+
+  /// Whether the compiler is known to be gone, e.g. because its process
+  /// exited. Defaults to `false`, since a failed [AsyncTransport::read] is
+  /// noticed anyway.
+  fn has_exited(&self) -> bool {
+    false
+  }
+
+  /// The exit status of the compiler process, if it has exited. Defaults to
+  /// `None`.
+  fn exit_status(&self) -> Option<ExitStatus> {
+    None
+  }
+
+  /// The last lines of diagnostic output of the compiler, e.g. of the
+  /// stderr of its process. Defaults to none.
+  fn stderr(&self) -> Vec<String> {
+    Vec::new()
+  }
+
+  /// The last lines of diagnostic output of the compiler after the
+  /// transport failed, which may wait briefly for the rest of it. Defaults
+  /// to [AsyncTransport::stderr].
+  async fn final_stderr(&self) -> Vec<String> {
+    self.stderr()
+  }
+}
+
+/// Reads and writes length-prefixed messages over a pair of async streams,
+/// until it's closed.
+#[derive(Debug)]
+struct Framed<R, W> {
+  reader: Mutex<R>,
+  writer: Mutex<W>,
+  closed: watch::Sender<bool>,
+}
+
+impl<R, W> Framed<R, W>
+where
+  R: AsyncRead + Unpin + Send,
+  W: AsyncWrite + Unpin + Send,
+{
+  fn new(reader: R, writer: W) -> Self {
+    Self {
+      reader: Mutex::new(reader),
+      writer: Mutex::new(writer),
+      closed: watch::channel(false).0,
+    }
+  }
+
+  async fn read(&self) -> io::Result<Vec<u8>> {
+    self
+      .until_closed(io::ErrorKind::UnexpectedEof, async {
+        let mut reader = self.reader.lock().await;
+        let len = check_len(varint::read_async(&mut *reader).await?)?;
+        let mut buf = vec![0; len];
+        reader.read_exact(&mut buf).await?;
+        Ok(buf)
+      })
+      .await
+  }
+
+  async fn write(&self, message: &[u8]) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(message.len() + 5);
+    varint::write(&mut packet, message.len())?;
+    packet.extend_from_slice(message);
+    self
+      .until_closed(io::ErrorKind::BrokenPipe, async {
+        let mut writer = self.writer.lock().await;
+        writer.write_all(&packet).await?;
+        writer.flush().await
+      })
+      .await
+  }
+
+  /// Runs `io`, unless the streams are closed before it's done, which fails
+  /// with `kind` instead.
+  async fn until_closed<T>(
+    &self,
+    kind: io::ErrorKind,
+    io: impl Future<Output = io::Result<T>>,
+  ) -> io::Result<T> {
+    let mut closed = self.closed.subscribe();
+    tokio::select! {
+      result = io => result,
+      _ = closed.wait_for(|closed| *closed) => Err(kind.into()),
+    }
+  }
+
+  fn close(&self) {
+    self.closed.send_replace(true);
+  }
+}
+
+/// A compiler process started with a [CompilerCommand] by
+/// [tokio](https://tokio.rs), which speaks the protocol over its stdin and
+/// stdout, like a [ChildTransport](crate::ChildTransport).
+#[derive(Debug)]
+pub struct AsyncChildTransport {
+  child: parking_lot::Mutex<Child>,
+  framed: Framed<ChildStdout, ChildStdin>,
+  stderr: Arc<StderrLog>,
+  stderr_reader: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AsyncChildTransport {
+  /// Starts the compiler process, which must be done within a tokio runtime.
+  pub fn spawn(command: &CompilerCommand) -> io::Result<Self> {
+    Self::spawn_with_stderr(command, SharedStderrOptions::default())
+  }
+
+  pub(crate) fn spawn_with_stderr(
+    command: &CompilerCommand,
+    stderr: SharedStderrOptions,
+  ) -> io::Result<Self> {
+    let mut child = Command::from(command.command()).spawn()?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let stderr = Arc::new(StderrLog::new(stderr));
+    let stderr_reader = match child.stderr.take() {
+      Some(pipe) => Some(Self::spawn_stderr_reader(pipe, Arc::clone(&stderr))),
+      // Not captured, see [CompilerStderr](crate::CompilerStderr).
+      None => {
+        stderr.close();
+        None
+      }
+    };
+    Ok(Self {
+      child: parking_lot::Mutex::new(child),
+      framed: Framed::new(stdout, stdin),
+      stderr,
+      stderr_reader: Mutex::new(stderr_reader),
+    })
+  }
+
+  /// Reads stderr in the background, so the compiler never blocks on writing
+  /// to it.
+  fn spawn_stderr_reader(
+    stderr: ChildStderr,
+    log: Arc<StderrLog>,
+  ) -> JoinHandle<()> {
+    tokio::spawn(async move {
+      let mut reader = BufReader::new(stderr);
+      let mut line = Vec::new();
+      while matches!(reader.read_until(b'\n', &mut line).await, Ok(n) if n > 0)
+      {
+        log.push(&line);
+        line.clear();
+      }
+      log.close();
+    })
+  }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransport for AsyncChildTransport {
+  async fn read(&self) -> io::Result<Vec<u8>> {
+    self.framed.read().await
+  }
+
+  async fn write(&self, message: &[u8]) -> io::Result<()> {
+    self.framed.write(message).await
+  }
+
+  /// Kills the compiler process, which closes its stdout.
+  fn close(&self) {
+    let _ = self.child.lock().start_kill();
+  }
+
+  fn has_exited(&self) -> bool {
+    !matches!(self.child.lock().try_wait(), Ok(None))
+  }
+
+  fn exit_status(&self) -> Option<ExitStatus> {
+    self.child.lock().try_wait().ok().flatten()
+  }
+
+  fn stderr(&self) -> Vec<String> {
+    self.stderr.tail()
+  }
+
+  async fn final_stderr(&self) -> Vec<String> {
+    if let Some(reader) = self.stderr_reader.lock().await.as_mut() {
+      let _ = tokio::time::timeout(StderrLog::CLOSE_TIMEOUT, reader).await;
+    }
+    self.stderr.tail()
+  }
+}
+
+/// A connection to a compiler daemon over TCP, like a
+/// [TcpTransport](crate::TcpTransport).
+#[derive(Debug)]
+pub struct AsyncTcpTransport {
+  framed: Framed<BufReader<OwnedReadHalf>, OwnedWriteHalf>,
+}
+
+impl AsyncTcpTransport {
+  /// Connects to the compiler daemon listening on `addr`.
+  pub async fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+    Self::new(TcpStream::connect(addr).await?)
+  }
+
+  /// Uses a connected stream.
+  pub fn new(stream: TcpStream) -> io::Result<Self> {
+    stream.set_nodelay(true)?;
+    let (reader, writer) = stream.into_split();
+    Ok(Self {
+      framed: Framed::new(BufReader::new(reader), writer),
+    })
+  }
+
+  /// Uses a stream that was connected synchronously, e.g. in the `connect`
+  /// of [AsyncEmbedded::with_async_transport]. It must be called within a
+  /// tokio runtime.
+  ///
+  /// [AsyncEmbedded::with_async_transport]: crate::AsyncEmbedded::with_async_transport
+  pub fn from_std(stream: std::net::TcpStream) -> io::Result<Self> {
+    stream.set_nonblocking(true)?;
+    Self::new(TcpStream::from_std(stream)?)
+  }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransport for AsyncTcpTransport {
+  async fn read(&self) -> io::Result<Vec<u8>> {
+    self.framed.read().await
+  }
+
+  async fn write(&self, message: &[u8]) -> io::Result<()> {
+    self.framed.write(message).await
+  }
+
+  fn close(&self) {
+    self.framed.close();
+  }
+}
+
+/// A connection to a compiler daemon over a Unix domain socket, like a
+/// [UnixTransport](crate::UnixTransport).
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct AsyncUnixTransport {
+  framed: Framed<
+    BufReader<tokio::net::unix::OwnedReadHalf>,
+    tokio::net::unix::OwnedWriteHalf,
+  >,
+}
+
+#[cfg(unix)]
+impl AsyncUnixTransport {
+  /// Connects to the compiler daemon listening on the socket at `path`.
+  pub async fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+    Ok(Self::new(tokio::net::UnixStream::connect(path).await?))
+  }
+
+  /// Uses a connected stream.
+  pub fn new(stream: tokio::net::UnixStream) -> Self {
+    let (reader, writer) = stream.into_split();
+    Self {
+      framed: Framed::new(BufReader::new(reader), writer),
+    }
+  }
+
+  /// Uses a stream that was connected synchronously, e.g. in the `connect`
+  /// of [AsyncEmbedded::with_async_transport]. It must be called within a
+  /// tokio runtime.
+  ///
+  /// [AsyncEmbedded::with_async_transport]: crate::AsyncEmbedded::with_async_transport
+  pub fn from_std(stream: std::os::unix::net::UnixStream) -> io::Result<Self> {
+    stream.set_nonblocking(true)?;
+    Ok(Self::new(tokio::net::UnixStream::from_std(stream)?))
+  }
+}
+
+#[cfg(unix)]
+#[async_trait::async_trait]
+impl AsyncTransport for AsyncUnixTransport {
+  async fn read(&self) -> io::Result<Vec<u8>> {
+    self.framed.read().await
+  }
+
+  async fn write(&self, message: &[u8]) -> io::Result<()> {
+    self.framed.write(message).await
+  }
+
+  fn close(&self) {
+    self.framed.close();
+  }
+}
+
+/// Runs a blocking [Transport], e.g. a [MemoryTransport], without blocking
+/// the tokio runtime's worker threads: a thread reads from it, and writes run
+/// on the blocking pool.
+///
+/// [MemoryTransport]: crate::MemoryTransport
+#[derive(Debug)]
+struct BlockingTransport {
+  transport: Arc<dyn Transport>,
+  /// The messages read by the thread that reads from the transport, ending
+  /// with the error that stopped it.
+  messages: Mutex<mpsc::UnboundedReceiver<io::Result<Vec<u8>>>>,
+}
+
+impl BlockingTransport {
+  fn new(transport: Box<dyn Transport>) -> Self {
+    let transport: Arc<dyn Transport> = Arc::from(transport);
+    let (tx, rx) = mpsc::unbounded_channel();
+    let reader = Arc::clone(&transport);
+    thread::spawn(move || loop {
+      let message = reader.read();
+      let failed = message.is_err();
+      if tx.send(message).is_err() || failed {
+        break;
+      }
+    });
+    Self {
+      transport,
+      messages: Mutex::new(rx),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransport for BlockingTransport {
+  async fn read(&self) -> io::Result<Vec<u8>> {
+    self
+      .messages
+      .lock()
+      .await
+      .recv()
+      .await
+      .unwrap_or_else(|| Err(io::ErrorKind::UnexpectedEof.into()))
+  }
+
+  async fn write(&self, message: &[u8]) -> io::Result<()> {
+    let transport = Arc::clone(&self.transport);
+    let message = message.to_vec();
+    tokio::task::spawn_blocking(move || transport.write(&message))
+      .await
+      .map_err(io::Error::other)?
+  }
+
+  fn close(&self) {
+    self.transport.close();
+  }
+
+  fn has_exited(&self) -> bool {
+    self.transport.has_exited()
+  }
+
+  fn exit_status(&self) -> Option<ExitStatus> {
+    self.transport.exit_status()
+  }
+
+  fn stderr(&self) -> Vec<String> {
+    self.transport.stderr()
+  }
+
+  async fn final_stderr(&self) -> Vec<String> {
+    let transport = Arc::clone(&self.transport);
+    tokio::task::spawn_blocking(move || transport.final_stderr())
+      .await
+      .unwrap_or_default()
+  }
+}
+
+/// Opens a new transport, every time the compiler is (re)started.
+type Connect = dyn Fn(&SharedStderrOptions) -> io::Result<Box<dyn AsyncTransport>>
+  + Send
+  + Sync;
+
+/// How an async channel starts its compiler.
+#[derive(Clone)]
+pub(crate) struct Connector {
+  connect: Arc<Connect>,
+  /// Whether the channel checks the protocol version of every compiler it
+  /// starts.
+  handshake: bool,
+  /// The absolute working directory of the compiler, if it's not ours.
+  current_dir: Option<PathBuf>,
+}
+
+impl fmt::Debug for Connector {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Connector")
+  }
+}
+
+impl Connector {
+  pub fn command(command: CompilerCommand) -> Self {
+    Self {
+      handshake: command.get_handshake(),
+      current_dir: command
+        .get_current_dir()
+        .and_then(|dir| std::path::absolute(dir).ok()),
+      connect: Arc::new(move |stderr| {
+        let transport =
+          AsyncChildTransport::spawn_with_stderr(&command, Arc::clone(stderr))?;
+        Ok(Box::new(transport) as Box<dyn AsyncTransport>)
+      }),
+    }
+  }
+
+  pub fn custom<T: AsyncTransport + 'static>(
+    connect: impl Fn() -> io::Result<T> + Send + Sync + 'static,
+  ) -> Self {
+    Self {
+      handshake: false,
+      current_dir: None,
+      connect: Arc::new(move |_| {
+        Ok(Box::new(connect()?) as Box<dyn AsyncTransport>)
+      }),
+    }
+  }
+
+  /// Runs the blocking transports that `connect` opens off the runtime's
+  /// worker threads.
+  pub fn blocking<T: Transport + 'static>(
+    connect: impl Fn() -> io::Result<T> + Send + Sync + 'static,
+  ) -> Self {
+    Self {
+      handshake: false,
+      current_dir: None,
+      connect: Arc::new(move |_| {
+        let transport = BlockingTransport::new(Box::new(connect()?));
+        Ok(Box::new(transport) as Box<dyn AsyncTransport>)
+      }),
+    }
+  }
+
+  pub fn connect(
+    &self,
+    stderr: &SharedStderrOptions,
+  ) -> io::Result<Box<dyn AsyncTransport>> {
+    (self.connect)(stderr)
+  }
+
+  /// Whether the protocol version of every compiler is checked once it's
+  /// started, see [CompilerCommand::handshake].
+  pub fn handshake(&self) -> bool {
+    self.handshake
+  }
+
+  /// The working directory of the compiler if it's not ours, see
+  /// [CompilerCommand::current_dir].
+  pub fn current_dir(&self) -> Option<&Path> {
+    self.current_dir.as_deref()
+  }
+}
@@ -0,0 +1,251 @@
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap},
+  fs,
+  hash::{Hash, Hasher},
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use parking_lot::Mutex;
+
+use crate::{protocol, CompileResult, Embedded, Options, Result, Url};
+
+/// How a [CompileCache] tells whether a `file:` dependency has changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Freshness {
+  /// Compares the modification time and size of the file, which only needs
+  /// its metadata.
+  #[default]
+  Mtime,
+  /// Compares a hash of the contents of the file, which also notices
+  /// changes that keep the modification time, e.g. of `git checkout`.
+  ContentHash,
+}
+
+/// Caches [CompileResult]s of [Embedded::compile], and reuses them as long
+/// as none of their [CompileResult::loaded_urls] have changed.
+///
+/// Results are keyed on the entrypoint path and a fingerprint of the
+/// [Options], which covers every option except the importers, functions and
+/// logger themselves. Use a separate cache for compilations whose importers
+/// or functions behave differently. Cached results don't call the logger
+/// again, and failed compilations aren't cached, nor are results whose
+/// dependencies changed while they were compiled.
+///
+/// `file:` dependencies are checked on every lookup according to the
+/// [Freshness]. Any other URL is provided by an importer, whose stylesheets
+/// the cache can't see, so it's considered unchanged until
+/// [CompileCache::invalidate] is called with it.
+///
+/// ```no_run
+/// use sass_embedded::{CompileCache, Options, Sass};
+///
+/// let sass = Sass::new("path/to/sass_embedded").unwrap();
+/// let cache = CompileCache::new();
+/// // Compiles the stylesheet.
+/// let res = cache.compile(&sass, "style.scss", Options::default()).unwrap();
+/// // Reuses the result, until style.scss or one of its dependencies changes.
+/// let res = cache.compile(&sass, "style.scss", Options::default()).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct CompileCache {
+  freshness: Freshness,
+  entries: Mutex<HashMap<(PathBuf, u64), Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+  result: CompileResult,
+  /// The loaded URLs, with the stamp of each `file:` URL when it was
+  /// compiled.
+  dependencies: Vec<(Url, Option<Stamp>)>,
+}
+
+/// The state of a file, which changes when the file does.
+#[derive(Debug, PartialEq, Eq)]
+enum Stamp {
+  Modified(SystemTime, u64),
+  Hash(u64),
+  Missing,
+}
+
+impl CompileCache {
+  /// Creates an empty cache that checks dependencies with
+  /// [Freshness::Mtime].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates an empty cache that checks dependencies with `freshness`.
+  pub fn with_freshness(freshness: Freshness) -> Self {
+    Self {
+      freshness,
+      entries: Mutex::default(),
+    }
+  }
+
+  /// Returns the cached result of compiling `path` with `options` if it's
+  /// still fresh, or compiles it with `sass` and caches the result.
+  pub fn compile(
+    &self,
+    sass: &Embedded,
+    path: impl AsRef<Path>,
+    options: Options,
+  ) -> Result<CompileResult> {
+    self.get_or_compile(path, options, |path, options| {
+      sass.compile(path, options)
+    })
+  }
+
+  /// Like [CompileCache::compile], but compiles with `compile`, e.g. to use
+  /// an [EmbeddedPool](crate::EmbeddedPool).
+  pub fn get_or_compile(
+    &self,
+    path: impl AsRef<Path>,
+    options: Options,
+    compile: impl FnOnce(&Path, Options) -> Result<CompileResult>,
+  ) -> Result<CompileResult> {
+    // Keyed on the absolute path the compiler is sent, so the same file is
+    // cached once however it's referred to.
+    let path = path.as_ref();
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut hasher = DefaultHasher::new();
+    fingerprint(&options, &mut hasher);
+    let key = (path.clone(), hasher.finish());
+    // Stamps the files this compilation is known to load before compiling,
+    // so an edit made during the compilation isn't cached as up to date.
+    let mut before: HashMap<Url, Option<Stamp>> = HashMap::new();
+    if let Some(entry) = self.entries.lock().get(&key) {
+      if entry.is_fresh(self.freshness) {
+        return Ok(entry.result.clone());
+      }
+      before.extend(
+        entry
+          .dependencies
+          .iter()
+          .map(|(url, _)| (url.clone(), stamp(url, self.freshness))),
+      );
+    }
+    if let Ok(url) = Url::from_file_path(&path) {
+      let stamp = stamp(&url, self.freshness);
+      before.insert(url, stamp);
+    }
+    let started = SystemTime::now();
+    // Compiles without holding the lock, so other stylesheets can be looked
+    // up in the meantime.
+    let result = compile(&path, options)?;
+    let dependencies: Vec<_> = result
+      .loaded_urls
+      .iter()
+      .map(|url| (url.clone(), stamp(url, self.freshness)))
+      .collect();
+    let changed = dependencies.iter().any(|(url, stamp)| {
+      before.get(url).is_some_and(|before| before != stamp)
+        || modified_since(url, started)
+    });
+    if !changed {
+      self.entries.lock().insert(
+        key,
+        Entry {
+          result: result.clone(),
+          dependencies,
+        },
+      );
+    }
+    Ok(result)
+  }
+
+  /// Drops every result that loaded `url`, e.g. once an importer knows that
+  /// the stylesheet it provides for `url` has changed.
+  pub fn invalidate(&self, url: &Url) {
+    self
+      .entries
+      .lock()
+      .retain(|_, entry| entry.dependencies.iter().all(|(dep, _)| dep != url));
+  }
+
+  /// Drops every result whose loaded URLs match `predicate`, e.g. all the
+  /// URLs of one importer's scheme.
+  pub fn invalidate_if(&self, mut predicate: impl FnMut(&Url) -> bool) {
+    self.entries.lock().retain(|_, entry| {
+      !entry.dependencies.iter().any(|(dep, _)| predicate(dep))
+    });
+  }
+
+  /// Drops every result.
+  pub fn clear(&self) {
+    self.entries.lock().clear();
+  }
+
+  /// The number of cached results, including stale ones that haven't been
+  /// looked up since their dependencies changed.
+  pub fn len(&self) -> usize {
+    self.entries.lock().len()
+  }
+
+  /// Whether no results are cached.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl Entry {
+  fn is_fresh(&self, freshness: Freshness) -> bool {
+    self
+      .dependencies
+      .iter()
+      .all(|(url, recorded)| match recorded {
+        Some(recorded) => stamp(url, freshness).as_ref() == Some(recorded),
+        None => true,
+      })
+  }
+}
+
+/// The stamp of a `file:` URL, or `None` for the URL of an importer.
+fn stamp(url: &Url, freshness: Freshness) -> Option<Stamp> {
+  if url.scheme() != "file" {
+    return None;
+  }
+  let path = match url.to_file_path() {
+    Ok(path) => path,
+    Err(()) => return Some(Stamp::Missing),
+  };
+  let stamp = match freshness {
+    Freshness::Mtime => fs::metadata(path)
+      .and_then(|meta| Ok(Stamp::Modified(meta.modified()?, meta.len()))),
+    Freshness::ContentHash => fs::read(path).map(|contents| {
+      let mut hasher = DefaultHasher::new();
+      contents.hash(&mut hasher);
+      Stamp::Hash(hasher.finish())
+    }),
+  };
+  Some(stamp.unwrap_or(Stamp::Missing))
+}
+
+/// Whether the file of a `file:` URL was modified at or after `time`.
+pub(crate) fn modified_since(url: &Url, time: SystemTime) -> bool {
+  url
+    .to_file_path()
+    .ok()
+    .and_then(|path| fs::metadata(path).ok())
+    .and_then(|meta| meta.modified().ok())
+    .is_some_and(|modified| modified >= time)
+}
+
+/// Feeds the options that affect the compiled CSS to `hasher`.
+pub(crate) fn fingerprint(options: &Options, hasher: &mut impl Hasher) {
+  options.alert_ascii.hash(hasher);
+  options.alert_color.hash(hasher);
+  options.load_paths.hash(hasher);
+  options.quiet_deps.hash(hasher);
+  options.source_map.hash(hasher);
+  options.source_map_include_sources.hash(hasher);
+  options.source_map_output.hash(hasher);
+  (protocol::OutputStyle::from(options.style) as i32).hash(hasher);
+  options.verbose.hash(hasher);
+  options.charset.hash(hasher);
+  options.importers.len().hash(hasher);
+  let mut signatures: Vec<_> = options.functions.keys().collect();
+  signatures.sort();
+  signatures.hash(hasher);
+}
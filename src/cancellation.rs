@@ -0,0 +1,119 @@
+use std::{sync::Arc, time::Duration};
+
+use crossbeam_channel::{Receiver, Sender};
+use parking_lot::Mutex;
+
+use crate::{Exception, ExceptionKind, Options};
+
+/// A token for cancelling compilations from another thread, passed with
+/// [Options::cancellation_token].
+///
+/// Clones share the same state, so cancelling one cancels every compilation
+/// that was given any of them.
+///
+/// ```no_run
+/// use std::thread;
+///
+/// use sass_embedded::{CancellationToken, OptionsBuilder, Sass};
+///
+/// let sass = Sass::new("path/to/sass_embedded").unwrap();
+/// let token = CancellationToken::new();
+/// let canceller = token.clone();
+/// thread::spawn(move || canceller.cancel());
+/// let res = sass.compile(
+///   "../styles/a.scss",
+///   OptionsBuilder::new().cancellation_token(token).build(),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+  inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+  /// Dropped on cancellation, which disconnects every clone of `receiver`.
+  sender: Mutex<Option<Sender<()>>>,
+  receiver: Receiver<()>,
+  #[cfg(feature = "async")]
+  notify: tokio::sync::Notify,
+}
+
+impl Default for CancellationToken {
+  fn default() -> Self {
+    let (sender, receiver) = crossbeam_channel::bounded(0);
+    Self {
+      inner: Arc::new(Inner {
+        sender: Mutex::new(Some(sender)),
+        receiver,
+        #[cfg(feature = "async")]
+        notify: tokio::sync::Notify::new(),
+      }),
+    }
+  }
+}
+
+impl CancellationToken {
+  /// Creates a new [CancellationToken].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Cancels the compilations that were given this token, including the
+  /// ones that start later.
+  pub fn cancel(&self) {
+    self.inner.sender.lock().take();
+    #[cfg(feature = "async")]
+    self.inner.notify.notify_waiters();
+  }
+
+  /// Whether [CancellationToken::cancel] was called.
+  pub fn is_cancelled(&self) -> bool {
+    self.inner.sender.lock().is_none()
+  }
+
+  /// A receiver that's disconnected once the token is cancelled.
+  pub(crate) fn receiver(&self) -> &Receiver<()> {
+    &self.inner.receiver
+  }
+
+  /// Waits until the token is cancelled.
+  #[cfg(feature = "async")]
+  pub(crate) async fn cancelled(&self) {
+    let notified = self.inner.notify.notified();
+    tokio::pin!(notified);
+    notified.as_mut().enable();
+    if !self.is_cancelled() {
+      notified.await;
+    }
+  }
+}
+
+/// The conditions under which a compilation stops waiting for the compiler.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Interrupt {
+  pub timeout: Option<Duration>,
+  pub token: Option<CancellationToken>,
+}
+
+impl Interrupt {
+  pub fn new(options: &Options) -> Self {
+    Self {
+      timeout: options.timeout,
+      token: options.cancellation_token.clone(),
+    }
+  }
+
+  pub fn timed_out(&self) -> Exception {
+    Exception::new(format!(
+      "Compilation timed out after {:?}.",
+      self.timeout.unwrap_or_default()
+    ))
+    .with_kind(ExceptionKind::Timeout)
+  }
+
+  pub fn cancelled(&self) -> Exception {
+    Exception::new("Compilation was cancelled.")
+      .with_kind(ExceptionKind::Cancelled)
+  }
+}
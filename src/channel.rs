@@ -1,39 +1,192 @@
-use std::{
-  ffi::{OsStr, OsString},
-  sync::Arc,
-};
+use std::{io, sync::Arc, thread};
+
+use parking_lot::{Condvar, Mutex, MutexGuard};
 
 use crate::{
   compiler::Compiler,
   connection::{ConnectedGuard, Connection},
   dispatcher::Dispatcher,
   host::Host,
-  Result,
+  restart::Supervisor,
+  stderr::{SharedStderrOptions, StderrOptions},
+  transport::Connector,
+  Exception, ExceptionKind, Result,
 };
 
 #[derive(Debug)]
 pub struct Channel {
-  path: OsString,
+  connector: Connector,
+  state: Mutex<State>,
+  /// Notified once a restart ends.
+  restart_done: Condvar,
+  supervisor: Mutex<Supervisor>,
+  stderr: SharedStderrOptions,
+}
+
+#[derive(Debug)]
+struct State {
+  /// The dispatcher of the running compiler, which is replaced with a new
+  /// one once the compiler crashes or reports a protocol error.
   dispatcher: Arc<Dispatcher>,
+  /// Whether a thread is restarting the compiler, which sleeps for the
+  /// backoff without holding the lock.
+  restarting: bool,
+  /// Why the last restart gave up, if it did, for the threads that waited
+  /// for it.
+  gave_up: Option<String>,
 }
 
 impl Channel {
-  pub fn new(path: impl AsRef<OsStr>) -> Result<Self> {
-    let path = path.as_ref().to_os_string();
-    let compiler = Compiler::new(&path)?;
-    let dispatcher = Dispatcher::new(compiler);
-    Ok(Self { path, dispatcher })
-  }
-
-  pub fn connect(&mut self, host: Host) -> Result<ConnectedGuard> {
-    let conn = Connection::new(Arc::clone(&self.dispatcher));
-    match self.dispatcher.subscribe(conn, host) {
-      Err((conn, host)) => {
-        let compiler = Compiler::new(&self.path)?;
-        self.dispatcher = Dispatcher::new(compiler);
-        Ok(self.dispatcher.subscribe(conn, host).unwrap())
+  pub fn new(connector: Connector) -> Result<Self> {
+    let stderr = SharedStderrOptions::default();
+    let state = Mutex::new(State {
+      dispatcher: Self::start(&connector, &stderr)?,
+      restarting: false,
+      gave_up: None,
+    });
+    Ok(Self {
+      connector,
+      state,
+      restart_done: Condvar::new(),
+      supervisor: Mutex::default(),
+      stderr,
+    })
+  }
+
+  /// Starts a compiler, and checks its protocol version if the connector
+  /// asks for a handshake.
+  fn start(
+    connector: &Connector,
+    stderr: &SharedStderrOptions,
+  ) -> Result<Arc<Dispatcher>> {
+    let dispatcher = Dispatcher::new(Compiler::new(connector, stderr)?);
+    if connector.handshake() {
+      let checked = Self::handshake(&dispatcher);
+      if checked.is_err() {
+        dispatcher.close();
       }
-      Ok(conn) => Ok(conn),
+      checked?;
     }
+    Ok(dispatcher)
+  }
+
+  fn handshake(dispatcher: &Arc<Dispatcher>) -> Result<()> {
+    let conn = Connection::new(Arc::clone(dispatcher));
+    let conn = dispatcher.subscribe(conn, Host::default()).map_err(|_| {
+      let eof = io::ErrorKind::UnexpectedEof.into();
+      Box::new(Exception::io("The compiler exited unexpectedly", eof))
+    })?;
+    conn.version()?.check_compatible()
+  }
+
+  pub fn supervisor(&mut self) -> &mut Supervisor {
+    self.supervisor.get_mut()
+  }
+
+  pub fn set_stderr_options(&self, options: StderrOptions) {
+    *self.stderr.lock() = options;
+  }
+
+  fn dispatcher(&self) -> Arc<Dispatcher> {
+    Arc::clone(&self.state.lock().dispatcher)
+  }
+
+  /// The last lines the running compiler wrote to stderr.
+  pub fn stderr(&self) -> Vec<String> {
+    self.dispatcher().stderr()
+  }
+
+  /// The number of compilations in flight on this channel.
+  pub fn load(&self) -> usize {
+    self.dispatcher().load()
+  }
+
+  /// Whether the compiler of this channel can no longer be used.
+  pub fn is_closed(&self) -> bool {
+    self.dispatcher().is_closed()
+  }
+
+  /// Kills the compiler of this channel.
+  pub fn close(&self) {
+    self.dispatcher().close();
+  }
+
+  /// Kills the compiler of this channel once its compilations are done.
+  pub fn retire(&self) {
+    self.dispatcher().retire();
+  }
+
+  pub fn connect(&self, mut host: Host) -> Result<ConnectedGuard> {
+    host.set_compiler_dir(self.connector.current_dir());
+    let mut state = self.state.lock();
+    loop {
+      if state.restarting {
+        // Fails like the restart that's waited for, rather than trying again.
+        while state.restarting {
+          self.restart_done.wait(&mut state);
+        }
+        if let Some(message) = &state.gave_up {
+          return Err(Box::new(
+            Exception::new(message.clone()).with_kind(ExceptionKind::Spawn),
+          ));
+        }
+      }
+      if !state.dispatcher.is_closed() {
+        let conn = Connection::new(Arc::clone(&state.dispatcher));
+        match state.dispatcher.subscribe(conn, host) {
+          Ok(conn) => return Ok(conn),
+          // Closed in the meantime.
+          Err(unsubscribed) => host = unsubscribed.1,
+        }
+      }
+      self.restart(&mut state)?;
+    }
+  }
+
+  /// Replaces the closed dispatcher with one for a new compiler, according
+  /// to the restart policy.
+  fn restart(&self, state: &mut MutexGuard<State>) -> Result<()> {
+    if state.dispatcher.is_retired() {
+      // Replaced after an interrupted compilation, rather than crashed.
+      state.dispatcher = Self::start(&self.connector, &self.stderr)?;
+      return Ok(());
+    }
+    let mut supervisor = self.supervisor.lock();
+    let dispatcher = Arc::clone(&state.dispatcher);
+    // Compilations that are still running may finish after a protocol error.
+    if dispatcher.load() == 0 {
+      dispatcher.close();
+    }
+    supervisor.crashed(dispatcher.exit_status(), dispatcher.has_responded());
+    state.restarting = true;
+    let result = loop {
+      let (attempt, backoff) = match supervisor.next_attempt() {
+        Ok(next) => next,
+        Err(e) => break Err(e),
+      };
+      if !backoff.is_zero() {
+        MutexGuard::unlocked(state, || thread::sleep(backoff));
+      }
+      match Self::start(&self.connector, &self.stderr) {
+        Ok(dispatcher) => {
+          state.dispatcher = dispatcher;
+          supervisor.restarted(attempt);
+          break Ok(());
+        }
+        // Starting the same compiler again wouldn't make it compatible.
+        Err(e) if e.kind() == ExceptionKind::Version => break Err(e),
+        Err(e) => supervisor.restart_failed(attempt, &e),
+      }
+    };
+    state.restarting = false;
+    state.gave_up = result.as_ref().err().map(|e| e.message().to_string());
+    self.restart_done.notify_all();
+    result
+  }
+}
+
+impl Drop for Channel {
+  fn drop(&mut self) {
+    self.close();
   }
 }
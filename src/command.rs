@@ -0,0 +1,193 @@
+use std::{
+  ffi::{OsStr, OsString},
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+};
+
+/// What happens to the output the compiler process writes to stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompilerStderr {
+  /// Reads it into the [StderrSink](crate::StderrSink), and attaches the
+  /// last lines to [Exception](crate::Exception)s.
+  #[default]
+  Capture,
+  /// Writes it to this process's stderr.
+  Inherit,
+  /// Discards it.
+  Null,
+}
+
+/// The command that starts the compiler process, accepted by
+/// [Embedded::with_command].
+///
+/// Its stdin and stdout are always piped, since they carry the embedded
+/// protocol.
+///
+/// ```no_run
+/// use sass_embedded::{CompilerCommand, Sass};
+///
+/// let sass = Sass::with_command(
+///   CompilerCommand::new("path/to/sass")
+///     .arg("--embedded")
+///     .env("DART_VM_OPTIONS", "--old_gen_heap_size=512")
+///     .current_dir("path/to/sandbox"),
+/// )
+/// .unwrap();
+/// ```
+///
+/// [Embedded::with_command]: crate::Embedded::with_command
+#[derive(Debug, Clone)]
+pub struct CompilerCommand {
+  program: OsString,
+  args: Vec<OsString>,
+  envs: Vec<(OsString, Option<OsString>)>,
+  env_clear: bool,
+  current_dir: Option<PathBuf>,
+  stderr: CompilerStderr,
+  handshake: bool,
+}
+
+impl CompilerCommand {
+  /// Creates a command that runs `program` without arguments, with the
+  /// environment and working directory of this process.
+  pub fn new(program: impl AsRef<OsStr>) -> Self {
+    Self {
+      program: program.as_ref().to_os_string(),
+      args: Vec::new(),
+      envs: Vec::new(),
+      env_clear: false,
+      current_dir: None,
+      stderr: CompilerStderr::default(),
+      handshake: false,
+    }
+  }
+
+  /// Adds an argument.
+  pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+    self.args.push(arg.as_ref().to_os_string());
+    self
+  }
+
+  /// Adds arguments.
+  pub fn args(
+    mut self,
+    args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+  ) -> Self {
+    self
+      .args
+      .extend(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+    self
+  }
+
+  /// Sets an environment variable.
+  pub fn env(mut self, key: impl AsRef<OsStr>, val: impl AsRef<OsStr>) -> Self {
+    self.envs.push((
+      key.as_ref().to_os_string(),
+      Some(val.as_ref().to_os_string()),
+    ));
+    self
+  }
+
+  /// Sets environment variables.
+  pub fn envs(
+    mut self,
+    vars: impl IntoIterator<Item = (impl AsRef<OsStr>, impl AsRef<OsStr>)>,
+  ) -> Self {
+    for (key, val) in vars {
+      self = self.env(key, val);
+    }
+    self
+  }
+
+  /// Removes an environment variable that would be inherited.
+  pub fn env_remove(mut self, key: impl AsRef<OsStr>) -> Self {
+    self.envs.push((key.as_ref().to_os_string(), None));
+    self
+  }
+
+  /// Doesn't inherit any environment variables, except the ones set with
+  /// [CompilerCommand::env] after this call.
+  pub fn env_clear(mut self) -> Self {
+    self.env_clear = true;
+    self.envs.clear();
+    self
+  }
+
+  /// Sets the working directory.
+  pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+    self.current_dir = Some(dir.into());
+    self
+  }
+
+  /// Sets what happens to stderr. Defaults to [CompilerStderr::Capture].
+  pub fn stderr(mut self, stderr: CompilerStderr) -> Self {
+    self.stderr = stderr;
+    self
+  }
+
+  /// Checks that the compiler speaks a compatible version of the embedded
+  /// protocol whenever it's started, including when it's restarted, see
+  /// [VersionInfo::check_compatible]. An outdated compiler then fails with an
+  /// [ExceptionKind::Version] exception rather than a protocol error later.
+  ///
+  /// ```no_run
+  /// use sass_embedded::{CompilerCommand, Sass};
+  ///
+  /// let sass =
+  ///   Sass::with_command(CompilerCommand::new("path/to/sass").handshake())
+  ///     .unwrap();
+  /// ```
+  ///
+  /// [VersionInfo::check_compatible]: crate::VersionInfo::check_compatible
+  /// [ExceptionKind::Version]: crate::ExceptionKind::Version
+  pub fn handshake(mut self) -> Self {
+    self.handshake = true;
+    self
+  }
+
+  /// Whether the compiler's protocol version is checked when it's started.
+  pub fn get_handshake(&self) -> bool {
+    self.handshake
+  }
+
+  /// The program that's run.
+  pub fn get_program(&self) -> &OsStr {
+    &self.program
+  }
+
+  /// The arguments that are passed to the program.
+  pub fn get_args(&self) -> impl Iterator<Item = &OsStr> {
+    self.args.iter().map(OsString::as_os_str)
+  }
+
+  /// The working directory, or `None` to inherit it.
+  pub fn get_current_dir(&self) -> Option<&Path> {
+    self.current_dir.as_deref()
+  }
+
+  /// The [std::process::Command] that starts the compiler.
+  pub(crate) fn command(&self) -> Command {
+    let mut cmd = Command::new(&self.program);
+    cmd.args(&self.args);
+    if self.env_clear {
+      cmd.env_clear();
+    }
+    for (key, val) in &self.envs {
+      match val {
+        Some(val) => cmd.env(key, val),
+        None => cmd.env_remove(key),
+      };
+    }
+    if let Some(dir) = &self.current_dir {
+      cmd.current_dir(dir);
+    }
+    cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(
+      match self.stderr {
+        CompilerStderr::Capture => Stdio::piped(),
+        CompilerStderr::Inherit => Stdio::inherit(),
+        CompilerStderr::Null => Stdio::null(),
+      },
+    );
+    cmd
+  }
+}
@@ -1,50 +1,69 @@
-use std::{
-  ffi::OsStr,
-  io::{Read, Write},
-  ops::DerefMut,
-  process::{ChildStdin, ChildStdout, Command, Stdio},
-};
+use std::{io, process::ExitStatus};
 
-use parking_lot::Mutex;
 use prost::Message;
 
 use crate::{
   protocol::{InboundMessage, OutboundMessage},
-  varint, Exception, Result,
+  stderr::SharedStderrOptions,
+  transport::{Connector, Transport},
+  Exception, ExceptionKind, Result,
 };
 
+/// Encodes and decodes the messages sent over a [Transport].
 #[derive(Debug)]
 pub struct Compiler {
-  stdin: Mutex<ChildStdin>,
-  stdout: Mutex<ChildStdout>,
+  transport: Box<dyn Transport>,
 }
 
 impl Compiler {
-  pub fn new(path: impl AsRef<OsStr>) -> Result<Self> {
-    let cmd = Command::new(path)
-      .stdin(Stdio::piped())
-      .stdout(Stdio::piped())
-      .stderr(Stdio::piped())
-      .spawn()
-      .map_err(|e| Exception::new(e.to_string()).set_source(e))?;
-    let stdin = Mutex::new(cmd.stdin.unwrap());
-    let stdout = Mutex::new(cmd.stdout.unwrap());
-
-    Ok(Self { stdin, stdout })
-  }
-
-  pub fn write(&self, message: InboundMessage) {
-    let buf = message.encode_to_vec();
-    let mut stdin = self.stdin.lock();
-    varint::write(stdin.deref_mut(), buf.len());
-    stdin.write_all(&buf[..]).unwrap();
-  }
-
-  pub fn read(&self) -> OutboundMessage {
-    let mut stdout = self.stdout.lock();
-    let len = varint::read(stdout.deref_mut());
-    let mut buf = vec![0; len];
-    stdout.read_exact(&mut buf).unwrap();
-    OutboundMessage::decode(&buf[..]).unwrap()
+  pub fn new(
+    connector: &Connector,
+    stderr: &SharedStderrOptions,
+  ) -> Result<Self> {
+    let transport = connector.connect(stderr).map_err(|e| {
+      Exception::new(e.to_string())
+        .with_kind(ExceptionKind::Spawn)
+        .set_source(e)
+    })?;
+    Ok(Self { transport })
+  }
+
+  /// The last lines the compiler wrote to stderr.
+  pub fn stderr(&self) -> Vec<String> {
+    self.transport.stderr()
+  }
+
+  /// The last lines the compiler wrote to stderr before it failed, which
+  /// waits briefly for the rest of the output of the exiting process.
+  pub fn final_stderr(&self) -> Vec<String> {
+    self.transport.final_stderr()
+  }
+
+  pub fn write(&self, message: InboundMessage) -> io::Result<()> {
+    self.transport.write(&message.encode_to_vec())
+  }
+
+  /// Reads the next message, or returns an error once the transport is
+  /// closed.
+  pub fn read(&self) -> io::Result<OutboundMessage> {
+    let buf = self.transport.read()?;
+    OutboundMessage::decode(&buf[..])
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+
+  /// Whether the compiler has exited.
+  pub fn has_exited(&self) -> bool {
+    self.transport.has_exited()
+  }
+
+  /// The exit status of the compiler process, if it has exited.
+  pub fn exit_status(&self) -> Option<ExitStatus> {
+    self.transport.exit_status()
+  }
+
+  /// Closes the transport, e.g. kills the compiler process, which stops the
+  /// dispatcher reading from it.
+  pub fn kill(&self) {
+    self.transport.close();
   }
 }
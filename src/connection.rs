@@ -3,16 +3,18 @@ use std::{fmt::Debug, ops::Deref, sync::Arc};
 use crossbeam_channel::{Receiver, Sender};
 
 use crate::{
+  cancellation::Interrupt,
   dispatcher::Dispatcher,
   host::Host,
   protocol::{
     inbound_message::{self, CompileRequest, VersionRequest},
     outbound_message::{
-      CanonicalizeRequest, CompileResponse, FileImportRequest, ImportRequest,
-      LogEvent, VersionResponse,
+      CanonicalizeRequest, CompileResponse, FileImportRequest,
+      FunctionCallRequest, ImportRequest, LogEvent, VersionResponse,
     },
     InboundMessage, ProtocolError,
   },
+  Exception, Result, VersionInfo,
 };
 
 enum ProtocolResponse {
@@ -20,13 +22,26 @@ enum ProtocolResponse {
   Version(VersionResponse),
 }
 
-type Response = Result<ProtocolResponse, ProtocolError>;
+type Response = Result<ProtocolResponse>;
+
+/// A message of the compiler for a connection. The dispatcher queues them,
+/// so the host's importers, functions and loggers run on the thread that
+/// waits for the compilation, rather than stalling the other compilations
+/// on the dispatcher's thread.
+enum Event {
+  Response(Response),
+  Log(LogEvent),
+  Canonicalize(CanonicalizeRequest),
+  Import(ImportRequest),
+  FileImport(FileImportRequest),
+  FunctionCall(FunctionCallRequest),
+}
 
 #[derive(Debug)]
 pub struct Connected {
   id: u32,
-  tx: Sender<Response>,
-  rx: Receiver<Response>,
+  tx: Sender<Event>,
+  rx: Receiver<Event>,
   host: Host,
 }
 
@@ -69,7 +84,7 @@ impl Connection<Unconnected> {
   }
 
   pub fn connect(self, id: u32, host: Host) -> ConnectedGuard {
-    let (tx, rx) = crossbeam_channel::bounded(1);
+    let (tx, rx) = crossbeam_channel::unbounded();
     ConnectedGuard(Arc::new(Connection {
       state: Connected { id, tx, rx, host },
       dispatcher: self.dispatcher,
@@ -82,88 +97,172 @@ impl Connection<Connected> {
     self.state.id
   }
 
+  /// The host of the compilation.
+  pub fn host(&self) -> &Host {
+    &self.state.host
+  }
+
   fn disconnect(&self) {
     self.dispatcher.unsubscribe(&self.id());
   }
 
-  fn send_message(&self, inbound_message: InboundMessage) {
-    self.dispatcher.send_message(inbound_message);
+  fn send_message(&self, inbound_message: InboundMessage) -> Result<()> {
+    self.dispatcher.send_message(inbound_message)
+  }
+
+  /// Sends a response to a request of the compiler, failing the pending
+  /// request if it can't be written.
+  fn reply(&self, message: inbound_message::Message) {
+    if let Err(e) = self.send_message(InboundMessage {
+      message: Some(message),
+    }) {
+      self.response(Err(e));
+    }
+  }
+
+  fn event(&self, event: Event) {
+    // The receiver lives as long as this connection, so sending can't fail.
+    let _ = self.state.tx.send(event);
   }
 
   fn response(&self, response: Response) {
-    self.state.tx.send(response).unwrap();
+    // Only the first response is waited for, e.g. when the compiler reports
+    // a protocol error and then exits.
+    self.event(Event::Response(response));
+  }
+
+  /// Handles `event` on the current thread, and returns it if it's the
+  /// response that's waited for.
+  fn handle(&self, event: Event) -> Option<Response> {
+    let host = &self.state.host;
+    let reply = match event {
+      Event::Response(response) => return Some(response),
+      Event::Log(e) => {
+        host.log(e);
+        return None;
+      }
+      Event::Canonicalize(e) => {
+        inbound_message::Message::CanonicalizeResponse(host.canonicalize(&e))
+      }
+      Event::Import(e) => {
+        inbound_message::Message::ImportResponse(host.import(&e))
+      }
+      Event::FileImport(e) => {
+        inbound_message::Message::FileImportResponse(host.file_import(&e))
+      }
+      Event::FunctionCall(e) => {
+        inbound_message::Message::FunctionCallResponse(host.function_call(&e))
+      }
+    };
+    self.reply(reply);
+    None
+  }
+
+  /// Handles events until the response arrives.
+  fn recv(&self) -> Response {
+    loop {
+      // The sender lives as long as this connection, so receiving can't
+      // fail.
+      let event = self.state.rx.recv().unwrap();
+      if let Some(response) = self.handle(event) {
+        return response;
+      }
+    }
   }
 
   pub fn error(&self, message: ProtocolError) {
-    self.response(Err(message));
+    self.response(Err(Box::new(message.into())));
+  }
+
+  /// Fails the pending request, because the compiler can't be reached.
+  pub fn fail(&self, exception: Exception) {
+    self.response(Err(Box::new(exception)));
   }
 
   pub fn log_event(&self, e: LogEvent) {
-    self.state.host.log(e);
+    self.event(Event::Log(e));
   }
 
   pub fn canonicalize_request(&self, e: CanonicalizeRequest) {
-    self.send_message(InboundMessage {
-      message: Some(inbound_message::Message::CanonicalizeResponse(
-        self.state.host.canonicalize(&e),
-      )),
-    });
+    self.event(Event::Canonicalize(e));
   }
 
   pub fn import_request(&self, e: ImportRequest) {
-    self.send_message(InboundMessage {
-      message: Some(inbound_message::Message::ImportResponse(
-        self.state.host.import(&e),
-      )),
-    });
+    self.event(Event::Import(e));
   }
 
   pub fn file_import_request(&self, e: FileImportRequest) {
-    self.send_message(InboundMessage {
-      message: Some(inbound_message::Message::FileImportResponse(
-        self.state.host.file_import(&e),
-      )),
-    });
+    self.event(Event::FileImport(e));
+  }
+
+  pub fn function_call_request(&self, e: FunctionCallRequest) {
+    self.event(Event::FunctionCall(e));
   }
 
   pub fn compile_request(
     &self,
     mut request: CompileRequest,
-  ) -> Result<CompileResponse, ProtocolError> {
+    interrupt: &Interrupt,
+  ) -> Result<CompileResponse> {
     request.id = self.id();
     self.send_message(InboundMessage {
       message: Some(inbound_message::Message::CompileRequest(request)),
-    });
-    self
-      .state
-      .rx
-      .recv()
-      .unwrap()
-      .map(|response| match response {
-        ProtocolResponse::Compile(response) => response,
-        _ => unreachable!(),
-      })
+    })?;
+    let timeout = match interrupt.timeout {
+      Some(timeout) => crossbeam_channel::after(timeout),
+      None => crossbeam_channel::never(),
+    };
+    let never = crossbeam_channel::never();
+    let cancelled = match &interrupt.token {
+      Some(token) => token.receiver(),
+      None => &never,
+    };
+    // An importer or function that's running isn't interrupted, but the
+    // compilation fails as soon as it returns.
+    let exception = loop {
+      let event = crossbeam_channel::select! {
+        recv(self.state.rx) -> event => event.unwrap(),
+        recv(timeout) -> _ => break interrupt.timed_out(),
+        recv(cancelled) -> _ => break interrupt.cancelled(),
+      };
+      if let Some(response) = self.handle(event) {
+        return response.map(|response| match response {
+          ProtocolResponse::Compile(response) => response,
+          _ => unreachable!(),
+        });
+      }
+    };
+    // The compiler may still be running the compilation, e.g. an endless
+    // loop, so it's replaced once the other compilations are done.
+    self.dispatcher.retire();
+    Err(Box::new(exception))
   }
 
   pub fn compile_response(&self, response: CompileResponse) {
     self.response(Ok(ProtocolResponse::Compile(response)));
   }
 
-  pub fn version_request(&self) -> Result<VersionResponse, ProtocolError> {
+  pub fn version_request(&self) -> Result<VersionResponse> {
     self.send_message(InboundMessage {
       message: Some(inbound_message::Message::VersionRequest(VersionRequest {
         id: self.id(),
       })),
-    });
-    self
-      .state
-      .rx
-      .recv()
-      .unwrap()
-      .map(|response| match response {
-        ProtocolResponse::Version(response) => response,
-        _ => unreachable!(),
-      })
+    })?;
+    self.recv().map(|response| match response {
+      ProtocolResponse::Version(response) => response,
+      _ => unreachable!(),
+    })
+  }
+
+  /// The versions of the compiler, which are only requested once per
+  /// compiler process.
+  pub fn version(&self) -> Result<VersionInfo> {
+    if let Some(version) = self.dispatcher.version() {
+      return Ok(version);
+    }
+    let version = VersionInfo::try_from(self.version_request()?)?;
+    self.dispatcher.set_version(version.clone());
+    Ok(version)
   }
 
   pub fn version_response(&self, response: VersionResponse) {
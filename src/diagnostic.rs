@@ -0,0 +1,494 @@
+use std::fmt::{self, Write};
+
+use atty::Stream;
+
+use crate::{
+  Exception, LoggerDebugOptions, LoggerWarnOptions, SourceSpan, StackFrame,
+};
+
+/// Options for [Report::render].
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+  /// Whether to color the output with ANSI escape codes. Defaults to `false`.
+  pub color: bool,
+  /// Whether to draw the code frames with unicode box characters rather than
+  /// ASCII. Defaults to `true`.
+  pub unicode: bool,
+  /// The width that messages and notes are wrapped to. Code is never
+  /// wrapped. Defaults to `None`, which doesn't wrap.
+  pub width: Option<usize>,
+}
+
+impl Default for RenderOptions {
+  fn default() -> Self {
+    Self {
+      color: false,
+      unicode: true,
+      width: None,
+    }
+  }
+}
+
+impl RenderOptions {
+  /// Options for printing to stderr: colored if it's a terminal, and wrapped
+  /// to the `COLUMNS` environment variable if it's set.
+  pub fn for_stderr() -> Self {
+    Self {
+      color: atty::is(Stream::Stderr),
+      unicode: true,
+      width: std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()),
+    }
+  }
+}
+
+/// The severity of a [Report].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+  /// A compilation failure.
+  Error,
+  /// A warning from `@warn` or the compiler.
+  Warning,
+  /// A deprecation warning.
+  Deprecation,
+  /// A message from `@debug`.
+  Debug,
+}
+
+impl Severity {
+  fn name(self) -> &'static str {
+    match self {
+      Self::Error => "error",
+      Self::Warning => "warning",
+      Self::Deprecation => "deprecation warning",
+      Self::Debug => "debug",
+    }
+  }
+
+  /// The ANSI color code of the severity.
+  fn color(self) -> &'static str {
+    match self {
+      Self::Error => "31",
+      Self::Warning | Self::Deprecation => "33",
+      Self::Debug => "36",
+    }
+  }
+}
+
+/// A span of Sass source code that a [Report] points at, with an optional
+/// message.
+#[derive(Debug, Clone)]
+pub struct Label {
+  /// The span of source code.
+  pub span: SourceSpan,
+  /// The message shown next to the span.
+  pub message: Option<String>,
+}
+
+/// An error, warning or debug message with annotated code frames, built from
+/// an [Exception] or a log event.
+///
+/// ```
+/// use sass_embedded::{Report, RenderOptions, Severity};
+///
+/// let report = Report::new(Severity::Warning, "Something's off.")
+///   .note("Check the docs.");
+/// assert_eq!(
+///   report.render(&RenderOptions::default()),
+///   "warning: Something's off.\n = Check the docs.\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Report {
+  /// How severe the message is.
+  pub severity: Severity,
+  /// The message.
+  pub message: String,
+  /// The spans of source code the message is about, the first of which is
+  /// the primary one.
+  pub labels: Vec<Label>,
+  /// Notes shown after the code frames, e.g. the Sass stack trace.
+  pub notes: Vec<String>,
+}
+
+impl Report {
+  /// Creates a report without labels and notes.
+  pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+    Self {
+      severity,
+      message: message.into(),
+      labels: Vec::new(),
+      notes: Vec::new(),
+    }
+  }
+
+  /// Creates a report for a [Logger::warn](crate::Logger::warn) call.
+  pub fn warning(message: &str, options: &LoggerWarnOptions) -> Self {
+    let severity = if options.deprecation {
+      Severity::Deprecation
+    } else {
+      Severity::Warning
+    };
+    let mut report = Self::new(severity, message);
+    if let Some(span) = &options.span {
+      report = report.label(span.clone(), None::<String>);
+    }
+    report.stack(options.stack_frames())
+  }
+
+  /// Creates a report for a [Logger::debug](crate::Logger::debug) call.
+  pub fn debug(message: &str, options: &LoggerDebugOptions) -> Self {
+    let mut report = Self::new(Severity::Debug, message);
+    if let Some(span) = &options.span {
+      report = report.label(span.clone(), None::<String>);
+    }
+    report
+  }
+
+  /// Adds a label for `span`.
+  pub fn label(
+    mut self,
+    span: SourceSpan,
+    message: Option<impl Into<String>>,
+  ) -> Self {
+    self.labels.push(Label {
+      span,
+      message: message.map(Into::into),
+    });
+    self
+  }
+
+  /// Adds a note.
+  pub fn note(mut self, note: impl Into<String>) -> Self {
+    self.notes.push(note.into());
+    self
+  }
+
+  /// Adds a note for each frame of a Sass stack trace.
+  fn stack(mut self, frames: Vec<StackFrame>) -> Self {
+    self
+      .notes
+      .extend(frames.iter().map(|frame| format!("at {}", frame)));
+    self
+  }
+
+  /// Renders the message, a code frame for each label, and the notes.
+  pub fn render(&self, options: &RenderOptions) -> String {
+    Renderer {
+      options,
+      out: String::new(),
+      gutter: self
+        .labels
+        .iter()
+        .map(|label| (label.span.end.line + 1).to_string().len())
+        .max()
+        .unwrap_or(0),
+    }
+    .render(self)
+  }
+}
+
+impl From<&Exception> for Report {
+  fn from(exception: &Exception) -> Self {
+    let message = exception.sass_message().unwrap_or(exception.message());
+    let mut report = Self::new(Severity::Error, message);
+    if let Some(span) = exception.span() {
+      report = report.label(span.clone(), None::<String>);
+    }
+    report.stack(exception.stack_frames())
+  }
+}
+
+impl fmt::Display for Report {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.render(&RenderOptions::default()))
+  }
+}
+
+impl std::error::Error for Report {}
+
+struct Renderer<'a> {
+  options: &'a RenderOptions,
+  out: String,
+  /// The width of the line numbers.
+  gutter: usize,
+}
+
+impl Renderer<'_> {
+  fn render(mut self, report: &Report) -> String {
+    let severity = report.severity.name();
+    let header = format!("{}: ", severity);
+    let message = wrap(&report.message, self.width(header.len()));
+    let mut lines = message.iter();
+    let first = lines.next().map(String::as_str).unwrap_or_default();
+    let styled =
+      self.style(severity, &format!("1;{}", report.severity.color()));
+    let _ = writeln!(self.out, "{}: {}", styled, self.style(first, "1"));
+    for line in lines {
+      let _ = writeln!(self.out, "{}{}", " ".repeat(header.len()), line);
+    }
+    for label in &report.labels {
+      self.frame(label, report.severity);
+    }
+    for note in &report.notes {
+      self.note(note);
+    }
+    self.out
+  }
+
+  fn frame(&mut self, label: &Label, severity: Severity) {
+    let span = &label.span;
+    let (bar, corner) = if self.options.unicode {
+      ("│", "┌─")
+    } else {
+      ("|", ",-")
+    };
+    let location = format!(
+      "{}:{}:{}",
+      span
+        .url
+        .as_ref()
+        .map(display_url)
+        .unwrap_or_else(|| "-".to_string()),
+      span.start.line + 1,
+      span.start.column + 1
+    );
+    let pad = " ".repeat(self.gutter);
+    let bar = self.style(bar, "34");
+    let _ = writeln!(
+      self.out,
+      "{} {} {}",
+      pad,
+      self.style(corner, "34"),
+      location
+    );
+    let _ = writeln!(self.out, "{} {}", pad, bar);
+
+    let context = span
+      .context
+      .clone()
+      .unwrap_or_else(|| " ".repeat(span.start.column) + &span.text);
+    let lines: Vec<_> = context.lines().collect();
+    // The line the label message goes on, even if the context is cut short.
+    let last = span
+      .end
+      .line
+      .min(span.start.line + lines.len().saturating_sub(1));
+    for (i, code) in lines.iter().enumerate() {
+      let number = span.start.line + i;
+      let gutter = format!("{:>width$}", number + 1, width = self.gutter);
+      let _ = writeln!(
+        self.out,
+        "{} {} {}",
+        self.style(&gutter, "34"),
+        bar,
+        expand_tabs(code)
+      );
+      if number > span.end.line {
+        continue;
+      }
+      let start = if number == span.start.line {
+        span.start.column
+      } else {
+        code.chars().take_while(|c| c.is_whitespace()).count()
+      };
+      let end = if number == span.end.line {
+        span.end.column
+      } else {
+        code.chars().count()
+      };
+      let start = display_column(code, start);
+      let end = display_column(code, end).max(start + 1);
+      let carets = self.style(&"^".repeat(end - start), severity.color());
+      let mut underline =
+        format!("{} {} {}{}", pad, bar, " ".repeat(start), carets);
+      if number == last {
+        if let Some(message) = &label.message {
+          let _ =
+            write!(underline, " {}", self.style(message, severity.color()));
+        }
+      }
+      let _ = writeln!(self.out, "{}", underline);
+    }
+  }
+
+  fn note(&mut self, note: &str) {
+    let pad = " ".repeat(self.gutter);
+    let prefix = format!("{} = ", pad);
+    let wrapped = wrap(note, self.width(prefix.len()));
+    for (i, line) in wrapped.iter().enumerate() {
+      if i == 0 {
+        let _ = writeln!(self.out, "{}{}", prefix, line);
+      } else {
+        let _ = writeln!(self.out, "{}{}", " ".repeat(prefix.len()), line);
+      }
+    }
+  }
+
+  /// The width left for text after an `indent`.
+  fn width(&self, indent: usize) -> Option<usize> {
+    self
+      .options
+      .width
+      .map(|width| width.saturating_sub(indent).max(20))
+  }
+
+  fn style(&self, text: &str, code: &str) -> String {
+    if self.options.color {
+      format!("\u{1b}[{}m{}\u{1b}[0m", code, text)
+    } else {
+      text.to_string()
+    }
+  }
+}
+
+/// Shows file: URLs as paths.
+fn display_url(url: &url::Url) -> String {
+  match url.to_file_path() {
+    Ok(path) if url.scheme() == "file" => path.display().to_string(),
+    _ => url.to_string(),
+  }
+}
+
+const TAB_WIDTH: usize = 4;
+
+fn expand_tabs(code: &str) -> String {
+  code.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// The column at which the character at `column` is displayed, once tabs are
+/// expanded.
+fn display_column(code: &str, column: usize) -> usize {
+  let chars = code.chars().take(column);
+  let (tabs, others) = chars.fold((0, 0), |(tabs, others), c| {
+    if c == '\t' {
+      (tabs + 1, others)
+    } else {
+      (tabs, others + 1)
+    }
+  });
+  // Columns past the end of the line, e.g. at a line break, still count.
+  others + tabs * TAB_WIDTH + column.saturating_sub(code.chars().count())
+}
+
+/// Splits `text` into lines no longer than `width`, breaking at whitespace.
+fn wrap(text: &str, width: Option<usize>) -> Vec<String> {
+  let mut lines = Vec::new();
+  for paragraph in text.lines() {
+    let width = match width {
+      Some(width) => width,
+      None => {
+        lines.push(paragraph.to_string());
+        continue;
+      }
+    };
+    let mut line = String::new();
+    for word in paragraph.split_whitespace() {
+      let len = line.chars().count();
+      if len > 0 && len + 1 + word.chars().count() > width {
+        lines.push(std::mem::take(&mut line));
+      }
+      if !line.is_empty() {
+        line.push(' ');
+      }
+      line.push_str(word);
+    }
+    lines.push(line);
+  }
+  if lines.is_empty() {
+    lines.push(String::new());
+  }
+  lines
+}
+
+#[cfg(feature = "miette")]
+mod miette_impl {
+  use miette::{Diagnostic, LabeledSpan, SourceCode};
+
+  use super::{Report, Severity};
+  use crate::{Exception, ExceptionKind, SourceSpan};
+
+  /// The label of `span`, relative to its context.
+  fn labeled_span(span: &SourceSpan, message: Option<String>) -> LabeledSpan {
+    let context = span.context.as_deref().unwrap_or(&span.text);
+    let offset = if span.context.is_some() {
+      context
+        .char_indices()
+        .nth(span.start.column)
+        .map_or(context.len(), |(i, _)| i)
+    } else {
+      0
+    };
+    LabeledSpan::new_with_span(message, (offset, span.text.len()))
+  }
+
+  fn source_code(span: &SourceSpan) -> &dyn SourceCode {
+    match &span.context {
+      Some(context) => context,
+      None => &span.text,
+    }
+  }
+
+  impl Diagnostic for Exception {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+      let code = match self.kind() {
+        ExceptionKind::CompileFailure => "sass::compile_failure",
+        ExceptionKind::ImporterFailure => "sass::importer_failure",
+        ExceptionKind::Protocol(_) => "sass::protocol",
+        ExceptionKind::Io => "sass::io",
+        ExceptionKind::Spawn => "sass::spawn",
+        ExceptionKind::Timeout => "sass::timeout",
+        ExceptionKind::Cancelled => "sass::cancelled",
+        ExceptionKind::Version => "sass::version",
+        ExceptionKind::Other => return None,
+      };
+      Some(Box::new(code))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+      self.span().map(source_code)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+      let span = self.span()?;
+      Some(Box::new(std::iter::once(labeled_span(span, None))))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+      self
+        .sass_stack()
+        .filter(|stack| !stack.trim().is_empty())
+        .map(|stack| Box::new(stack.trim_end()) as Box<dyn std::fmt::Display>)
+    }
+  }
+
+  impl Diagnostic for Report {
+    fn severity(&self) -> Option<miette::Severity> {
+      Some(match self.severity {
+        Severity::Error => miette::Severity::Error,
+        Severity::Warning | Severity::Deprecation => miette::Severity::Warning,
+        Severity::Debug => miette::Severity::Advice,
+      })
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+      self.labels.first().map(|label| source_code(&label.span))
+    }
+
+    /// Only the primary label, since the others may be in other sources.
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+      let label = self.labels.first()?;
+      Some(Box::new(std::iter::once(labeled_span(
+        &label.span,
+        label.message.clone(),
+      ))))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+      if self.notes.is_empty() {
+        None
+      } else {
+        Some(Box::new(self.notes.join("\n")))
+      }
+    }
+  }
+}
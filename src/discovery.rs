@@ -0,0 +1,165 @@
+use std::{
+  env,
+  ffi::OsString,
+  fmt::Write,
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::{Exception, ExceptionKind, Result};
+
+/// The names of the dart-sass-embedded executable.
+#[cfg(windows)]
+const EXECUTABLES: &[&str] =
+  &["dart-sass-embedded.bat", "dart-sass-embedded.exe"];
+#[cfg(not(windows))]
+const EXECUTABLES: &[&str] = &["dart-sass-embedded"];
+
+/// Where [Embedded::discover] looks for the dart-sass-embedded executable,
+/// in the order of the fields.
+///
+/// [Embedded::discover]: crate::Embedded::discover
+#[derive(Debug, Clone)]
+pub struct DiscoveryOptions {
+  /// An environment variable that contains the path of the executable, or of
+  /// the directory it's in. Defaults to `SASS_EMBEDDED_PATH`.
+  pub env_var: Option<OsString>,
+  /// Whether to search the directories in `$PATH`. Defaults to `true`.
+  pub search_path: bool,
+  /// The directory whose `node_modules`, and the `node_modules` of its
+  /// ancestors, are searched for the executable vendored by the
+  /// `sass-embedded` npm packages. Defaults to the current directory.
+  pub node_modules_root: Option<PathBuf>,
+  /// A directory that the executable was downloaded to. Defaults to
+  /// `sass-embedded` in the user's cache directory, e.g.
+  /// `~/.cache/sass-embedded`.
+  pub cache_dir: Option<PathBuf>,
+}
+
+impl Default for DiscoveryOptions {
+  fn default() -> Self {
+    Self {
+      env_var: Some("SASS_EMBEDDED_PATH".into()),
+      search_path: true,
+      node_modules_root: env::current_dir().ok(),
+      cache_dir: user_cache_dir().map(|dir| dir.join("sass-embedded")),
+    }
+  }
+}
+
+impl DiscoveryOptions {
+  /// Finds the executable, or returns an [ExceptionKind::Spawn] exception
+  /// that lists every location that was tried.
+  pub fn find(&self) -> Result<PathBuf> {
+    let mut tried = Vec::new();
+    self.find_in(&mut tried).ok_or_else(|| {
+      let mut message =
+        format!("Couldn't find the {} executable. Tried:", EXECUTABLES[0]);
+      for location in &tried {
+        let _ = write!(message, "\n  - {}", location);
+      }
+      Box::new(Exception::new(message).with_kind(ExceptionKind::Spawn))
+    })
+  }
+
+  fn find_in(&self, tried: &mut Vec<String>) -> Option<PathBuf> {
+    if let Some(var) = &self.env_var {
+      let name = var.to_string_lossy();
+      match env::var_os(var) {
+        Some(value) if !value.is_empty() => {
+          let path = PathBuf::from(value);
+          if is_executable(&path) {
+            return Some(path);
+          }
+          if let Some(found) = search_dir(&path, tried) {
+            return Some(found);
+          }
+          tried.push(format!("${} ({})", name, path.display()));
+        }
+        _ => tried.push(format!("${} (not set)", name)),
+      }
+    }
+    if self.search_path {
+      let paths = env::var_os("PATH").unwrap_or_default();
+      for dir in env::split_paths(&paths) {
+        if let Some(found) = search_dir(&dir, tried) {
+          return Some(found);
+        }
+      }
+    }
+    if let Some(root) = &self.node_modules_root {
+      for dir in root.ancestors() {
+        for vendor in vendor_dirs(&dir.join("node_modules")) {
+          for dir in [vendor.join("dart-sass-embedded"), vendor] {
+            if let Some(found) = search_dir(&dir, tried) {
+              return Some(found);
+            }
+          }
+        }
+      }
+    }
+    if let Some(cache) = &self.cache_dir {
+      for dir in [cache.join("sass_embedded"), cache.clone()] {
+        if let Some(found) = search_dir(&dir, tried) {
+          return Some(found);
+        }
+      }
+    }
+    None
+  }
+}
+
+/// Looks for the executable directly in `dir`, recording the paths it tried.
+fn search_dir(dir: &Path, tried: &mut Vec<String>) -> Option<PathBuf> {
+  EXECUTABLES.iter().map(|name| dir.join(name)).find(|path| {
+    tried.push(path.display().to_string());
+    is_executable(path)
+  })
+}
+
+/// The `dist/lib/src/vendor` directories of the `sass-embedded*` packages in
+/// `node_modules`.
+fn vendor_dirs(node_modules: &Path) -> Vec<PathBuf> {
+  let mut dirs: Vec<_> = fs::read_dir(node_modules)
+    .into_iter()
+    .flatten()
+    .flatten()
+    .filter(|entry| {
+      entry
+        .file_name()
+        .to_string_lossy()
+        .starts_with("sass-embedded")
+    })
+    .map(|entry| entry.path().join("dist/lib/src/vendor"))
+    .filter(|dir| dir.is_dir())
+    .collect();
+  dirs.sort();
+  dirs
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+  use std::os::unix::fs::PermissionsExt;
+
+  fs::metadata(path)
+    .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+    .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+  path.is_file()
+}
+
+fn user_cache_dir() -> Option<PathBuf> {
+  let var = |name| env::var_os(name).filter(|value| !value.is_empty());
+  if cfg!(windows) {
+    var("LOCALAPPDATA").map(PathBuf::from)
+  } else if cfg!(target_os = "macos") {
+    var("HOME").map(|home| PathBuf::from(home).join("Library/Caches"))
+  } else {
+    var("XDG_CACHE_HOME")
+      .map(PathBuf::from)
+      .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".cache")))
+  }
+}
@@ -0,0 +1,427 @@
+use std::{
+  collections::HashMap,
+  fs::{self, File},
+  hash::Hasher,
+  io::{self, Write},
+  path::{Path, PathBuf},
+  process,
+  sync::atomic::{AtomicU64, Ordering},
+  time::{Duration, SystemTime},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+  cache::{fingerprint, modified_since},
+  CompileResult, Embedded, Exception, Options, Result, Url, VersionInfo,
+};
+
+/// The first line of every entry, which changes with the format.
+const HEADER: &str = "sass-embedded-cache 1";
+
+/// The extension of the files that hold cached results.
+const EXTENSION: &str = "entry";
+
+/// How old a temporary file must be before it's considered abandoned by a
+/// crashed process.
+const ABANDONED: Duration = Duration::from_secs(60 * 60);
+
+/// Distinguishes the temporary files of the threads of one process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Caches [CompileResult]s of [Embedded::compile] in a directory, so they
+/// can be reused by later processes, e.g. of the next CI run.
+///
+/// Each result is stored under a hash of the entrypoint's path and
+/// contents, the fingerprint of the [Options] that [CompileCache] uses, and
+/// the [VersionInfo] of the compiler. It's reused as long as the contents of
+/// every file it loaded hash the same. Results that loaded a URL of an
+/// importer aren't stored, since the cache can't tell whether it changed,
+/// and neither are results whose files changed while they were compiled.
+///
+/// Several processes can share the directory: entries are written to a
+/// temporary file and renamed into place, so readers never see a partial
+/// entry. Failing to read or write the cache never fails a compilation.
+///
+/// ```no_run
+/// use sass_embedded::{DiskCache, Options, Sass};
+///
+/// let sass = Sass::new("path/to/sass_embedded").unwrap();
+/// let cache = DiskCache::new("target/sass-cache")
+///   .unwrap()
+///   .with_max_size(64 * 1024 * 1024);
+/// let res = cache.compile(&sass, "style.scss", Options::default()).unwrap();
+/// ```
+///
+/// [CompileCache]: crate::CompileCache
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+  dir: PathBuf,
+  max_size: u64,
+}
+
+impl DiskCache {
+  /// Opens the cache in `dir`, creating the directory if needed. It may
+  /// grow to 256 MiB, see [DiskCache::with_max_size].
+  pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+    let dir = dir.into();
+    fs::create_dir_all(&dir).map_err(|e| {
+      Exception::io(&format!("Failed to create {}", dir.display()), e)
+    })?;
+    Ok(Self {
+      dir,
+      max_size: 256 * 1024 * 1024,
+    })
+  }
+
+  /// Sets how many bytes the entries may take up. Once they take up more,
+  /// the least recently used ones are removed.
+  pub fn with_max_size(mut self, max_size: u64) -> Self {
+    self.max_size = max_size;
+    self
+  }
+
+  /// The directory of the cache.
+  pub fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  /// Returns the cached result of compiling `path` with `options` if it's
+  /// still fresh, or compiles it with `sass` and stores the result.
+  pub fn compile(
+    &self,
+    sass: &Embedded,
+    path: impl AsRef<Path>,
+    options: Options,
+  ) -> Result<CompileResult> {
+    // Only requested from the compiler once, see [Embedded::version].
+    let compiler = sass.version()?;
+    self.get_or_compile(path, options, &compiler, |path, options| {
+      sass.compile(path, options)
+    })
+  }
+
+  /// Like [DiskCache::compile], but compiles with `compile`, e.g. to use an
+  /// [EmbeddedPool](crate::EmbeddedPool), whose version is `compiler`.
+  pub fn get_or_compile(
+    &self,
+    path: impl AsRef<Path>,
+    options: Options,
+    compiler: &VersionInfo,
+    compile: impl FnOnce(&Path, Options) -> Result<CompileResult>,
+  ) -> Result<CompileResult> {
+    let path = path.as_ref();
+    // The entrypoint is hashed into the key, so it's read only once.
+    let Some((source, contents)) =
+      fs::canonicalize(path).ok().and_then(|source| {
+        let contents = fs::read(&source).ok()?;
+        Some((source, contents))
+      })
+    else {
+      // The entrypoint can't be read, which the compiler reports.
+      return compile(path, options);
+    };
+    let entry = self
+      .dir
+      .join(key(&source, &contents, &options, compiler))
+      .with_extension(EXTENSION);
+    // Hashes the files this compilation is known to load before compiling,
+    // so an edit made during the compilation isn't stored as up to date.
+    let mut before = HashMap::new();
+    match read_entry(&entry) {
+      Some(stored) if stored.is_fresh() => {
+        // Marks the entry as recently used.
+        let _ = File::options()
+          .write(true)
+          .open(&entry)
+          .and_then(|file| file.set_modified(SystemTime::now()));
+        return Ok(stored.result);
+      }
+      Some(stored) => {
+        for (_, url) in stored.dependencies {
+          let hash = hash_file(&url);
+          before.insert(url, hash);
+        }
+      }
+      None => {}
+    }
+    let started = SystemTime::now();
+    let result = compile(path, options)?;
+    let Some(dependencies) = hash_dependencies(&result) else {
+      return Ok(result);
+    };
+    let changed = fs::read(&source).ok().as_deref() != Some(&contents[..])
+      || dependencies.iter().any(|(hash, url)| {
+        before
+          .get(url)
+          .is_some_and(|before| before.as_ref() != Some(hash))
+          || modified_since(url, started)
+      });
+    if !changed {
+      let contents = encode(&dependencies, &result);
+      if self.write_entry(&entry, &contents).is_ok() {
+        let _ = self.evict();
+      }
+    }
+    Ok(result)
+  }
+
+  /// The number of bytes the entries take up.
+  pub fn size(&self) -> u64 {
+    self
+      .entries()
+      .map(|entries| entries.iter().map(|(_, size, _)| size).sum())
+      .unwrap_or(0)
+  }
+
+  /// Removes every entry.
+  pub fn clear(&self) -> Result<()> {
+    let entries = self.entries().map_err(|e| {
+      Exception::io(&format!("Failed to read {}", self.dir.display()), e)
+    })?;
+    for (path, _, _) in entries {
+      remove(&path).map_err(|e| {
+        Exception::io(&format!("Failed to remove {}", path.display()), e)
+      })?;
+    }
+    Ok(())
+  }
+
+  fn write_entry(&self, entry: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = self.dir.join(format!(
+      ".{}.{}.tmp",
+      process::id(),
+      TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let written = File::create(&temp).and_then(|mut file| {
+      file.write_all(contents)?;
+      file.sync_all()
+    });
+    match written.and_then(|()| fs::rename(&temp, entry)) {
+      Ok(()) => Ok(()),
+      Err(e) => {
+        let _ = fs::remove_file(&temp);
+        Err(e)
+      }
+    }
+  }
+
+  /// Removes the least recently used entries until the cache fits
+  /// [DiskCache::with_max_size], and temporary files that were abandoned.
+  fn evict(&self) -> io::Result<()> {
+    let mut entries = self.entries()?;
+    let mut size: u64 = entries.iter().map(|(_, size, _)| size).sum();
+    entries.sort_by_key(|(_, _, used)| *used);
+    for (path, len, _) in entries {
+      if size <= self.max_size {
+        break;
+      }
+      remove(&path)?;
+      size -= len;
+    }
+    for entry in fs::read_dir(&self.dir)? {
+      let path = entry?.path();
+      let is_temp = path.extension().is_some_and(|ext| ext == "tmp");
+      let age = fs::metadata(&path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok());
+      if is_temp && age.is_some_and(|age| age > ABANDONED) {
+        remove(&path)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// The path, size and last use of every entry.
+  fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(&self.dir)? {
+      let entry = entry?;
+      let path = entry.path();
+      if path.extension().is_none_or(|ext| ext != EXTENSION) {
+        continue;
+      }
+      // Another process may have removed it in the meantime.
+      if let Ok(meta) = entry.metadata() {
+        entries.push((path, meta.len(), meta.modified()?));
+      }
+    }
+    Ok(entries)
+  }
+}
+
+/// Removes a file, unless another process already did.
+fn remove(path: &Path) -> io::Result<()> {
+  match fs::remove_file(path) {
+    Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+    _ => Ok(()),
+  }
+}
+
+/// Feeds [Hasher] input, e.g. of [fingerprint], to a SHA-256 digest.
+struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+  fn write(&mut self, bytes: &[u8]) {
+    self.0.update(bytes);
+  }
+
+  fn finish(&self) -> u64 {
+    let digest = self.0.clone().finalize();
+    u64::from_be_bytes(digest[..8].try_into().unwrap())
+  }
+}
+
+/// The name of the entry of a compilation of the entrypoint at the
+/// canonical path `source`, whose contents are `contents`.
+fn key(
+  source: &Path,
+  contents: &[u8],
+  options: &Options,
+  compiler: &VersionInfo,
+) -> String {
+  let mut hasher = Sha256Hasher(Sha256::new());
+  hasher.0.update(HEADER);
+  field(&mut hasher.0, source.to_string_lossy().as_bytes());
+  field(&mut hasher.0, contents);
+  fingerprint(options, &mut hasher);
+  field(
+    &mut hasher.0,
+    compiler.protocol_version.to_string().as_bytes(),
+  );
+  field(
+    &mut hasher.0,
+    compiler.compiler_version.to_string().as_bytes(),
+  );
+  field(
+    &mut hasher.0,
+    compiler.implementation_version.to_string().as_bytes(),
+  );
+  field(&mut hasher.0, compiler.implementation_name.as_bytes());
+  hex(&hasher.0.finalize())
+}
+
+/// Hashes `bytes` with their length, so consecutive fields can't run into
+/// each other.
+fn field(digest: &mut Sha256, bytes: &[u8]) {
+  digest.update((bytes.len() as u64).to_le_bytes());
+  digest.update(bytes);
+}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The hash of the contents of a `file:` URL, or `None` if it can't be read.
+fn hash_file(url: &Url) -> Option<String> {
+  let contents = fs::read(url.to_file_path().ok()?).ok()?;
+  Some(hex(&Sha256::digest(contents)))
+}
+
+/// The hash of every file `result` loaded, or `None` if it can't be cached.
+fn hash_dependencies(result: &CompileResult) -> Option<Vec<(String, Url)>> {
+  result
+    .loaded_urls
+    .iter()
+    .map(|url| match url.scheme() {
+      "file" => Some((hash_file(url)?, url.clone())),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Encodes `result` with the hash of every file it loaded.
+///
+/// ```text
+/// sass-embedded-cache 1
+/// <number of loaded URLs>
+/// <SHA-256 of the file> <URL>
+/// ...
+/// <length of the CSS>
+/// <CSS>
+/// <length of the source map, or "-" if there's none>
+/// <source map>
+/// ```
+fn encode(dependencies: &[(String, Url)], result: &CompileResult) -> Vec<u8> {
+  let mut out = format!("{}\n{}\n", HEADER, dependencies.len());
+  for (hash, url) in dependencies {
+    out.push_str(&format!("{} {}\n", hash, url));
+  }
+  out.push_str(&format!("{}\n{}\n", result.css.len(), result.css));
+  match &result.source_map {
+    Some(map) => out.push_str(&format!("{}\n{}\n", map.len(), map)),
+    None => out.push_str("-\n"),
+  }
+  out.into_bytes()
+}
+
+/// An entry that was read, with the hashes its files had when it was
+/// stored.
+struct Stored {
+  dependencies: Vec<(String, Url)>,
+  result: CompileResult,
+}
+
+impl Stored {
+  /// Whether every file the result loaded still hashes the same.
+  fn is_fresh(&self) -> bool {
+    self
+      .dependencies
+      .iter()
+      .all(|(hash, url)| hash_file(url).as_ref() == Some(hash))
+  }
+}
+
+/// Reads the entry at `entry`, if it exists and is well-formed.
+fn read_entry(entry: &Path) -> Option<Stored> {
+  let contents = fs::read_to_string(entry).ok()?;
+  let mut reader = Reader(&contents);
+  if reader.line()? != HEADER {
+    return None;
+  }
+  let count: usize = reader.line()?.parse().ok()?;
+  let mut dependencies = Vec::with_capacity(count);
+  for _ in 0..count {
+    let (hash, url) = reader.line()?.split_once(' ')?;
+    dependencies.push((hash.to_string(), Url::parse(url).ok()?));
+  }
+  let css = reader.sized()?.to_string();
+  let source_map = match reader.line()? {
+    "-" => None,
+    len => Some(reader.bytes(len.parse().ok()?)?.to_string()),
+  };
+  let loaded_urls = dependencies.iter().map(|(_, url)| url.clone()).collect();
+  Some(Stored {
+    dependencies,
+    result: CompileResult {
+      css,
+      loaded_urls,
+      source_map,
+    },
+  })
+}
+
+/// Reads the fields of an entry.
+struct Reader<'a>(&'a str);
+
+impl<'a> Reader<'a> {
+  fn line(&mut self) -> Option<&'a str> {
+    let (line, rest) = self.0.split_once('\n')?;
+    self.0 = rest;
+    Some(line)
+  }
+
+  /// Reads a field that's prefixed with its length.
+  fn sized(&mut self) -> Option<&'a str> {
+    let len = self.line()?.parse().ok()?;
+    self.bytes(len)
+  }
+
+  /// Reads `len` bytes followed by a newline.
+  fn bytes(&mut self, len: usize) -> Option<&'a str> {
+    let field = self.0.get(..len)?;
+    self.0 = self.0.get(len..)?.strip_prefix('\n')?;
+    Some(field)
+  }
+}
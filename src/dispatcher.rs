@@ -1,12 +1,20 @@
 use dashmap::DashMap;
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::{
+  io,
+  process::ExitStatus,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, OnceLock,
+  },
+};
 
 use crate::{
   compiler::Compiler,
   connection::{Connected, ConnectedGuard, Connection, Unconnected},
   host::Host,
   protocol::{outbound_message, InboundMessage, OutboundMessage},
+  Exception, VersionInfo,
 };
 
 #[derive(Debug)]
@@ -14,6 +22,12 @@ pub struct Dispatcher {
   compiler: Compiler,
   observers: DashMap<u32, Arc<Connection<Connected>>>,
   id: Mutex<u32>,
+  /// Whether the compiler has completed a request.
+  responded: AtomicBool,
+  /// Whether the compiler is killed once its compilations are done.
+  retired: AtomicBool,
+  /// The versions of the compiler, once they were requested.
+  version: OnceLock<VersionInfo>,
 }
 
 impl Dispatcher {
@@ -24,6 +38,9 @@ impl Dispatcher {
       compiler,
       observers: DashMap::new(),
       id: Mutex::new(0),
+      responded: AtomicBool::new(false),
+      retired: AtomicBool::new(false),
+      version: OnceLock::new(),
     });
     Self::spawn(Arc::clone(&this));
     this
@@ -31,7 +48,14 @@ impl Dispatcher {
 
   fn spawn(dispatcher: Arc<Dispatcher>) {
     std::thread::spawn(move || loop {
-      dispatcher.receive_message(dispatcher.compiler.read());
+      match dispatcher.compiler.read() {
+        Ok(message) => dispatcher.receive_message(message),
+        Err(e) => {
+          dispatcher.fail(e);
+          // The stream can't be resynchronized, e.g. after a corrupt message.
+          break dispatcher.close();
+        }
+      }
     });
   }
 
@@ -39,10 +63,10 @@ impl Dispatcher {
     &self,
     observer: Connection<Unconnected>,
     host: Host,
-  ) -> Result<ConnectedGuard, (Connection<Unconnected>, Host)> {
+  ) -> Result<ConnectedGuard, Box<(Connection<Unconnected>, Host)>> {
     let mut id = self.id.lock();
     if *id == Self::PROTOCOL_ERROR_ID {
-      return Err((observer, host));
+      return Err(Box::new((observer, host)));
     }
     let observer = observer.connect(*id, host);
     self.observers.insert(*id, Arc::clone(&observer.0));
@@ -50,16 +74,111 @@ impl Dispatcher {
     Ok(observer)
   }
 
+  /// The number of compilations in flight.
+  pub fn load(&self) -> usize {
+    self.observers.len()
+  }
+
+  /// Whether the compiler can no longer be used, because its process has
+  /// exited or it reported a protocol error.
+  pub fn is_closed(&self) -> bool {
+    *self.id.lock() == Self::PROTOCOL_ERROR_ID || self.compiler.has_exited()
+  }
+
+  /// Whether the compiler has completed a request.
+  pub fn has_responded(&self) -> bool {
+    self.responded.load(Ordering::Relaxed)
+  }
+
+  /// The versions of the compiler, if they were requested before.
+  pub fn version(&self) -> Option<VersionInfo> {
+    self.version.get().cloned()
+  }
+
+  /// Remembers the versions of the compiler, which don't change while it
+  /// runs.
+  pub fn set_version(&self, version: VersionInfo) {
+    let _ = self.version.set(version);
+  }
+
+  /// The exit status of the compiler process, if it has exited.
+  pub fn exit_status(&self) -> Option<ExitStatus> {
+    self.compiler.exit_status()
+  }
+
+  /// The last lines the compiler wrote to stderr.
+  pub fn stderr(&self) -> Vec<String> {
+    self.compiler.stderr()
+  }
+
+  /// Kills the compiler process.
+  pub fn close(&self) {
+    self.compiler.kill();
+  }
+
+  /// Closes the dispatcher after reading from the compiler failed, failing
+  /// every pending connection with `error`.
+  fn fail(&self, error: io::Error) {
+    // Set while holding the lock, so no connection subscribes after this.
+    *self.id.lock() = Self::PROTOCOL_ERROR_ID;
+    let context = if error.kind() == io::ErrorKind::UnexpectedEof {
+      "The compiler exited unexpectedly"
+    } else {
+      "Failed to read from the compiler"
+    };
+    let stderr = self.compiler.final_stderr();
+    for ob in self.observers.iter() {
+      let error = io::Error::new(error.kind(), error.to_string());
+      ob.fail(
+        Exception::io(context, error).with_compiler_stderr(stderr.clone()),
+      );
+    }
+  }
+
+  /// Stops new compilations from using the compiler, and kills it once the
+  /// compilations in flight are done.
+  pub fn retire(&self) {
+    *self.id.lock() = Self::PROTOCOL_ERROR_ID;
+    self.retired.store(true, Ordering::Relaxed);
+    if self.observers.is_empty() {
+      self.close();
+    }
+  }
+
+  /// Whether [Dispatcher::retire] was called.
+  pub fn is_retired(&self) -> bool {
+    self.retired.load(Ordering::Relaxed)
+  }
+
   pub fn unsubscribe(&self, id: &u32) {
     self.observers.remove(id);
+    if self.is_retired() && self.observers.is_empty() {
+      self.close();
+    }
+  }
+
+  pub fn send_message(
+    &self,
+    inbound_message: InboundMessage,
+  ) -> Result<(), Box<Exception>> {
+    self.compiler.write(inbound_message).map_err(|e| {
+      Box::new(
+        Exception::io("Failed to write to the compiler", e)
+          .with_compiler_stderr(self.compiler.final_stderr()),
+      )
+    })
   }
 
-  pub fn send_message(&self, inbound_message: InboundMessage) {
-    self.compiler.write(inbound_message);
+  /// The connection of the compilation `id`, without keeping its entry
+  /// locked, so it can unsubscribe meanwhile.
+  fn observer(&self, id: u32) -> Option<Arc<Connection<Connected>>> {
+    self.observers.get(&id).map(|ob| Arc::clone(&ob))
   }
 
   fn receive_message(&self, outbound_message: OutboundMessage) {
-    let oneof = outbound_message.message.unwrap();
+    let Some(oneof) = outbound_message.message else {
+      return;
+    };
     match oneof {
       outbound_message::Message::Error(e) => {
         *self.id.lock() = Self::PROTOCOL_ERROR_ID;
@@ -67,41 +186,47 @@ impl Dispatcher {
           for ob in self.observers.iter() {
             ob.error(e.clone());
           }
-        } else if let Some(ob) = self.observers.get(&e.id) {
+        } else if let Some(ob) = self.observer(e.id) {
           ob.error(e);
         }
       }
       outbound_message::Message::CompileResponse(e) => {
-        if let Some(ob) = self.observers.get(&e.id) {
+        self.responded.store(true, Ordering::Relaxed);
+        if let Some(ob) = self.observer(e.id) {
           ob.compile_response(e);
         }
       }
       outbound_message::Message::VersionResponse(e) => {
-        if let Some(ob) = self.observers.get(&e.id) {
+        self.responded.store(true, Ordering::Relaxed);
+        if let Some(ob) = self.observer(e.id) {
           ob.version_response(e);
         }
       }
       outbound_message::Message::LogEvent(e) => {
-        if let Some(ob) = self.observers.get(&e.compilation_id) {
+        if let Some(ob) = self.observer(e.compilation_id) {
           ob.log_event(e);
         }
       }
       outbound_message::Message::CanonicalizeRequest(e) => {
-        if let Some(ob) = self.observers.get(&e.compilation_id) {
+        if let Some(ob) = self.observer(e.compilation_id) {
           ob.canonicalize_request(e);
         }
       }
       outbound_message::Message::ImportRequest(e) => {
-        if let Some(ob) = self.observers.get(&e.compilation_id) {
+        if let Some(ob) = self.observer(e.compilation_id) {
           ob.import_request(e);
         }
       }
       outbound_message::Message::FileImportRequest(e) => {
-        if let Some(ob) = self.observers.get(&e.compilation_id) {
+        if let Some(ob) = self.observer(e.compilation_id) {
           ob.file_import_request(e);
         }
       }
-      outbound_message::Message::FunctionCallRequest(_) => todo!(),
+      outbound_message::Message::FunctionCallRequest(e) => {
+        if let Some(ob) = self.observer(e.compilation_id) {
+          ob.function_call_request(e);
+        }
+      }
     }
   }
 }
@@ -1,19 +1,26 @@
-use std::{ffi::OsStr, path::Path};
+use std::{ffi::OsStr, io, path::Path, sync::Arc};
 
 use atty::Stream;
 
+#[cfg(feature = "watch")]
+use crate::WatchOptions;
 use crate::{
+  cancellation::Interrupt,
   channel::Channel,
-  host::ImporterRegistry,
-  host::{Host, LoggerRegistry},
+  connection::ConnectedGuard,
+  host::{FunctionRegistry, Host, ImporterRegistry, LoggerRegistry},
   protocol::{
     self,
     inbound_message::{
       compile_request::{Input, StringInput},
       CompileRequest,
     },
+    outbound_message::CompileResponse,
   },
-  CompileResult, Options, Result, StringOptions,
+  transport::Connector,
+  CompileResult, CompilerCommand, CompilerEvent, DiscoveryOptions, Exception,
+  ExceptionKind, Options, RestartPolicy, Result, SassImporter, StderrOptions,
+  StringOptions, Transport, VersionInfo,
 };
 #[cfg(feature = "legacy")]
 use crate::{
@@ -21,6 +28,10 @@ use crate::{
 };
 
 /// The sass-embedded compiler for rust host.
+///
+/// Compilations are multiplexed over one dart-sass-embedded process, so a
+/// single compiler can be shared across threads with [std::sync::Arc] and run
+/// many compilations at once.
 #[derive(Debug)]
 pub struct Embedded {
   channel: Channel,
@@ -30,63 +41,153 @@ impl Embedded {
   /// Creates a sass-embedded compiler and connects with the dart-sass-embedded.
   ///
   /// ```no_run
-  /// let mut sass = sass_embedded::Sass::new("path/to/sass_embedded").unwrap();
+  /// let sass = sass_embedded::Sass::new("path/to/sass_embedded").unwrap();
   /// ```
   pub fn new(exe_path: impl AsRef<OsStr>) -> Result<Self> {
+    Self::with_command(CompilerCommand::new(exe_path))
+  }
+
+  /// Creates a sass-embedded compiler that's started with `command`, e.g. to
+  /// pass arguments or set its environment.
+  ///
+  /// ```no_run
+  /// use sass_embedded::{CompilerCommand, Sass};
+  ///
+  /// let sass =
+  ///   Sass::with_command(CompilerCommand::new("path/to/sass").arg("--embedded"))
+  ///     .unwrap();
+  /// ```
+  pub fn with_command(command: CompilerCommand) -> Result<Self> {
+    Ok(Self {
+      channel: Channel::new(Connector::command(command))?,
+    })
+  }
+
+  /// Creates a sass-embedded compiler that talks to the compiler over the
+  /// [Transport] that `connect` opens, e.g. to a compiler daemon. `connect`
+  /// is called again whenever the compiler is restarted.
+  ///
+  /// [StderrOptions] only apply to compilers started with a
+  /// [CompilerCommand].
+  ///
+  /// ```no_run
+  /// use sass_embedded::{Sass, TcpTransport};
+  ///
+  /// let sass =
+  ///   Sass::with_transport(|| TcpTransport::connect("127.0.0.1:7000")).unwrap();
+  /// ```
+  pub fn with_transport<T: Transport + 'static>(
+    connect: impl Fn() -> io::Result<T> + Send + Sync + 'static,
+  ) -> Result<Self> {
     Ok(Self {
-      channel: Channel::new(exe_path)?,
+      channel: Channel::new(Connector::custom(connect))?,
     })
   }
 
+  /// Creates a sass-embedded compiler with the dart-sass-embedded executable
+  /// found in the default locations, see [DiscoveryOptions].
+  ///
+  /// ```no_run
+  /// let sass = sass_embedded::Sass::discover().unwrap();
+  /// ```
+  pub fn discover() -> Result<Self> {
+    Self::discover_with(&DiscoveryOptions::default())
+  }
+
+  /// Creates a sass-embedded compiler with the dart-sass-embedded executable
+  /// found in the locations of `options`.
+  ///
+  /// ```no_run
+  /// use sass_embedded::{DiscoveryOptions, Sass};
+  ///
+  /// let sass = Sass::discover_with(&DiscoveryOptions {
+  ///   cache_dir: Some("path/to/cache".into()),
+  ///   ..Default::default()
+  /// })
+  /// .unwrap();
+  /// ```
+  pub fn discover_with(options: &DiscoveryOptions) -> Result<Self> {
+    Self::new(options.find()?)
+  }
+
+  /// Sets how the compiler is restarted once its process crashes or it
+  /// reports a protocol error. Defaults to [RestartPolicy::default].
+  ///
+  /// ```no_run
+  /// use sass_embedded::{RestartPolicy, Sass};
+  ///
+  /// let sass = Sass::new("path/to/sass_embedded")
+  ///   .unwrap()
+  ///   .with_restart_policy(RestartPolicy {
+  ///     max_retries: 10,
+  ///     ..Default::default()
+  ///   });
+  /// ```
+  pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+    self.channel.supervisor().policy = policy;
+    self
+  }
+
+  /// Sets a hook that's called with every [CompilerEvent], e.g. to log when
+  /// the compiler crashes and is restarted.
+  ///
+  /// ```no_run
+  /// let sass = sass_embedded::Sass::new("path/to/sass_embedded")
+  ///   .unwrap()
+  ///   .on_compiler_event(|event| eprintln!("{:?}", event));
+  /// ```
+  pub fn on_compiler_event(
+    mut self,
+    hook: impl Fn(&CompilerEvent) + Send + Sync + 'static,
+  ) -> Self {
+    self.channel.supervisor().on_event = Some(Arc::new(hook));
+    self
+  }
+  /// Sets where the output the compiler writes to stderr goes. The last
+  /// lines are attached to the [Exception]s raised when the compiler process
+  /// fails, see [Exception::compiler_stderr].
+  ///
+  /// ```no_run
+  /// use sass_embedded::{Sass, StderrOptions, StderrSink};
+  ///
+  /// let sass = Sass::new("path/to/sass_embedded")
+  ///   .unwrap()
+  ///   .with_stderr(StderrOptions {
+  ///     sink: StderrSink::Callback(Box::new(|line| eprintln!("{}", line))),
+  ///     ..Default::default()
+  ///   });
+  /// ```
+  pub fn with_stderr(self, options: StderrOptions) -> Self {
+    self.channel.set_stderr_options(options);
+    self
+  }
+
+  /// The last lines the running compiler wrote to stderr.
+  pub fn stderr(&self) -> Vec<String> {
+    self.channel.stderr()
+  }
+
   /// Compiles the Sass file at path to CSS. If it succeeds it returns a [CompileResult],
   /// and if it fails it throws an [Exception].
   ///
   /// ```no_run
   /// use sass_embedded::{Sass, Options};
   ///
-  /// let mut sass = Sass::new("path/to/sass_embedded").unwrap();
+  /// let sass = Sass::new("path/to/sass_embedded").unwrap();
   /// let res = sass.compile("../styles/a.scss", Options::default()).unwrap();
   /// ```
   ///
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/modules#compile)
   pub fn compile(
-    &mut self,
+    &self,
     path: impl AsRef<Path>,
     options: Options,
   ) -> Result<CompileResult> {
-    let mut logger_registry = LoggerRegistry::default();
-    let mut importer_registry = ImporterRegistry::default();
-    let importers = importer_registry
-      .register_all(options.importers, options.load_paths)
-      .collect();
-    if let Some(l) = options.logger {
-      logger_registry.register(l);
-    }
-
-    let request = CompileRequest {
-      style: protocol::OutputStyle::from(options.style) as i32,
-      source_map: options.source_map,
-      alert_color: options
-        .alert_color
-        .unwrap_or_else(|| atty::is(Stream::Stdout)),
-      alert_ascii: options.alert_ascii,
-      verbose: options.verbose,
-      quiet_deps: options.quiet_deps,
-      source_map_include_sources: options.source_map_include_sources,
-      charset: options.charset,
-      importers,
-      input: Some(Input::Path(path.as_ref().to_str().unwrap().to_string())),
-      // id: set in compile_request
-      // global_functions: not implemented
-      ..Default::default()
-    };
-
-    let host = Host::new(importer_registry, logger_registry);
-    let conn = self.channel.connect(host)?;
-    let response = conn
-      .compile_request(request)
-      .map_err(|e| Box::new(e.into()))?;
-    CompileResult::try_from(response)
+    assert_sync(&options, None)?;
+    let interrupt = Interrupt::new(&options);
+    let output = options.source_map_output.clone();
+    let (request, host) = compile_request(path.as_ref(), options);
+    output.apply(compile(self.channel.connect(host)?, request, &interrupt)?)
   }
 
   /// Compiles a stylesheet whose contents is source to CSS. If it succeeds it returns
@@ -95,100 +196,270 @@ impl Embedded {
   /// ```no_run
   /// use sass_embedded::{Sass, StringOptions};
   ///
-  /// let mut sass = Sass::new("path/to/sass_embedded").unwrap();
+  /// let sass = Sass::new("path/to/sass_embedded").unwrap();
   /// let res = sass.compile_string("a {b: c}", StringOptions::default()).unwrap();
   /// ```
   ///
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/modules#compileString)
   pub fn compile_string(
-    &mut self,
+    &self,
     source: impl Into<String>,
     options: StringOptions,
   ) -> Result<CompileResult> {
-    let mut logger_registry = LoggerRegistry::default();
-    let mut importer_registry = ImporterRegistry::default();
-    let importers = importer_registry
-      .register_all(options.common.importers, options.common.load_paths)
+    assert_sync(&options.common, options.input_importer.as_ref())?;
+    let interrupt = Interrupt::new(&options.common);
+    let output = options.common.source_map_output.clone();
+    let (request, host) = compile_string_request(source.into(), options);
+    output.apply(compile(self.channel.connect(host)?, request, &interrupt)?)
+  }
+
+  /// Compiles the stylesheets at `entries`, then watches the files they
+  /// loaded and recompiles the entrypoints that are affected by a change,
+  /// until the process exits. Uses [WatchOptions::default], see
+  /// [Embedded::watch_with].
+  ///
+  /// `options` creates the [Options] of each compilation of an entrypoint,
+  /// and `callback` is called with the result of each compilation, including
+  /// the failed ones.
+  ///
+  /// ```no_run
+  /// use sass_embedded::{Options, Sass};
+  ///
+  /// let sass = Sass::new("path/to/sass_embedded").unwrap();
+  /// sass
+  ///   .watch(
+  ///     ["styles/a.scss", "styles/b.scss"],
+  ///     |_| Options::default(),
+  ///     |entry, result| match result {
+  ///       Ok(result) => println!("{}: {}", entry.display(), result.css),
+  ///       Err(e) => eprintln!("{}", e),
+  ///     },
+  ///   )
+  ///   .unwrap();
+  /// ```
+  #[cfg(feature = "watch")]
+  pub fn watch(
+    &self,
+    entries: impl IntoIterator<Item = impl AsRef<Path>>,
+    options: impl FnMut(&Path) -> Options,
+    callback: impl FnMut(&Path, Result<CompileResult>),
+  ) -> Result<()> {
+    self.watch_with(entries, options, callback, &WatchOptions::default())
+  }
+
+  /// Like [Embedded::watch], but debounces changes and stops according to
+  /// `watch_options`.
+  ///
+  /// Returns once [WatchOptions::cancellation_token] is cancelled, or with
+  /// an error if the files can't be watched.
+  #[cfg(feature = "watch")]
+  pub fn watch_with(
+    &self,
+    entries: impl IntoIterator<Item = impl AsRef<Path>>,
+    mut options: impl FnMut(&Path) -> Options,
+    callback: impl FnMut(&Path, Result<CompileResult>),
+    watch_options: &WatchOptions,
+  ) -> Result<()> {
+    let entries: Vec<_> = entries
+      .into_iter()
+      .map(|entry| entry.as_ref().to_path_buf())
       .collect();
-    if let Some(l) = options.common.logger {
-      logger_registry.register(l);
-    }
+    crate::watch::watch(
+      &entries,
+      |entry| self.compile(entry, options(entry)),
+      callback,
+      watch_options,
+    )
+  }
+
+  /// Gets the version of the sass-embedded compiler.
+  pub fn info(&self) -> Result<String> {
+    info(self.channel.connect(Host::default())?)
+  }
+
+  /// Gets the versions of the compiler and of the embedded protocol it
+  /// speaks, which are only requested once per compiler process.
+  pub fn version(&self) -> Result<VersionInfo> {
+    version(self.channel.connect(Host::default())?)
+  }
+}
+
+/// Sends `request` over `conn` and waits for its result, unless `interrupt`
+/// stops the wait.
+pub(crate) fn compile(
+  conn: ConnectedGuard,
+  request: CompileRequest,
+  interrupt: &Interrupt,
+) -> Result<CompileResult> {
+  let response = conn.compile_request(request, interrupt)?;
+  compile_result(response, conn.host())
+}
 
-    #[cfg(feature = "legacy")]
-    let importer = if let Some(input_importer) = options.input_importer {
-      Some(importer_registry.register(input_importer))
-    } else if matches!(&options.url, Some(u) if u.to_string() == LEGACY_IMPORTER_PROTOCOL)
-    {
-      Some(compile_request::Importer {
-        importer: Some(compile_request::importer::Importer::Path(
-          std::env::current_dir()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(),
-        )),
-      })
+/// Converts the compiler's response to a [CompileResult], blaming a failure
+/// on the importers if one of them returned an error.
+pub(crate) fn compile_result(
+  response: CompileResponse,
+  host: &Host,
+) -> Result<CompileResult> {
+  CompileResult::try_from(response).map_err(|e| {
+    let e = e.with_compiler_dir(host.compiler_dir());
+    if host.importer_failed() && e.kind() == ExceptionKind::CompileFailure {
+      Box::new(e.with_kind(ExceptionKind::ImporterFailure))
     } else {
-      None
-    };
-
-    #[cfg(feature = "legacy")]
-    let url = options
-      .url
-      .map(|url| url.to_string())
-      .filter(|url| url != LEGACY_IMPORTER_PROTOCOL)
-      .unwrap_or_default();
-
-    #[cfg(not(feature = "legacy"))]
-    let importer = options
-      .input_importer
-      .map(|i| importer_registry.register(i));
-
-    #[cfg(not(feature = "legacy"))]
-    let url = options.url.map(|url| url.to_string()).unwrap_or_default();
-
-    let request = CompileRequest {
-      style: protocol::OutputStyle::from(options.common.style) as i32,
-      source_map: options.common.source_map,
-      alert_color: options
-        .common
-        .alert_color
-        .unwrap_or_else(|| atty::is(Stream::Stdout)),
-      alert_ascii: options.common.alert_ascii,
-      verbose: options.common.verbose,
-      quiet_deps: options.common.quiet_deps,
-      source_map_include_sources: options.common.source_map_include_sources,
-      charset: options.common.charset,
-      importers,
-      input: Some(Input::String(StringInput {
-        source: source.into(),
-        url,
-        syntax: protocol::Syntax::from(options.syntax) as i32,
-        importer,
-      })),
-      // id: set in compile_request
-      // global_functions: not implemented
-      ..Default::default()
-    };
-
-    let host = Host::new(importer_registry, logger_registry);
-    let conn = self.channel.connect(host)?;
-    let response = conn
-      .compile_request(request)
-      .map_err(|e| Box::new(e.into()))?;
-    CompileResult::try_from(response)
+      Box::new(e)
+    }
+  })
+}
+
+/// Requests the version of the compiler over `conn`.
+pub(crate) fn info(conn: ConnectedGuard) -> Result<String> {
+  let response = conn.version_request()?;
+  Ok(format!(
+    "sass-embedded\t#{}",
+    response.implementation_version
+  ))
+}
+
+/// Requests the versions of the compiler over `conn`, unless they're known.
+pub(crate) fn version(conn: ConnectedGuard) -> Result<VersionInfo> {
+  conn.version()
+}
+
+/// Returns an error if `options` contains async importers or loggers, which
+/// can only be used with [AsyncEmbedded].
+///
+/// [AsyncEmbedded]: crate::AsyncEmbedded
+pub(crate) fn assert_sync(
+  options: &Options,
+  input_importer: Option<&SassImporter>,
+) -> Result<()> {
+  #[cfg(feature = "async")]
+  let has_async_logger = options.async_logger.is_some();
+  #[cfg(not(feature = "async"))]
+  let has_async_logger = false;
+  if has_async_logger
+    || options
+      .importers
+      .iter()
+      .chain(input_importer)
+      .any(SassImporter::is_async)
+  {
+    return Err(
+      Exception::new(
+        "Async importers and loggers can only be used with AsyncEmbedded.",
+      )
+      .into(),
+    );
   }
+  Ok(())
+}
 
-  /// Gets the version of the sass-embedded compiler.
-  pub fn info(&mut self) -> Result<String> {
-    let logger_registry = LoggerRegistry::default();
-    let importer_registry = ImporterRegistry::default();
-    let host = Host::new(importer_registry, logger_registry);
-    let conn = self.channel.connect(host)?;
-    let response = conn.version_request().map_err(|e| Box::new(e.into()))?;
-    Ok(format!(
-      "sass-embedded\t#{}",
-      response.implementation_version
-    ))
+/// Registers the importers, functions and logger of `options` and returns
+/// a [CompileRequest] without an input, along with the registries.
+fn base_request(
+  options: Options,
+  importer_registry: &mut ImporterRegistry,
+) -> (CompileRequest, LoggerRegistry, FunctionRegistry) {
+  let mut logger_registry = LoggerRegistry::default();
+  let mut function_registry = FunctionRegistry::default();
+  let importers = importer_registry
+    .register_all(options.importers, options.load_paths)
+    .collect();
+  let global_functions =
+    function_registry.register_all(options.functions).collect();
+  if let Some(l) = options.logger {
+    logger_registry.register(l);
+  }
+  #[cfg(feature = "async")]
+  if let Some(l) = options.async_logger {
+    logger_registry.register_async(l);
   }
+
+  let request = CompileRequest {
+    style: protocol::OutputStyle::from(options.style) as i32,
+    // A sourceMappingURL comment needs a source map to point to.
+    source_map: options.source_map || options.source_map_output.url.is_some(),
+    alert_color: options
+      .alert_color
+      .unwrap_or_else(|| atty::is(Stream::Stdout)),
+    alert_ascii: options.alert_ascii,
+    verbose: options.verbose,
+    quiet_deps: options.quiet_deps,
+    source_map_include_sources: options.source_map_include_sources,
+    charset: options.charset,
+    importers,
+    global_functions,
+    // id: set in compile_request
+    ..Default::default()
+  };
+  (request, logger_registry, function_registry)
+}
+
+/// Creates the [CompileRequest] and [Host] for compiling the Sass file at
+/// `path`.
+pub(crate) fn compile_request(
+  path: &Path,
+  options: Options,
+) -> (CompileRequest, Host) {
+  let mut importer_registry = ImporterRegistry::default();
+  let (mut request, logger_registry, function_registry) =
+    base_request(options, &mut importer_registry);
+  // Absolute, since the compiler may run in another working directory.
+  let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+  request.input = Some(Input::Path(path.to_str().unwrap().to_string()));
+  let host = Host::new(importer_registry, logger_registry, function_registry);
+  (request, host)
+}
+
+/// Creates the [CompileRequest] and [Host] for compiling the stylesheet
+/// `source`.
+pub(crate) fn compile_string_request(
+  source: String,
+  options: StringOptions,
+) -> (CompileRequest, Host) {
+  let mut importer_registry = ImporterRegistry::default();
+  let (mut request, logger_registry, function_registry) =
+    base_request(options.common, &mut importer_registry);
+
+  #[cfg(feature = "legacy")]
+  let importer = if let Some(input_importer) = options.input_importer {
+    Some(importer_registry.register(input_importer))
+  } else if matches!(&options.url, Some(u) if u.to_string() == LEGACY_IMPORTER_PROTOCOL)
+  {
+    Some(compile_request::Importer {
+      importer: Some(compile_request::importer::Importer::Path(
+        std::env::current_dir()
+          .unwrap()
+          .to_str()
+          .unwrap()
+          .to_string(),
+      )),
+    })
+  } else {
+    None
+  };
+
+  #[cfg(feature = "legacy")]
+  let url = options
+    .url
+    .map(|url| url.to_string())
+    .filter(|url| url != LEGACY_IMPORTER_PROTOCOL)
+    .unwrap_or_default();
+
+  #[cfg(not(feature = "legacy"))]
+  let importer = options
+    .input_importer
+    .map(|i| importer_registry.register(i));
+
+  #[cfg(not(feature = "legacy"))]
+  let url = options.url.map(|url| url.to_string()).unwrap_or_default();
+
+  request.input = Some(Input::String(StringInput {
+    source,
+    url,
+    syntax: protocol::Syntax::from(options.syntax) as i32,
+    importer,
+  }));
+  let host = Host::new(importer_registry, logger_registry, function_registry);
+  (request, host)
 }
@@ -1,28 +1,89 @@
-use std::fmt;
+use std::{
+  fmt,
+  path::{Path, PathBuf},
+};
 
 use crate::{
   protocol::{
-    outbound_message::compile_response::CompileFailure, ProtocolError,
+    self, outbound_message::compile_response::CompileFailure, ProtocolError,
   },
-  SourceSpan,
+  SourceSpan, StackFrame,
 };
 
 /// An alias for [std::result::Result<T, Exception>].
 pub type Result<T> = std::result::Result<T, Box<Exception>>;
 
+/// What caused an [Exception], which tells a mistake in the Sass source or
+/// an importer apart from a failure of the compiler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceptionKind {
+  /// The Sass source is invalid, e.g. it has a syntax error or uses an
+  /// undefined variable.
+  CompileFailure,
+  /// The compilation failed because an importer returned an error.
+  ImporterFailure,
+  /// The compiler reported a violation of the embedded protocol.
+  Protocol(ProtocolErrorType),
+  /// Communicating with the compiler process failed, e.g. because it exited.
+  Io,
+  /// The compiler process couldn't be started, or restarted.
+  Spawn,
+  /// The compilation took longer than [Options::timeout](crate::Options::timeout).
+  Timeout,
+  /// The compilation was cancelled with a
+  /// [CancellationToken](crate::CancellationToken).
+  Cancelled,
+  /// The compiler's version couldn't be parsed, or it speaks an incompatible
+  /// version of the embedded protocol.
+  Version,
+  /// Any other exception, e.g. one thrown by a custom function.
+  Other,
+}
+
+/// The type of a protocol error reported by the compiler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolErrorType {
+  /// A message couldn't be decoded.
+  Parse,
+  /// A message violated a documented restriction, such as not providing a
+  /// mandatory field.
+  Params,
+  /// Something unexpected went wrong within the compiler.
+  Internal,
+}
+
+impl From<i32> for ProtocolErrorType {
+  fn from(t: i32) -> Self {
+    match protocol::ProtocolErrorType::from_i32(t) {
+      Some(protocol::ProtocolErrorType::Parse) => Self::Parse,
+      Some(protocol::ProtocolErrorType::Params) => Self::Params,
+      Some(protocol::ProtocolErrorType::Internal) | None => Self::Internal,
+    }
+  }
+}
+
 /// An exception for this crate, thrown because a Sass compilation failed or `io::Error`.
 ///
 /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Exception)
 #[derive(Debug)]
 pub struct Exception {
+  kind: ExceptionKind,
   message: String,
   sass_message: Option<String>,
   sass_stack: Option<String>,
   span: Option<SourceSpan>,
+  compiler_stderr: Vec<String>,
+  /// The working directory of the compiler, if it's not ours.
+  compiler_dir: Option<PathBuf>,
   source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
 }
 
 impl Exception {
+  /// What caused the exception.
+  pub fn kind(&self) -> ExceptionKind {
+    self.kind
+  }
+
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Exception#message)
   pub fn message(&self) -> &str {
     &self.message
@@ -38,13 +99,37 @@ impl Exception {
     self.sass_stack.as_deref()
   }
 
+  /// The frames of [Exception::sass_stack].
+  pub fn stack_frames(&self) -> Vec<StackFrame> {
+    self
+      .sass_stack
+      .as_deref()
+      .map(|stack| {
+        StackFrame::parse_trace_in(stack, self.compiler_dir.as_deref())
+      })
+      .unwrap_or_default()
+  }
+
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Exception#span)
   pub fn span(&self) -> Option<&SourceSpan> {
     self.span.as_ref()
   }
+
+  /// The last lines the compiler process wrote to stderr, if this exception
+  /// was raised because the process failed.
+  pub fn compiler_stderr(&self) -> &[String] {
+    &self.compiler_stderr
+  }
 }
 
-impl std::error::Error for Exception {}
+impl std::error::Error for Exception {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    self
+      .source
+      .as_deref()
+      .map(|e| e as &(dyn std::error::Error + 'static))
+  }
+}
 
 impl fmt::Display for Exception {
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Exception#toString)
@@ -56,10 +141,13 @@ impl fmt::Display for Exception {
 impl From<CompileFailure> for Exception {
   fn from(failure: CompileFailure) -> Self {
     Self {
+      kind: ExceptionKind::CompileFailure,
       message: failure.formatted,
       sass_message: Some(failure.message),
       sass_stack: Some(failure.stack_trace),
       span: failure.span.map(|span| span.into()),
+      compiler_stderr: Vec::new(),
+      compiler_dir: None,
       source: None,
     }
   }
@@ -67,7 +155,7 @@ impl From<CompileFailure> for Exception {
 
 impl From<ProtocolError> for Exception {
   fn from(e: ProtocolError) -> Self {
-    Self::new(e.message)
+    Self::new(e.message).with_kind(ExceptionKind::Protocol(e.r#type.into()))
   }
 }
 
@@ -75,14 +163,53 @@ impl Exception {
   /// Creates a new Exception with the given message.
   pub fn new(message: impl Into<String>) -> Self {
     Self {
+      kind: ExceptionKind::Other,
       message: message.into(),
       sass_message: None,
       sass_stack: None,
       span: None,
+      compiler_stderr: Vec::new(),
+      compiler_dir: None,
       source: None,
     }
   }
 
+  /// Creates a new Exception for an invalid argument of a custom function,
+  /// prefixing the message with the argument's name if it's given.
+  pub fn argument(message: impl Into<String>, name: Option<&str>) -> Self {
+    let message = message.into();
+    match name {
+      Some(name) => Self::new(format!("${}: {}", name, message)),
+      None => Self::new(message),
+    }
+  }
+
+  /// Creates a new Exception for a failure to communicate with the compiler,
+  /// with `error` as its source.
+  pub(crate) fn io(context: &str, error: std::io::Error) -> Self {
+    Self::new(format!("{}: {}", context, error))
+      .with_kind(ExceptionKind::Io)
+      .set_source(error)
+  }
+
+  pub(crate) fn with_kind(mut self, kind: ExceptionKind) -> Self {
+    self.kind = kind;
+    self
+  }
+
+  /// Attaches the last lines the compiler process wrote to stderr.
+  pub(crate) fn with_compiler_stderr(mut self, lines: Vec<String>) -> Self {
+    self.compiler_stderr = lines;
+    self
+  }
+
+  /// Sets the working directory of the compiler that reported the
+  /// exception, which its stack trace is relative to.
+  pub(crate) fn with_compiler_dir(mut self, dir: Option<&Path>) -> Self {
+    self.compiler_dir = dir.map(Path::to_path_buf);
+    self
+  }
+
   /// Sets the source error of the exception.
   pub fn set_source(
     mut self,
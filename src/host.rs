@@ -1,13 +1,21 @@
+mod function_registry;
 mod importer_registry;
 mod logger_registry;
 
+pub use function_registry::FunctionRegistry;
 pub use importer_registry::ImporterRegistry;
 pub use logger_registry::LoggerRegistry;
 
+use std::path::{Path, PathBuf};
+
 use crate::protocol::{
-  inbound_message::{CanonicalizeResponse, FileImportResponse, ImportResponse},
+  inbound_message::{
+    CanonicalizeResponse, FileImportResponse, FunctionCallResponse,
+    ImportResponse,
+  },
   outbound_message::{
-    CanonicalizeRequest, FileImportRequest, ImportRequest, LogEvent,
+    CanonicalizeRequest, FileImportRequest, FunctionCallRequest, ImportRequest,
+    LogEvent,
   },
 };
 
@@ -15,11 +23,38 @@ use crate::protocol::{
 pub struct Host {
   importer: ImporterRegistry,
   logger: LoggerRegistry,
+  function: FunctionRegistry,
+  /// The working directory of the compiler, if it's not ours.
+  compiler_dir: Option<PathBuf>,
 }
 
 impl Host {
-  pub fn new(importer: ImporterRegistry, logger: LoggerRegistry) -> Self {
-    Self { importer, logger }
+  pub fn new(
+    importer: ImporterRegistry,
+    logger: LoggerRegistry,
+    function: FunctionRegistry,
+  ) -> Self {
+    Self {
+      importer,
+      logger,
+      function,
+      compiler_dir: None,
+    }
+  }
+
+  /// Sets the working directory of the compiler the compilation runs on,
+  /// which the paths in its stack traces are relative to.
+  pub fn set_compiler_dir(&mut self, dir: Option<&Path>) {
+    self.compiler_dir = dir.map(Path::to_path_buf);
+  }
+
+  pub fn compiler_dir(&self) -> Option<&Path> {
+    self.compiler_dir.as_deref()
+  }
+
+  /// Whether an importer returned an error during the compilation.
+  pub fn importer_failed(&self) -> bool {
+    self.importer.has_failed()
   }
 
   pub fn canonicalize(
@@ -37,7 +72,40 @@ impl Host {
     self.importer.file_import(request)
   }
 
+  pub fn function_call(
+    &self,
+    request: &FunctionCallRequest,
+  ) -> FunctionCallResponse {
+    self.function.call(request)
+  }
+
   pub fn log(&self, event: LogEvent) {
-    self.logger.log(event);
+    self.logger.log(event, self.compiler_dir());
+  }
+
+  #[cfg(feature = "async")]
+  pub async fn canonicalize_async(
+    &self,
+    request: &CanonicalizeRequest,
+  ) -> CanonicalizeResponse {
+    self.importer.canonicalize_async(request).await
+  }
+
+  #[cfg(feature = "async")]
+  pub async fn import_async(&self, request: &ImportRequest) -> ImportResponse {
+    self.importer.import_async(request).await
+  }
+
+  #[cfg(feature = "async")]
+  pub async fn file_import_async(
+    &self,
+    request: &FileImportRequest,
+  ) -> FileImportResponse {
+    self.importer.file_import_async(request).await
+  }
+
+  #[cfg(feature = "async")]
+  pub async fn log_async(&self, event: LogEvent) {
+    self.logger.log_async(event, self.compiler_dir()).await;
   }
 }
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, sync::Arc};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+use crate::{
+  api::{BoxCustomFunction, CustomFunction},
+  protocol::{
+    inbound_message::{function_call_response, FunctionCallResponse},
+    outbound_message::{
+      function_call_request::Identifier, FunctionCallRequest,
+    },
+  },
+  Exception, Result, Value,
+};
+
+/// A registry of custom functions defined in the host that can be invoked by
+/// the compiler.
+#[derive(Debug, Default)]
+pub struct FunctionRegistry {
+  /// A map from global function names to their corresponding functions.
+  functions_by_name: FxHashMap<String, BoxCustomFunction>,
+  /// A map from host function IDs to their corresponding functions. Host
+  /// functions are registered when they are returned to the compiler.
+  functions_by_id: Mutex<FxHashMap<u32, Arc<dyn CustomFunction>>>,
+}
+
+impl FunctionRegistry {
+  /// Registers the global functions and returns their signatures.
+  pub fn register_all(
+    &mut self,
+    functions: HashMap<String, BoxCustomFunction>,
+  ) -> impl Iterator<Item = String> + '_ {
+    functions.into_iter().map(|(signature, function)| {
+      let name = signature
+        .split_once('(')
+        .map(|(name, _)| name)
+        .unwrap_or(&signature)
+        .trim()
+        .to_string();
+      self.functions_by_name.insert(name, function);
+      signature
+    })
+  }
+
+  /// Handles a function call request.
+  pub fn call(&self, request: &FunctionCallRequest) -> FunctionCallResponse {
+    match self.execute(request) {
+      Ok((value, accessed_argument_lists)) => FunctionCallResponse {
+        id: request.id,
+        accessed_argument_lists,
+        result: Some(function_call_response::Result::Success(value.into())),
+      },
+      Err(e) => FunctionCallResponse {
+        id: request.id,
+        accessed_argument_lists: Vec::new(),
+        result: Some(function_call_response::Result::Error(e.to_string())),
+      },
+    }
+  }
+
+  fn execute(
+    &self,
+    request: &FunctionCallRequest,
+  ) -> Result<(Value, Vec<u32>)> {
+    let args = request
+      .arguments
+      .iter()
+      .cloned()
+      .map(Value::try_from)
+      .collect::<Result<Vec<_>>>()?;
+    let value = match &request.identifier {
+      Some(Identifier::Name(name)) => self
+        .functions_by_name
+        .get(name)
+        .ok_or_else(|| Exception::new(format!("Unknown function {}.", name)))?
+        .call(&args)?,
+      Some(Identifier::FunctionId(id)) => {
+        let function = self
+          .functions_by_id
+          .lock()
+          .get(id)
+          .cloned()
+          .ok_or_else(|| {
+            Exception::new(format!("Unknown function ID {}.", id))
+          })?;
+        function.call(&args)?
+      }
+      None => {
+        return Err(
+          Exception::new("FunctionCallRequest.identifier is mandatory").into(),
+        )
+      }
+    };
+
+    let mut host_functions = Vec::new();
+    value.host_functions(&mut host_functions);
+    let mut functions_by_id = self.functions_by_id.lock();
+    for function in host_functions {
+      if let Some(callback) = function.callback() {
+        functions_by_id.insert(function.id(), callback);
+      }
+    }
+
+    let mut accessed_argument_lists = Vec::new();
+    for arg in &args {
+      arg.accessed_argument_lists(&mut accessed_argument_lists);
+    }
+    accessed_argument_lists.sort_unstable();
+    accessed_argument_lists.dedup();
+    Ok((value, accessed_argument_lists))
+  }
+}
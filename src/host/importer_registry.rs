@@ -1,9 +1,17 @@
-use std::path::PathBuf;
+use std::{
+  fmt::Debug,
+  path::PathBuf,
+  sync::atomic::{AtomicBool, Ordering},
+};
 
 use rustc_hash::FxHashMap;
 
+#[cfg(feature = "async")]
+use crate::api::{BoxAsyncFileImporter, BoxAsyncImporter};
 use crate::{
-  api::{FileImporter, Importer, ImporterOptions, SassImporter},
+  api::{
+    FileImporter, Importer, ImporterOptions, ImporterResult, SassImporter,
+  },
   protocol::{
     self,
     inbound_message::{
@@ -13,7 +21,7 @@ use crate::{
     },
     outbound_message::{CanonicalizeRequest, FileImportRequest, ImportRequest},
   },
-  Url,
+  Result, Url,
 };
 
 /// A registry of importers defined in the host that can be invoked by the
@@ -26,6 +34,14 @@ pub struct ImporterRegistry {
   importers_by_id: FxHashMap<u32, Box<dyn Importer>>,
   /// A map from file importer IDs to their corresponding importers.
   file_importers_by_id: FxHashMap<u32, Box<dyn FileImporter>>,
+  /// A map from importer IDs to their corresponding async importers.
+  #[cfg(feature = "async")]
+  async_importers_by_id: FxHashMap<u32, BoxAsyncImporter>,
+  /// A map from file importer IDs to their corresponding async importers.
+  #[cfg(feature = "async")]
+  async_file_importers_by_id: FxHashMap<u32, BoxAsyncFileImporter>,
+  /// Whether an importer returned an error.
+  failed: AtomicBool,
 }
 
 impl ImporterRegistry {
@@ -52,6 +68,8 @@ impl ImporterRegistry {
     load_paths: Vec<PathBuf>,
   ) -> impl Iterator<Item = compile_request::Importer> + '_ {
     load_paths.into_iter().map(|p| {
+      // Absolute, since the compiler may run in another working directory.
+      let p = std::path::absolute(&p).unwrap_or(p);
       let i = compile_request::importer::Importer::Path(
         p.to_str().unwrap().to_string(),
       );
@@ -73,87 +91,188 @@ impl ImporterRegistry {
         self.file_importers_by_id.insert(self.id, i);
         compile_request::importer::Importer::FileImporterId(self.id)
       }
+      #[cfg(feature = "async")]
+      SassImporter::AsyncImporter(i) => {
+        self.async_importers_by_id.insert(self.id, i);
+        compile_request::importer::Importer::ImporterId(self.id)
+      }
+      #[cfg(feature = "async")]
+      SassImporter::AsyncFileImporter(i) => {
+        self.async_file_importers_by_id.insert(self.id, i);
+        compile_request::importer::Importer::FileImporterId(self.id)
+      }
     };
     self.id += 1;
     compile_request::Importer { importer: Some(i) }
   }
 
+  /// Whether an importer returned an error during the compilation.
+  pub fn has_failed(&self) -> bool {
+    self.failed.load(Ordering::Relaxed)
+  }
+
+  fn record<T>(&self, result: Result<T>) -> Result<T> {
+    if result.is_err() {
+      self.failed.store(true, Ordering::Relaxed);
+    }
+    result
+  }
+
   /// Handles a canonicalization request.
   pub fn canonicalize(
     &self,
     request: &CanonicalizeRequest,
   ) -> CanonicalizeResponse {
     let importer = self.importers_by_id.get(&request.importer_id).unwrap();
-    match importer.canonicalize(
-      &request.url,
-      &ImporterOptions {
-        from_import: request.from_import,
-      },
-    ) {
-      Ok(url) => CanonicalizeResponse {
-        id: request.id,
-        result: url
-          .map(|url| canonicalize_response::Result::Url(url.to_string())),
-      },
-      Err(e) => CanonicalizeResponse {
-        id: request.id,
-        result: Some(canonicalize_response::Result::Error(e.to_string())),
-      },
-    }
+    canonicalize_response(
+      request,
+      self.record(
+        importer.canonicalize(&request.url, &importer_options(request)),
+      ),
+    )
   }
 
   /// Handles an import request.
   pub fn import(&self, request: &ImportRequest) -> ImportResponse {
     let importer = self.importers_by_id.get(&request.importer_id).unwrap();
-    match importer.load(&Url::parse(&request.url).unwrap()) {
-      Ok(result) => ImportResponse {
-        id: request.id,
-        result: if let Some(result) = result {
-          Some(import_response::Result::Success(ImportSuccess {
-            contents: result.contents,
-            syntax: protocol::Syntax::from(result.syntax) as i32,
-            source_map_url: result
-              .source_map_url
-              .map(|url| url.to_string())
-              .unwrap_or_default(),
-          }))
-        } else {
-          None
-        },
-      },
-      Err(e) => ImportResponse {
-        id: request.id,
-        result: Some(import_response::Result::Error(e.to_string())),
-      },
-    }
+    let url = Url::parse(&request.url).unwrap();
+    import_response(request, self.record(importer.load(&url)))
   }
 
   /// Handles a file import request.
   pub fn file_import(&self, request: &FileImportRequest) -> FileImportResponse {
     let importer = self.file_importers_by_id.get(&request.importer_id).unwrap();
-    match importer.find_file_url(
-      &request.url,
-      &ImporterOptions {
-        from_import: request.from_import,
-      },
-    ) {
-      Ok(url) => FileImportResponse {
-        id: request.id,
-        result: url.map(|url| {
-          if url.scheme() != "file" {
-            file_import_response::Result::Error(format!(
-              "FileImporter {:?} returned non-file: URL {} for URL {}.",
-              importer, url, request.url
-            ))
-          } else {
-            file_import_response::Result::FileUrl(url.to_string())
-          }
-        }),
-      },
-      Err(e) => FileImportResponse {
-        id: request.id,
-        result: Some(file_import_response::Result::Error(e.to_string())),
+    file_import_response(
+      request,
+      self.record(
+        importer.find_file_url(&request.url, &file_importer_options(request)),
+      ),
+      importer,
+    )
+  }
+
+  /// Handles a canonicalization request with either an async or a sync
+  /// importer.
+  #[cfg(feature = "async")]
+  pub async fn canonicalize_async(
+    &self,
+    request: &CanonicalizeRequest,
+  ) -> CanonicalizeResponse {
+    let options = importer_options(request);
+    let result = match self.async_importers_by_id.get(&request.importer_id) {
+      Some(importer) => importer.canonicalize(&request.url, &options).await,
+      None => return self.canonicalize(request),
+    };
+    canonicalize_response(request, self.record(result))
+  }
+
+  /// Handles an import request with either an async or a sync importer.
+  #[cfg(feature = "async")]
+  pub async fn import_async(&self, request: &ImportRequest) -> ImportResponse {
+    let url = Url::parse(&request.url).unwrap();
+    let result = match self.async_importers_by_id.get(&request.importer_id) {
+      Some(importer) => importer.load(&url).await,
+      None => return self.import(request),
+    };
+    import_response(request, self.record(result))
+  }
+
+  /// Handles a file import request with either an async or a sync importer.
+  #[cfg(feature = "async")]
+  pub async fn file_import_async(
+    &self,
+    request: &FileImportRequest,
+  ) -> FileImportResponse {
+    let importer =
+      match self.async_file_importers_by_id.get(&request.importer_id) {
+        Some(importer) => importer,
+        None => return self.file_import(request),
+      };
+    let result = importer
+      .find_file_url(&request.url, &file_importer_options(request))
+      .await;
+    file_import_response(request, self.record(result), importer)
+  }
+}
+
+fn importer_options(request: &CanonicalizeRequest) -> ImporterOptions {
+  ImporterOptions {
+    from_import: request.from_import,
+  }
+}
+
+fn file_importer_options(request: &FileImportRequest) -> ImporterOptions {
+  ImporterOptions {
+    from_import: request.from_import,
+  }
+}
+
+fn canonicalize_response(
+  request: &CanonicalizeRequest,
+  result: Result<Option<Url>>,
+) -> CanonicalizeResponse {
+  match result {
+    Ok(url) => CanonicalizeResponse {
+      id: request.id,
+      result: url
+        .map(|url| canonicalize_response::Result::Url(url.to_string())),
+    },
+    Err(e) => CanonicalizeResponse {
+      id: request.id,
+      result: Some(canonicalize_response::Result::Error(e.to_string())),
+    },
+  }
+}
+
+fn import_response(
+  request: &ImportRequest,
+  result: Result<Option<ImporterResult>>,
+) -> ImportResponse {
+  match result {
+    Ok(result) => ImportResponse {
+      id: request.id,
+      result: if let Some(result) = result {
+        Some(import_response::Result::Success(ImportSuccess {
+          contents: result.contents,
+          syntax: protocol::Syntax::from(result.syntax) as i32,
+          source_map_url: result
+            .source_map_url
+            .map(|url| url.to_string())
+            .unwrap_or_default(),
+        }))
+      } else {
+        None
       },
-    }
+    },
+    Err(e) => ImportResponse {
+      id: request.id,
+      result: Some(import_response::Result::Error(e.to_string())),
+    },
+  }
+}
+
+fn file_import_response(
+  request: &FileImportRequest,
+  result: Result<Option<Url>>,
+  importer: &dyn Debug,
+) -> FileImportResponse {
+  match result {
+    Ok(url) => FileImportResponse {
+      id: request.id,
+      result: url.map(|url| {
+        if url.scheme() != "file" {
+          file_import_response::Result::Error(format!(
+            "FileImporter {:?} returned non-file: URL {} for URL {}.",
+            importer, url, request.url
+          ))
+        } else {
+          file_import_response::Result::FileUrl(url.to_string())
+        }
+      }),
+    },
+    Err(e) => FileImportResponse {
+      id: request.id,
+      result: Some(file_import_response::Result::Error(e.to_string())),
+    },
   }
 }
@@ -1,3 +1,7 @@
+use std::path::Path;
+
+#[cfg(feature = "async")]
+use crate::BoxAsyncLogger;
 use crate::{
   protocol::{outbound_message::LogEvent, LogEventType},
   BoxLogger, LoggerDebugOptions, LoggerWarnOptions,
@@ -6,6 +10,45 @@ use crate::{
 #[derive(Debug, Default)]
 pub struct LoggerRegistry {
   logger: Option<BoxLogger>,
+  #[cfg(feature = "async")]
+  async_logger: Option<BoxAsyncLogger>,
+}
+
+/// A [LogEvent] converted to the arguments of a logger.
+enum Log {
+  Debug(String, LoggerDebugOptions),
+  Warn(String, LoggerWarnOptions),
+}
+
+impl Log {
+  /// Converts an event of a compiler running in `compiler_dir`.
+  fn new(event: LogEvent, compiler_dir: Option<&Path>) -> Self {
+    if event.r#type() == LogEventType::Debug {
+      Log::Debug(
+        event.message,
+        LoggerDebugOptions {
+          span: event.span.map(|span| span.into()),
+          formatted: event.formatted,
+        },
+      )
+    } else {
+      let deprecation = event.r#type() == LogEventType::DeprecationWarning;
+      Log::Warn(
+        event.message,
+        LoggerWarnOptions {
+          span: event.span.map(|span| span.into()),
+          deprecation,
+          stack: if event.stack_trace.is_empty() {
+            None
+          } else {
+            Some(event.stack_trace)
+          },
+          formatted: event.formatted,
+          compiler_dir: compiler_dir.map(Path::to_path_buf),
+        },
+      )
+    }
+  }
 }
 
 impl LoggerRegistry {
@@ -13,34 +56,33 @@ impl LoggerRegistry {
     self.logger = Some(logger);
   }
 
-  pub fn log(&self, event: LogEvent) {
+  #[cfg(feature = "async")]
+  pub fn register_async(&mut self, logger: BoxAsyncLogger) {
+    self.async_logger = Some(logger);
+  }
+
+  pub fn log(&self, event: LogEvent, compiler_dir: Option<&Path>) {
     if let Some(logger) = &self.logger {
-      if event.r#type() == LogEventType::Debug {
-        logger.debug(
-          &event.message,
-          &LoggerDebugOptions {
-            span: event.span.map(|span| span.into()),
-            formatted: event.formatted,
-          },
-        );
-      } else {
-        let deprecation = event.r#type() == LogEventType::DeprecationWarning;
-        logger.warn(
-          &event.message,
-          &LoggerWarnOptions {
-            span: event.span.map(|span| span.into()),
-            deprecation,
-            stack: if event.stack_trace.is_empty() {
-              None
-            } else {
-              Some(event.stack_trace)
-            },
-            formatted: event.formatted,
-          },
-        );
+      match Log::new(event, compiler_dir) {
+        Log::Debug(message, options) => logger.debug(&message, &options),
+        Log::Warn(message, options) => logger.warn(&message, &options),
       }
     } else {
       eprintln!("{}", event.formatted);
     }
   }
+
+  /// Logs the event with the async logger if there is one, or the sync
+  /// logger otherwise.
+  #[cfg(feature = "async")]
+  pub async fn log_async(&self, event: LogEvent, compiler_dir: Option<&Path>) {
+    if let Some(logger) = &self.async_logger {
+      match Log::new(event, compiler_dir) {
+        Log::Debug(message, options) => logger.debug(&message, &options).await,
+        Log::Warn(message, options) => logger.warn(&message, &options).await,
+      }
+    } else {
+      self.log(event, compiler_dir);
+    }
+  }
 }
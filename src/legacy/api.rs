@@ -10,7 +10,7 @@ use urlencoding::decode;
 
 use crate::{
   legacy::url_to_file_path_cross_platform, CompileResult, Options,
-  StringOptions, Syntax, Url,
+  SourceMapOutput, SourceMapUrl, StringOptions, Syntax, Url,
 };
 pub use crate::{BoxLogger, Logger, OutputStyle};
 
@@ -262,6 +262,30 @@ impl LegacyOptionsBuilder {
     self
   }
 
+  /// Sets the [LegacyOptions]'s [source_map_embed] field.
+  pub fn source_map_embed(mut self, arg: impl Into<bool>) -> Self {
+    self.options.source_map_embed = arg.into();
+    self
+  }
+
+  /// Sets the [LegacyOptions]'s [source_map_root] field.
+  pub fn source_map_root(mut self, arg: impl Into<String>) -> Self {
+    self.options.source_map_root = Some(arg.into());
+    self
+  }
+
+  /// Sets the [LegacyOptions]'s [omit_source_map_url] field.
+  pub fn omit_source_map_url(mut self, arg: impl Into<bool>) -> Self {
+    self.options.omit_source_map_url = arg.into();
+    self
+  }
+
+  /// Sets the [LegacyOptions]'s [out_file] field.
+  pub fn out_file(mut self, arg: impl AsRef<Path>) -> Self {
+    self.options.out_file = Some(arg.as_ref().to_owned());
+    self
+  }
+
   /// Sets the [LegacyOptions]'s [sass_importers] field with [SassLegacyImporter]s.
   pub fn sass_importers(
     mut self,
@@ -357,6 +381,14 @@ pub struct LegacyOptions {
   pub source_map: bool,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/LegacySharedOptions#sourceMapContents)
   pub source_map_contents: bool,
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/LegacySharedOptions#sourceMapEmbed)
+  pub source_map_embed: bool,
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/LegacySharedOptions#sourceMapRoot)
+  pub source_map_root: Option<String>,
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/LegacySharedOptions#omitSourceMapUrl)
+  pub omit_source_map_url: bool,
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/LegacySharedOptions#outFile)
+  pub out_file: Option<PathBuf>,
   /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/interfaces/LegacySharedOptions#importer)
   #[cfg_attr(feature = "serde", serde(skip))]
   pub importers: Option<Vec<BoxLegacyImporter>>,
@@ -388,6 +420,10 @@ impl Default for LegacyOptions {
       output_style: OutputStyle::Expanded,
       source_map: false,
       source_map_contents: false,
+      source_map_embed: false,
+      source_map_root: None,
+      omit_source_map_url: false,
+      out_file: None,
       importers: None,
       quiet_deps: false,
       verbose: false,
@@ -401,12 +437,14 @@ impl Default for LegacyOptions {
 
 impl From<LegacyOptions> for Options {
   fn from(options: LegacyOptions) -> Self {
+    let source_map_output = options.source_map_output();
     Self {
       load_paths: options.include_paths,
       logger: options.logger,
       quiet_deps: options.quiet_deps,
       source_map: options.source_map,
       source_map_include_sources: options.source_map_contents,
+      source_map_output,
       style: options.output_style,
       verbose: options.verbose,
       charset: options.charset,
@@ -438,6 +476,32 @@ impl From<LegacyOptions> for StringOptions {
 }
 
 impl LegacyOptions {
+  /// Like the legacy JS API, writes the source map next to
+  /// [LegacyOptions::out_file], with a `.map` extension appended, as long as
+  /// [LegacyOptions::source_map] is set.
+  fn source_map_output(&self) -> SourceMapOutput {
+    if !self.source_map {
+      return SourceMapOutput::default();
+    }
+    let map_file = self.out_file.as_ref().map(|out_file| {
+      let mut map_file = out_file.clone().into_os_string();
+      map_file.push(".map");
+      PathBuf::from(map_file)
+    });
+    let url = if self.omit_source_map_url {
+      None
+    } else if self.source_map_embed {
+      Some(SourceMapUrl::Inline)
+    } else {
+      map_file.map(SourceMapUrl::File)
+    };
+    SourceMapOutput {
+      out_file: self.out_file.clone(),
+      url,
+      source_root: self.source_map_root.clone(),
+    }
+  }
+
   pub(crate) fn adjust_options(mut self) -> Self {
     if let Some(file) = &self.file {
       if self.data.is_none()
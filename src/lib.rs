@@ -3,7 +3,7 @@
 //! ```no_run
 //! use sass_embedded::{Sass, StringOptions};
 //!
-//! let mut sass = Sass::new("path/to/sass_embedded").unwrap();
+//! let sass = Sass::new("path/to/sass_embedded").unwrap();
 //! let res = sass.compile_string("a {b: c}", StringOptions::default()).unwrap();
 //! println!("{:?}", res);
 //! ```
@@ -11,34 +11,103 @@
 //! # features
 //!
 //! - **`legacy`**: support for [sass's legacy APIs](https://sass-lang.com/documentation/js-api/modules#renderSync)
+//! - **`async`**: an [AsyncEmbedded] compiler on top of [tokio](https://tokio.rs), with async importers and loggers
+//! - **`tracing`**: [StderrSink::Tracing] to emit the compiler's stderr as [tracing](https://docs.rs/tracing) events
+//! - **`miette`**: [miette::Diagnostic](https://docs.rs/miette/latest/miette/trait.Diagnostic.html) implementations for [Exception] and [Report]
+//! - **`testing`**: a [testing::MockCompiler] that speaks the protocol in-process, for testing importers, functions and loggers without the compiler
+//! - **`watch`**: [Embedded::watch] to recompile entrypoints when the files they loaded change, using [notify](https://docs.rs/notify)
+//! - **`disk-cache`**: a [DiskCache] that stores compilation results in a directory shared by several processes
 //!
 
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
 
 mod api;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod cache;
+mod cancellation;
 mod channel;
+mod command;
 mod compiler;
 mod connection;
+mod diagnostic;
+mod discovery;
+#[cfg(feature = "disk-cache")]
+mod disk_cache;
 mod dispatcher;
 mod embedded;
 mod error;
 mod host;
+mod pool;
 mod protocol;
+mod restart;
+mod source_map;
+mod stack;
+mod stderr;
+mod transport;
+mod value;
 mod varint;
+mod version;
+#[cfg(feature = "watch")]
+mod watch;
 
 #[cfg(feature = "legacy")]
 pub mod legacy;
+#[cfg(feature = "testing")]
+pub mod testing;
 
+#[cfg(feature = "async")]
 pub use api::{
-  BoxFileImporter, BoxImporter, BoxLogger, CompileResult, FileImporter,
-  Importer, ImporterOptions, ImporterResult, Logger, LoggerDebugOptions,
-  LoggerWarnOptions, Options, OptionsBuilder, OutputStyle, SassImporter,
-  SourceSpan, StringOptions, StringOptionsBuilder, Syntax,
+  AsyncFileImporter, AsyncImporter, AsyncLogger, BoxAsyncFileImporter,
+  BoxAsyncImporter, BoxAsyncLogger,
 };
+pub use api::{
+  BoxCustomFunction, BoxFileImporter, BoxImporter, BoxLogger, CompileResult,
+  CustomFunction, FileImporter, Importer, ImporterOptions, ImporterResult,
+  Logger, LoggerDebugOptions, LoggerWarnOptions, Options, OptionsBuilder,
+  OutputStyle, SassImporter, SourceLocation, SourceSpan, StringOptions,
+  StringOptionsBuilder, Syntax,
+};
+/// Re-exported for implementing [AsyncImporter], [AsyncFileImporter] and
+/// [AsyncLogger].
+#[cfg(feature = "async")]
+pub use async_trait::async_trait;
+#[cfg(all(feature = "async", unix))]
+pub use asynchronous::AsyncUnixTransport;
+#[cfg(feature = "async")]
+pub use asynchronous::{
+  AsyncChildTransport, AsyncEmbedded, AsyncTcpTransport, AsyncTransport,
+};
+pub use cache::{CompileCache, Freshness};
+pub use cancellation::CancellationToken;
+pub use command::{CompilerCommand, CompilerStderr};
+pub use diagnostic::{Label, RenderOptions, Report, Severity};
+pub use discovery::DiscoveryOptions;
+#[cfg(feature = "disk-cache")]
+pub use disk_cache::DiskCache;
 pub use embedded::{Embedded, Embedded as Sass};
-pub use error::{Exception, Result};
+pub use error::{Exception, ExceptionKind, ProtocolErrorType, Result};
+pub use pool::{EmbeddedPool, PoolOptions, PoolOptionsBuilder};
+pub use restart::{CompilerEvent, RestartPolicy};
+pub use semver::{self, Version};
+pub use source_map::{
+  Mapping, OriginalLocation, SourceMap, SourceMapOutput, SourceMapUrl,
+};
+pub use stack::StackFrame;
+pub use stderr::{StderrOptions, StderrSink};
+#[cfg(unix)]
+pub use transport::UnixTransport;
+pub use transport::{ChildTransport, MemoryTransport, TcpTransport, Transport};
 pub use url::{self, Url};
+pub use value::{
+  CalculationOperation, CalculationOperator, CalculationValue, ColorChannels,
+  ListSeparator, SassArgumentList, SassCalculation, SassColor, SassFunction,
+  SassList, SassMap, SassNumber, SassString, Value,
+};
+pub use version::{VersionInfo, PROTOCOL_VERSION};
+#[cfg(feature = "watch")]
+pub use watch::WatchOptions;
 
 /// A Logger that silently ignores all warnings and debug messages.
 ///
@@ -0,0 +1,341 @@
+use std::{
+  ffi::OsStr,
+  path::Path,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Weak,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::{
+  cancellation::Interrupt,
+  channel::Channel,
+  connection::ConnectedGuard,
+  embedded::{
+    assert_sync, compile, compile_request, compile_string_request, info,
+    version,
+  },
+  host::Host,
+  transport::Connector,
+  CompileResult, CompilerCommand, Exception, Options, Result, StringOptions,
+  VersionInfo,
+};
+
+/// Options that can be passed to [EmbeddedPool::new].
+#[derive(Debug, Clone)]
+pub struct PoolOptions {
+  /// The number of compiler processes that are kept running even when they
+  /// are idle. Defaults to `1`.
+  pub min_size: usize,
+  /// The maximum number of compiler processes. Defaults to the number of
+  /// available CPUs.
+  pub max_size: usize,
+  /// How long a compiler process may be idle before it's shut down, as long
+  /// as there are more than [PoolOptions::min_size] processes. `None` keeps
+  /// idle processes running. Defaults to one minute.
+  pub idle_timeout: Option<Duration>,
+}
+
+impl Default for PoolOptions {
+  fn default() -> Self {
+    Self {
+      min_size: 1,
+      max_size: thread::available_parallelism().map_or(1, |n| n.get()),
+      idle_timeout: Some(Duration::from_secs(60)),
+    }
+  }
+}
+
+/// A builder for [PoolOptions].
+#[derive(Debug, Default)]
+pub struct PoolOptionsBuilder {
+  options: PoolOptions,
+}
+
+impl PoolOptionsBuilder {
+  /// Creates a new [PoolOptionsBuilder].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Build the [PoolOptions].
+  pub fn build(self) -> PoolOptions {
+    self.options
+  }
+
+  /// Sets the [PoolOptions]'s [min_size] field.
+  pub fn min_size(mut self, arg: usize) -> Self {
+    self.options.min_size = arg;
+    self
+  }
+
+  /// Sets the [PoolOptions]'s [max_size] field.
+  pub fn max_size(mut self, arg: usize) -> Self {
+    self.options.max_size = arg;
+    self
+  }
+
+  /// Sets the [PoolOptions]'s [idle_timeout] field.
+  pub fn idle_timeout(mut self, arg: impl Into<Option<Duration>>) -> Self {
+    self.options.idle_timeout = arg.into();
+    self
+  }
+}
+
+/// A pool of dart-sass-embedded processes, for compiling more stylesheets
+/// in parallel than a single [Embedded](crate::Embedded) can.
+///
+/// Each compilation is routed to the process with the fewest compilations in
+/// flight. A new process is started when all of them are busy, up to
+/// [PoolOptions::max_size], and processes that crashed are replaced.
+///
+/// ```no_run
+/// use sass_embedded::{EmbeddedPool, PoolOptions, StringOptions};
+///
+/// let pool = EmbeddedPool::new("path/to/sass_embedded", PoolOptions::default()).unwrap();
+/// let res = pool.compile_string("a {b: c}", StringOptions::default()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct EmbeddedPool {
+  pool: Arc<Pool>,
+}
+
+impl EmbeddedPool {
+  /// Creates a pool and starts [PoolOptions::min_size] compiler processes.
+  pub fn new(
+    exe_path: impl AsRef<OsStr>,
+    options: PoolOptions,
+  ) -> Result<Self> {
+    Self::with_command(CompilerCommand::new(exe_path), options)
+  }
+
+  /// Creates a pool whose compiler processes are started with `command`,
+  /// like [Embedded::with_command](crate::Embedded::with_command).
+  pub fn with_command(
+    command: CompilerCommand,
+    options: PoolOptions,
+  ) -> Result<Self> {
+    let connector = Connector::command(command);
+    if options.max_size == 0 || options.min_size > options.max_size {
+      return Err(
+        Exception::new(format!(
+          "Invalid pool size: min_size ({}) must be at most max_size ({}), \
+           which must be at least 1.",
+          options.min_size, options.max_size
+        ))
+        .into(),
+      );
+    }
+    let members = (0..options.min_size)
+      .map(|_| Member::new(&connector).map(Arc::new))
+      .collect::<Result<_>>()?;
+    let pool = Arc::new(Pool {
+      connector,
+      members: Mutex::new(members),
+      starting: AtomicUsize::new(0),
+      started: Condvar::new(),
+      options,
+    });
+    if let Some(idle_timeout) = pool.options.idle_timeout {
+      Pool::spawn_reaper(Arc::downgrade(&pool), idle_timeout);
+    }
+    Ok(Self { pool })
+  }
+
+  /// The number of compiler processes currently in the pool.
+  pub fn size(&self) -> usize {
+    self.pool.members.lock().len()
+  }
+
+  /// Compiles the Sass file at path to CSS, like [Embedded::compile].
+  ///
+  /// [Embedded::compile]: crate::Embedded::compile
+  pub fn compile(
+    &self,
+    path: impl AsRef<Path>,
+    options: Options,
+  ) -> Result<CompileResult> {
+    assert_sync(&options, None)?;
+    let interrupt = Interrupt::new(&options);
+    let output = options.source_map_output.clone();
+    let (request, host) = compile_request(path.as_ref(), options);
+    output.apply(
+      self
+        .pool
+        .run(host, |conn| compile(conn, request, &interrupt))?,
+    )
+  }
+
+  /// Compiles a stylesheet whose contents is source to CSS, like
+  /// [Embedded::compile_string].
+  ///
+  /// [Embedded::compile_string]: crate::Embedded::compile_string
+  pub fn compile_string(
+    &self,
+    source: impl Into<String>,
+    options: StringOptions,
+  ) -> Result<CompileResult> {
+    assert_sync(&options.common, options.input_importer.as_ref())?;
+    let interrupt = Interrupt::new(&options.common);
+    let output = options.common.source_map_output.clone();
+    let (request, host) = compile_string_request(source.into(), options);
+    output.apply(
+      self
+        .pool
+        .run(host, |conn| compile(conn, request, &interrupt))?,
+    )
+  }
+
+  /// Gets the version of the sass-embedded compiler.
+  pub fn info(&self) -> Result<String> {
+    self.pool.run(Host::default(), info)
+  }
+
+  /// Gets the versions of the compiler and of the embedded protocol it
+  /// speaks, like [Embedded::version].
+  ///
+  /// [Embedded::version]: crate::Embedded::version
+  pub fn version(&self) -> Result<VersionInfo> {
+    self.pool.run(Host::default(), version)
+  }
+}
+
+#[derive(Debug)]
+struct Pool {
+  connector: Connector,
+  options: PoolOptions,
+  members: Mutex<Vec<Arc<Member>>>,
+  /// The compilers that are being started outside of the lock, which count
+  /// towards [PoolOptions::max_size]. Only changed while `members` is locked.
+  starting: AtomicUsize,
+  /// Notified whenever a compiler is done starting, successfully or not.
+  started: Condvar,
+}
+
+impl Pool {
+  /// Connects to the least busy compiler and runs `f` with the connection.
+  fn run<T>(
+    &self,
+    host: Host,
+    f: impl FnOnce(ConnectedGuard) -> Result<T>,
+  ) -> Result<T> {
+    let (member, conn) = self.connect(host)?;
+    let result = f(conn);
+    *member.last_used.lock() = Instant::now();
+    result
+  }
+
+  fn connect(&self, host: Host) -> Result<(Arc<Member>, ConnectedGuard)> {
+    let member = self.reserve()?;
+    // Connected without holding the lock, since restarting a crashed
+    // compiler may back off.
+    let conn = member.channel.connect(host);
+    member.connecting.fetch_sub(1, Ordering::Relaxed);
+    let conn = conn?;
+    *member.last_used.lock() = Instant::now();
+    Ok((member, conn))
+  }
+
+  /// Picks the least busy compiler, starting a new one if needed, and counts
+  /// the connection that's about to be opened in its load, so the next
+  /// caller sees it.
+  fn reserve(&self) -> Result<Arc<Member>> {
+    let mut members = self.members.lock();
+    loop {
+      members.retain(|member| {
+        let closed = member.channel.is_closed();
+        if closed {
+          member.channel.retire();
+        }
+        !closed
+      });
+      let full = members.len() + self.starting.load(Ordering::Relaxed)
+        >= self.options.max_size;
+      match members.iter().min_by_key(|member| member.load()).cloned() {
+        Some(member) if member.load() == 0 || full => {
+          member.connecting.fetch_add(1, Ordering::Relaxed);
+          return Ok(member);
+        }
+        // Every compiler there's room for is still being started.
+        None if full => self.started.wait(&mut members),
+        _ => break,
+      }
+    }
+    self.starting.fetch_add(1, Ordering::Relaxed);
+    drop(members);
+    // Started without holding the lock, since spawning the compiler and
+    // checking its version takes a while.
+    let member = Member::new(&self.connector).map(Arc::new);
+    let mut members = self.members.lock();
+    self.starting.fetch_sub(1, Ordering::Relaxed);
+    self.started.notify_all();
+    let member = member?;
+    member.connecting.fetch_add(1, Ordering::Relaxed);
+    members.push(Arc::clone(&member));
+    Ok(member)
+  }
+
+  /// Shuts down the compilers that have been idle for longer than
+  /// `idle_timeout`, keeping at least [PoolOptions::min_size] of them.
+  fn shrink(&self, idle_timeout: Duration) {
+    let mut members = self.members.lock();
+    let mut excess = members.len().saturating_sub(self.options.min_size);
+    members.retain(|member| {
+      let idle =
+        member.load() == 0 && member.last_used.lock().elapsed() >= idle_timeout;
+      if excess > 0 && idle {
+        excess -= 1;
+        member.channel.close();
+        false
+      } else {
+        true
+      }
+    });
+  }
+
+  fn spawn_reaper(pool: Weak<Pool>, idle_timeout: Duration) {
+    let interval = (idle_timeout / 2).max(Duration::from_millis(10));
+    thread::spawn(move || loop {
+      thread::sleep(interval);
+      match pool.upgrade() {
+        Some(pool) => pool.shrink(idle_timeout),
+        None => break,
+      }
+    });
+  }
+}
+
+impl Drop for Pool {
+  fn drop(&mut self) {
+    for member in self.members.lock().iter() {
+      member.channel.close();
+    }
+  }
+}
+
+#[derive(Debug)]
+struct Member {
+  channel: Channel,
+  last_used: Mutex<Instant>,
+  /// The connections that are being opened, see [Pool::reserve].
+  connecting: AtomicUsize,
+}
+
+impl Member {
+  fn new(connector: &Connector) -> Result<Self> {
+    Ok(Self {
+      channel: Channel::new(connector.clone())?,
+      last_used: Mutex::new(Instant::now()),
+      connecting: AtomicUsize::new(0),
+    })
+  }
+
+  /// The compilations in flight, and the ones that are about to start.
+  fn load(&self) -> usize {
+    self.channel.load() + self.connecting.load(Ordering::Relaxed)
+  }
+}
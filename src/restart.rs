@@ -0,0 +1,157 @@
+use std::{fmt, process::ExitStatus, sync::Arc, time::Duration};
+
+use crate::{Exception, ExceptionKind, Result};
+
+/// How a compiler whose process crashed, or that reported a protocol error,
+/// is restarted.
+///
+/// A dead compiler is detected, and restarted, when the next compilation
+/// starts. Restarts in a row are delayed by an exponential backoff, starting
+/// from the second one, and once the compiler has been restarted
+/// [RestartPolicy::max_retries] times in a row without completing a request,
+/// that compilation fails. The next one starts over.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+  /// How many times in a row the compiler is restarted before giving up.
+  /// `0` never restarts it. Defaults to `3`.
+  pub max_retries: u32,
+  /// The delay before the second restart in a row, which doubles for each
+  /// further one. Defaults to 100 milliseconds.
+  pub initial_backoff: Duration,
+  /// The longest delay between restarts. Defaults to 5 seconds.
+  pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+  fn default() -> Self {
+    Self {
+      max_retries: 3,
+      initial_backoff: Duration::from_millis(100),
+      max_backoff: Duration::from_secs(5),
+    }
+  }
+}
+
+impl RestartPolicy {
+  /// A policy that never restarts the compiler.
+  pub fn never() -> Self {
+    Self {
+      max_retries: 0,
+      ..Self::default()
+    }
+  }
+
+  /// The delay before the `attempt`th restart in a row, counting from 1.
+  fn backoff(&self, attempt: u32) -> Duration {
+    if attempt <= 1 {
+      return Duration::ZERO;
+    }
+    let factor = 2u32.saturating_pow(attempt - 2);
+    self
+      .initial_backoff
+      .checked_mul(factor)
+      .unwrap_or(self.max_backoff)
+      .min(self.max_backoff)
+  }
+}
+
+/// An event in the lifecycle of a compiler process, passed to the hook set
+/// with [Embedded::on_compiler_event](crate::Embedded::on_compiler_event).
+#[derive(Debug, Clone)]
+pub enum CompilerEvent {
+  /// The compiler can no longer be used. `status` is the exit status of its
+  /// process, or `None` if it's still running, e.g. after a protocol error.
+  Crashed {
+    /// The exit status of the compiler process.
+    status: Option<ExitStatus>,
+  },
+  /// A new compiler process was started.
+  Restarted {
+    /// The number of restarts in a row, counting from 1.
+    attempt: u32,
+  },
+  /// Starting a new compiler process failed.
+  RestartFailed {
+    /// The number of restarts in a row, counting from 1.
+    attempt: u32,
+    /// Why the process couldn't be started.
+    message: String,
+  },
+  /// The compiler was restarted [RestartPolicy::max_retries] times in a row,
+  /// so the compilation failed.
+  GaveUp {
+    /// The number of restarts in a row.
+    attempts: u32,
+  },
+}
+
+/// A hook that's called with every [CompilerEvent].
+pub(crate) type EventHook = Arc<dyn Fn(&CompilerEvent) + Send + Sync>;
+
+/// Tracks the restarts of a compiler according to a [RestartPolicy].
+#[derive(Default)]
+pub(crate) struct Supervisor {
+  pub policy: RestartPolicy,
+  pub on_event: Option<EventHook>,
+  restarts: u32,
+}
+
+impl fmt::Debug for Supervisor {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Supervisor")
+      .field("policy", &self.policy)
+      .field("restarts", &self.restarts)
+      .finish()
+  }
+}
+
+impl Supervisor {
+  fn emit(&self, event: CompilerEvent) {
+    if let Some(on_event) = &self.on_event {
+      on_event(&event);
+    }
+  }
+
+  /// Records that the compiler died. If it completed a request, the restarts
+  /// in a row start over.
+  pub fn crashed(&mut self, status: Option<ExitStatus>, responded: bool) {
+    if responded {
+      self.restarts = 0;
+    }
+    self.emit(CompilerEvent::Crashed { status });
+  }
+
+  /// Returns the number and the delay of the next restart, or an error if
+  /// the policy gives up.
+  pub fn next_attempt(&mut self) -> Result<(u32, Duration)> {
+    if self.restarts >= self.policy.max_retries {
+      let attempts = self.restarts;
+      self.restarts = 0;
+      self.emit(CompilerEvent::GaveUp { attempts });
+      let message = if attempts == 0 {
+        "The compiler crashed, and restarting it is disabled.".to_string()
+      } else {
+        format!(
+          "The compiler crashed after {} restarts in a row, giving up.",
+          attempts
+        )
+      };
+      return Err(Box::new(
+        Exception::new(message).with_kind(ExceptionKind::Spawn),
+      ));
+    }
+    self.restarts += 1;
+    Ok((self.restarts, self.policy.backoff(self.restarts)))
+  }
+
+  pub fn restarted(&self, attempt: u32) {
+    self.emit(CompilerEvent::Restarted { attempt });
+  }
+
+  pub fn restart_failed(&self, attempt: u32, error: &Exception) {
+    self.emit(CompilerEvent::RestartFailed {
+      attempt,
+      message: error.message().to_string(),
+    });
+  }
+}
@@ -0,0 +1,567 @@
+use std::{
+  fmt::Write,
+  path::{Component, Path, PathBuf},
+  str::FromStr,
+};
+
+use base64::Engine;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{CompileResult, Exception, Result, Url};
+
+const BASE64: &[u8; 64] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A parsed [source map](https://sourcemaps.info/spec.html) of version 3,
+/// e.g. of [CompileResult::source_map](crate::CompileResult::source_map).
+///
+/// Lines and columns are 0-based, like in [SourceLocation]. Its mappings
+/// are decoded, so they can be looked up and edited, and are encoded again
+/// by [SourceMap::to_json].
+///
+/// ```
+/// use sass_embedded::SourceMap;
+///
+/// let map: SourceMap = r#"{
+///   "version": 3,
+///   "sources": ["file:///styles/a.scss"],
+///   "names": [],
+///   "mappings": "AAAA;EACE"
+/// }"#
+/// .parse()
+/// .unwrap();
+/// let original = map.lookup(1, 4).unwrap();
+/// assert_eq!(map.sources[original.source], "file:///styles/a.scss");
+/// assert_eq!((original.line, original.column), (1, 2));
+/// ```
+///
+/// [SourceLocation]: crate::SourceLocation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+  /// The name of the generated file.
+  pub file: Option<String>,
+  /// The URL that relative [SourceMap::sources] are resolved against.
+  pub source_root: Option<String>,
+  /// The URLs of the original files.
+  pub sources: Vec<String>,
+  /// The contents of each of the [SourceMap::sources], if it's embedded.
+  /// Empty if no contents are embedded.
+  pub sources_content: Vec<Option<String>>,
+  /// The names that [OriginalLocation::name] refers to.
+  pub names: Vec<String>,
+  /// The mappings of each generated line, in the order of their columns.
+  pub mappings: Vec<Vec<Mapping>>,
+}
+
+/// A segment of a generated line, see [SourceMap::mappings].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+  /// The 0-based column in the generated line where the segment starts.
+  pub generated_column: usize,
+  /// Where the segment comes from, or `None` if it's generated.
+  pub original: Option<OriginalLocation>,
+}
+
+/// A location in an original file of a [SourceMap].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OriginalLocation {
+  /// The index of the file in [SourceMap::sources].
+  pub source: usize,
+  /// The 0-based line number.
+  pub line: usize,
+  /// The 0-based column number.
+  pub column: usize,
+  /// The index of the original name in [SourceMap::names], if any.
+  pub name: Option<usize>,
+}
+
+impl SourceMap {
+  /// Parses the JSON of a source map.
+  pub fn parse(json: &str) -> Result<Self> {
+    let invalid = |message: &str| {
+      Box::new(Exception::new(format!("Invalid source map: {}", message)))
+    };
+    // serde_json limits the nesting depth, so a hostile map can't overflow
+    // the stack.
+    let fields =
+      match serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))? {
+        Value::Object(fields) => fields,
+        _ => return Err(invalid("expected an object")),
+      };
+    let mut map = Self::default();
+    let mut mappings = None;
+    for (key, value) in fields {
+      match (key.as_str(), value) {
+        ("version", Value::Number(n)) if n.as_f64() == Some(3.0) => {}
+        ("version", _) => return Err(invalid("expected version 3")),
+        ("file", Value::String(file)) => map.file = Some(file),
+        ("sourceRoot", Value::String(root)) => map.source_root = Some(root),
+        ("sources", value) => {
+          map.sources = strings(value)
+            .ok_or_else(|| invalid("expected sources to be strings"))?
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect()
+        }
+        ("sourcesContent", value) => {
+          map.sources_content = strings(value)
+            .ok_or_else(|| invalid("expected sourcesContent to be strings"))?
+        }
+        ("names", value) => {
+          map.names = strings(value)
+            .ok_or_else(|| invalid("expected names to be strings"))?
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect()
+        }
+        ("mappings", Value::String(value)) => mappings = Some(value),
+        ("mappings", _) => {
+          return Err(invalid("expected mappings to be a string"))
+        }
+        _ => {}
+      }
+    }
+    let mappings = mappings.ok_or_else(|| invalid("missing mappings"))?;
+    map.mappings = Self::decode_mappings(&mappings)?;
+    Ok(map)
+  }
+
+  /// Decodes the Base64 VLQ `mappings` field of a source map.
+  pub fn decode_mappings(mappings: &str) -> Result<Vec<Vec<Mapping>>> {
+    let invalid = |message: &str| {
+      Box::new(Exception::new(format!(
+        "Invalid source map mappings: {}",
+        message
+      )))
+    };
+    // Every field but the generated column is relative to the previous
+    // segment of any line.
+    let mut previous = [0i64; 4];
+    let mut lines = Vec::new();
+    for line in mappings.split(';') {
+      let mut segments = Vec::new();
+      let mut generated_column = 0i64;
+      for segment in line.split(',').filter(|segment| !segment.is_empty()) {
+        let mut fields = Vec::with_capacity(5);
+        let mut bytes = segment.bytes();
+        while bytes.len() > 0 {
+          fields.push(decode_vlq(&mut bytes).ok_or_else(|| invalid(segment))?);
+        }
+        generated_column += fields[0];
+        let original = match fields.len() {
+          1 => None,
+          4 | 5 => {
+            for (previous, field) in previous.iter_mut().zip(&fields[1..]) {
+              *previous += field;
+            }
+            Some(OriginalLocation {
+              source: to_index(previous[0]).ok_or_else(|| invalid(segment))?,
+              line: to_index(previous[1]).ok_or_else(|| invalid(segment))?,
+              column: to_index(previous[2]).ok_or_else(|| invalid(segment))?,
+              name: match fields.len() {
+                5 => {
+                  Some(to_index(previous[3]).ok_or_else(|| invalid(segment))?)
+                }
+                _ => None,
+              },
+            })
+          }
+          _ => return Err(invalid(segment)),
+        };
+        segments.push(Mapping {
+          generated_column: to_index(generated_column)
+            .ok_or_else(|| invalid(segment))?,
+          original,
+        });
+      }
+      lines.push(segments);
+    }
+    Ok(lines)
+  }
+
+  /// Encodes `mappings` as the Base64 VLQ `mappings` field of a source map.
+  pub fn encode_mappings(mappings: &[Vec<Mapping>]) -> String {
+    let mut out = String::new();
+    let mut previous = [0i64; 4];
+    for (i, line) in mappings.iter().enumerate() {
+      if i > 0 {
+        out.push(';');
+      }
+      let mut generated_column = 0i64;
+      for (j, mapping) in line.iter().enumerate() {
+        if j > 0 {
+          out.push(',');
+        }
+        let column = mapping.generated_column as i64;
+        encode_vlq(&mut out, column - generated_column);
+        generated_column = column;
+        if let Some(original) = &mapping.original {
+          let fields = [
+            Some(original.source),
+            Some(original.line),
+            Some(original.column),
+            original.name,
+          ];
+          for (previous, field) in previous.iter_mut().zip(fields) {
+            if let Some(field) = field {
+              encode_vlq(&mut out, field as i64 - *previous);
+              *previous = field as i64;
+            }
+          }
+        }
+      }
+    }
+    out
+  }
+
+  /// The original location of the generated `line` and `column`: that of
+  /// the last segment of the line that starts at or before `column`.
+  pub fn lookup(
+    &self,
+    line: usize,
+    column: usize,
+  ) -> Option<&OriginalLocation> {
+    let segments = self.mappings.get(line)?;
+    let i = segments.partition_point(|m| m.generated_column <= column);
+    segments[..i].last()?.original.as_ref()
+  }
+
+  /// The embedded contents of the source at `index`, if any.
+  pub fn source_content(&self, index: usize) -> Option<&str> {
+    self.sources_content.get(index)?.as_deref()
+  }
+
+  /// Embeds `content` as the contents of the source at `index`, or removes
+  /// the embedded contents if it's `None`. Returns `false` without changing
+  /// anything if there's no source at `index`.
+  pub fn set_source_content(
+    &mut self,
+    index: usize,
+    content: Option<String>,
+  ) -> bool {
+    if index >= self.sources.len() {
+      return false;
+    }
+    if self.sources_content.len() < self.sources.len() {
+      self.sources_content.resize(self.sources.len(), None);
+    }
+    self.sources_content[index] = content;
+    true
+  }
+
+  /// Replaces every source URL with what `rewrite` returns for it.
+  pub fn rewrite_sources(&mut self, mut rewrite: impl FnMut(&str) -> String) {
+    for source in &mut self.sources {
+      *source = rewrite(source);
+    }
+  }
+
+  /// Replaces the `file:` URLs of sources with paths relative to `root`,
+  /// e.g. to not leak the absolute paths of the build machine. Other URLs
+  /// are kept.
+  pub fn make_sources_relative(&mut self, root: impl AsRef<Path>) {
+    let root = root.as_ref();
+    self.rewrite_sources(|source| {
+      let path = match Url::parse(source).map(|url| url.to_file_path()) {
+        Ok(Ok(path)) => path,
+        _ => return source.to_string(),
+      };
+      relative_url(&path, root).unwrap_or_else(|| source.to_string())
+    });
+  }
+
+  /// Moves every mapping `count` lines down, e.g. after a banner of `count`
+  /// lines was prepended to the generated CSS.
+  pub fn offset_lines(&mut self, count: usize) {
+    self
+      .mappings
+      .splice(0..0, std::iter::repeat_with(Vec::new).take(count));
+  }
+
+  /// Serializes the source map to JSON.
+  pub fn to_json(&self) -> String {
+    let mut out = String::from("{\"version\":3");
+    if let Some(file) = &self.file {
+      out.push_str(",\"file\":");
+      write_string(&mut out, file);
+    }
+    if let Some(root) = &self.source_root {
+      out.push_str(",\"sourceRoot\":");
+      write_string(&mut out, root);
+    }
+    out.push_str(",\"sources\":");
+    write_array(&mut out, self.sources.iter().map(Some));
+    if !self.sources_content.is_empty() {
+      out.push_str(",\"sourcesContent\":");
+      write_array(&mut out, self.sources_content.iter().map(Option::as_ref));
+    }
+    out.push_str(",\"names\":");
+    write_array(&mut out, self.names.iter().map(Some));
+    out.push_str(",\"mappings\":");
+    write_string(&mut out, &Self::encode_mappings(&self.mappings));
+    out.push('}');
+    out
+  }
+}
+
+impl FromStr for SourceMap {
+  type Err = Box<Exception>;
+
+  fn from_str(json: &str) -> Result<Self> {
+    Self::parse(json)
+  }
+}
+
+/// Where the `sourceMappingURL` comment that's appended to
+/// [CompileResult::css] points, see [SourceMapOutput::url].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SourceMapUrl {
+  /// Embeds the source map in the comment as a base64 `data:` URL.
+  Inline,
+  /// Points to the file at the path, which the caller writes
+  /// [CompileResult::source_map] to. The comment holds the path relative to
+  /// [SourceMapOutput::out_file], if it's set.
+  File(PathBuf),
+}
+
+/// Adapts [CompileResult::source_map] to where it and the CSS are written,
+/// see [Options::source_map_output](crate::Options::source_map_output).
+///
+/// Relative paths are resolved against the current directory. The default
+/// leaves the source map as the compiler generated it.
+///
+/// ```
+/// use sass_embedded::{OptionsBuilder, SourceMapOutput, SourceMapUrl};
+///
+/// let options = OptionsBuilder::default()
+///   .source_map(true)
+///   .source_map_output(SourceMapOutput {
+///     out_file: Some("dist/style.css".into()),
+///     url: Some(SourceMapUrl::File("dist/style.css.map".into())),
+///     source_root: None,
+///   })
+///   .build();
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SourceMapOutput {
+  /// The path the CSS is written to. Becomes the `file` of the source map,
+  /// whose `file:` sources are made relative to the directory the source
+  /// map is written to: the one of [SourceMapUrl::File], or else this one's.
+  pub out_file: Option<PathBuf>,
+  /// Where the `/*# sourceMappingURL=... */` comment appended to the CSS
+  /// points, or `None` to not append one. Setting it turns on
+  /// [Options::source_map](crate::Options::source_map).
+  pub url: Option<SourceMapUrl>,
+  /// The `sourceRoot` of the source map.
+  pub source_root: Option<String>,
+}
+
+impl SourceMapOutput {
+  /// Rewrites the source map of `result`, if it has one, and appends the
+  /// comment that points to it.
+  pub(crate) fn apply(
+    &self,
+    mut result: CompileResult,
+  ) -> Result<CompileResult> {
+    if *self == Self::default() {
+      return Ok(result);
+    }
+    let mut map = match result.parse_source_map()? {
+      Some(map) => map,
+      None => return Ok(result),
+    };
+    let out_file = self.out_file.as_deref().map(absolute);
+    let out_dir = out_file.as_deref().and_then(Path::parent);
+    let map_dir = match &self.url {
+      Some(SourceMapUrl::File(path)) => {
+        absolute(path).parent().map(Path::to_path_buf)
+      }
+      _ => out_dir.map(Path::to_path_buf),
+    };
+    if let Some(map_dir) = &map_dir {
+      map.make_sources_relative(map_dir);
+    }
+    if let Some(out_file) = &out_file {
+      map.file = Some(
+        map_dir
+          .as_deref()
+          .and_then(|dir| relative_url(out_file, dir))
+          .unwrap_or_else(|| file_url(out_file)),
+      );
+    }
+    if let Some(root) = &self.source_root {
+      map.source_root = Some(root.clone());
+    }
+    let json = map.to_json();
+    if let Some(url) = &self.url {
+      let url = match url {
+        SourceMapUrl::Inline => format!(
+          "data:application/json;charset=utf-8;base64,{}",
+          base64::engine::general_purpose::STANDARD.encode(&json)
+        ),
+        SourceMapUrl::File(path) => out_dir
+          .and_then(|dir| relative_url(&absolute(path), dir))
+          .unwrap_or_else(|| file_url(path)),
+      };
+      write!(result.css, "\n\n/*# sourceMappingURL={} */", url).unwrap();
+    }
+    result.source_map = Some(json);
+    Ok(result)
+  }
+}
+
+fn to_index(value: i64) -> Option<usize> {
+  usize::try_from(value).ok()
+}
+
+/// Decodes one Base64 VLQ value.
+fn decode_vlq(bytes: &mut impl Iterator<Item = u8>) -> Option<i64> {
+  let mut value = 0i64;
+  let mut shift = 0;
+  loop {
+    let byte = bytes.next()?;
+    let digit = BASE64.iter().position(|&c| c == byte)? as i64;
+    value |= (digit & 0b11111) << shift;
+    if digit & 0b100000 == 0 {
+      break;
+    }
+    shift += 5;
+    if shift > 60 {
+      return None;
+    }
+  }
+  // The lowest bit is the sign.
+  Some(if value & 1 == 1 {
+    -(value >> 1)
+  } else {
+    value >> 1
+  })
+}
+
+/// Encodes one Base64 VLQ value.
+fn encode_vlq(out: &mut String, value: i64) {
+  let mut value = if value < 0 {
+    (-value << 1) | 1
+  } else {
+    value << 1
+  };
+  loop {
+    let mut digit = value & 0b11111;
+    value >>= 5;
+    if value > 0 {
+      digit |= 0b100000;
+    }
+    out.push(BASE64[digit as usize] as char);
+    if value == 0 {
+      break;
+    }
+  }
+}
+
+/// `path` resolved against the current directory.
+fn absolute(path: &Path) -> PathBuf {
+  std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `path` as a URL relative to the directory `root`, if both are absolute.
+fn relative_url(path: &Path, root: &Path) -> Option<String> {
+  relative_path(path, root).map(|relative| to_url(&relative))
+}
+
+/// `path` as a URL that can't be made relative: a `file:` URL if it's
+/// absolute, since joining its components would start it with `//`, which
+/// browsers read as the host of a protocol-relative URL.
+fn file_url(path: &Path) -> String {
+  match Url::from_file_path(path) {
+    Ok(url) if path.is_absolute() => url.into(),
+    _ => to_url(path),
+  }
+}
+
+/// The characters that are percent-encoded in a path segment of a URL,
+/// besides controls and non-ASCII characters.
+const SEGMENT: &AsciiSet = &CONTROLS
+  .add(b' ')
+  .add(b'"')
+  .add(b'#')
+  .add(b'%')
+  .add(b'/')
+  .add(b'<')
+  .add(b'>')
+  .add(b'?')
+  .add(b'\\')
+  .add(b'`')
+  .add(b'{')
+  .add(b'}');
+
+/// Joins the percent-encoded components of the relative `path` with `/`.
+fn to_url(path: &Path) -> String {
+  path
+    .components()
+    .map(|c| {
+      utf8_percent_encode(&c.as_os_str().to_string_lossy(), SEGMENT).to_string()
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// `path` relative to the directory `root`, if both are absolute.
+fn relative_path(path: &Path, root: &Path) -> Option<PathBuf> {
+  if !path.is_absolute() || !root.is_absolute() {
+    return None;
+  }
+  let path: Vec<_> = path.components().collect();
+  let root: Vec<_> = root.components().collect();
+  // Paths on different Windows drives have no relative path.
+  if path.first() != root.first() {
+    return None;
+  }
+  let common = path.iter().zip(&root).take_while(|(a, b)| a == b).count();
+  let mut relative = PathBuf::new();
+  for _ in &root[common..] {
+    relative.push(Component::ParentDir);
+  }
+  relative.extend(&path[common..]);
+  Some(relative)
+}
+
+fn write_array<'a>(
+  out: &mut String,
+  items: impl Iterator<Item = Option<&'a String>>,
+) {
+  out.push('[');
+  for (i, item) in items.enumerate() {
+    if i > 0 {
+      out.push(',');
+    }
+    match item {
+      Some(item) => write_string(out, item),
+      None => out.push_str("null"),
+    }
+  }
+  out.push(']');
+}
+
+fn write_string(out: &mut String, value: &str) {
+  out.push_str(&Value::from(value).to_string());
+}
+
+/// A string array of a source map, whose items may be `null`.
+fn strings(value: Value) -> Option<Vec<Option<String>>> {
+  match value {
+    Value::Array(items) => items
+      .into_iter()
+      .map(|item| match item {
+        Value::String(s) => Some(Some(s)),
+        Value::Null => Some(None),
+        _ => None,
+      })
+      .collect(),
+    _ => None,
+  }
+}
@@ -0,0 +1,141 @@
+use std::{
+  env, fmt,
+  path::{Component, Path, PathBuf},
+};
+
+use crate::Url;
+
+/// A frame of a Sass stack trace, parsed from [Exception::sass_stack] or
+/// [LoggerWarnOptions::stack].
+///
+/// [Exception::sass_stack]: crate::Exception::sass_stack
+/// [LoggerWarnOptions::stack]: crate::LoggerWarnOptions::stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+  /// The canonical URL of the stylesheet, or `None` if it was compiled from a
+  /// string without a URL.
+  ///
+  /// Relative paths that the compiler prints for files are resolved against
+  /// its working directory, and files loaded by legacy importers are
+  /// resolved to `file:` URLs.
+  ///
+  /// [StackFrame::parse_trace] assumes the compiler shares the current
+  /// directory, while the frames of exceptions and warnings know the
+  /// [CompilerCommand::current_dir](crate::CompilerCommand::current_dir) of
+  /// the compiler that reported them.
+  pub url: Option<Url>,
+  /// The 0-based line number.
+  pub line: usize,
+  /// The 0-based column number.
+  pub column: usize,
+  /// The mixin, function or `@content` block being run, or
+  /// `"root stylesheet"`.
+  pub member: String,
+}
+
+impl StackFrame {
+  /// Parses a Sass stack trace, skipping the lines that aren't frames and
+  /// the frames of the stylesheets internal to the legacy APIs.
+  pub fn parse_trace(stack: &str) -> Vec<Self> {
+    Self::parse_trace_in(stack, None)
+  }
+
+  /// Parses a Sass stack trace printed by a compiler running in `dir`, or in
+  /// the current directory if it's `None`.
+  pub(crate) fn parse_trace_in(stack: &str, dir: Option<&Path>) -> Vec<Self> {
+    stack
+      .lines()
+      .filter_map(|line| Self::parse(line, dir))
+      .collect()
+  }
+
+  /// Parses a `<url> <line>:<column>  <member>` line.
+  fn parse(line: &str, dir: Option<&Path>) -> Option<Self> {
+    let line = line.trim_end();
+    // The URL may contain spaces, so look for the first position that's
+    // followed by a valid location.
+    line.match_indices(' ').find_map(|(i, _)| {
+      let (location, rest) = line[i + 1..]
+        .split_once(char::is_whitespace)
+        .unwrap_or((&line[i + 1..], ""));
+      let (row, column) = location.split_once(':')?;
+      let row = row.parse::<usize>().ok()?.checked_sub(1)?;
+      let column = column.parse::<usize>().ok()?.checked_sub(1)?;
+      let url = match &line[..i] {
+        "-" => None,
+        url => Some(resolve_url(url, dir)?),
+      };
+      Some(Self {
+        url,
+        line: row,
+        column,
+        member: rest.trim().to_string(),
+      })
+    })
+  }
+}
+
+impl fmt::Display for StackFrame {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.url {
+      Some(url) if url.scheme() == "file" => match url.to_file_path() {
+        Ok(path) => write!(f, "{}", path.display()),
+        Err(_) => write!(f, "{}", url),
+      },
+      Some(url) => write!(f, "{}", url),
+      None => write!(f, "-"),
+    }?;
+    write!(f, " {}:{}  {}", self.line + 1, self.column + 1, self.member)
+  }
+}
+
+/// Resolves the URL of a frame printed by a compiler running in `dir`, or
+/// returns `None` for the frames that should be filtered out.
+fn resolve_url(url: &str, dir: Option<&Path>) -> Option<Url> {
+  #[cfg(feature = "legacy")]
+  {
+    use crate::legacy::{END_OF_LOAD_PROTOCOL, LEGACY_IMPORTER_PROTOCOL};
+
+    if url.starts_with(END_OF_LOAD_PROTOCOL) {
+      return None;
+    }
+    if let Some(file) = url.strip_prefix(LEGACY_IMPORTER_PROTOCOL) {
+      // Written by the host, so relative to our working directory.
+      let file = urlencoding::decode(file).ok()?;
+      return resolve_url(&file, None);
+    }
+  }
+  // The compiler prints `file:` URLs as paths relative to its working
+  // directory. A single letter scheme is a Windows drive.
+  match Url::parse(url) {
+    Ok(url) if url.scheme().len() > 1 => Some(url),
+    _ => {
+      let path = Path::new(url);
+      let path = if path.is_absolute() {
+        path.to_path_buf()
+      } else {
+        match dir {
+          Some(dir) => dir.join(path),
+          None => env::current_dir().ok()?.join(path),
+        }
+      };
+      Url::from_file_path(normalize(&path)).ok()
+    }
+  }
+}
+
+/// Removes the `.` and `..` components of `path` without touching the file
+/// system.
+fn normalize(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        normalized.pop();
+      }
+      component => normalized.push(component),
+    }
+  }
+  normalized
+}
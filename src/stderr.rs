@@ -0,0 +1,132 @@
+use std::{collections::VecDeque, fmt, sync::Arc, time::Duration};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::{BoxLogger, LoggerWarnOptions};
+
+/// Where the lines that the compiler process writes to stderr are sent.
+pub enum StderrSink {
+  /// Only keeps the last lines, see [StderrOptions::max_lines].
+  Buffer,
+  /// Passes each line to [Logger::warn](crate::Logger::warn).
+  Logger(BoxLogger),
+  /// Emits each line as a `WARN` [tracing](https://docs.rs/tracing) event.
+  #[cfg(feature = "tracing")]
+  Tracing,
+  /// Calls the function with each line.
+  Callback(Box<dyn Fn(&str) + Send + Sync>),
+}
+
+impl fmt::Debug for StderrSink {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Buffer => write!(f, "Buffer"),
+      Self::Logger(logger) => f.debug_tuple("Logger").field(logger).finish(),
+      #[cfg(feature = "tracing")]
+      Self::Tracing => write!(f, "Tracing"),
+      Self::Callback(_) => write!(f, "Callback"),
+    }
+  }
+}
+
+impl StderrSink {
+  fn write(&self, line: &str) {
+    match self {
+      Self::Buffer => {}
+      Self::Logger(logger) => logger.warn(
+        line,
+        &LoggerWarnOptions {
+          deprecation: false,
+          span: None,
+          stack: None,
+          formatted: line.to_string(),
+          compiler_dir: None,
+        },
+      ),
+      #[cfg(feature = "tracing")]
+      Self::Tracing => tracing::warn!(target: "sass_embedded", "{}", line),
+      Self::Callback(callback) => callback(line),
+    }
+  }
+}
+
+/// Options for the output the compiler process writes to stderr.
+#[derive(Debug)]
+pub struct StderrOptions {
+  /// Where each line is sent. Defaults to [StderrSink::Buffer].
+  pub sink: StderrSink,
+  /// How many of the last lines are kept, and attached to the [Exception]s
+  /// raised when the compiler process fails. Defaults to `20`.
+  ///
+  /// [Exception]: crate::Exception
+  pub max_lines: usize,
+}
+
+impl Default for StderrOptions {
+  fn default() -> Self {
+    Self {
+      sink: StderrSink::Buffer,
+      max_lines: 20,
+    }
+  }
+}
+
+/// [StderrOptions] shared by every compiler process a channel starts.
+pub(crate) type SharedStderrOptions = Arc<Mutex<StderrOptions>>;
+
+/// The stderr of one compiler process: sends each line to the sink and keeps
+/// the last ones.
+#[derive(Debug)]
+pub(crate) struct StderrLog {
+  options: SharedStderrOptions,
+  lines: Mutex<VecDeque<String>>,
+  closed: Mutex<bool>,
+  closed_cond: Condvar,
+}
+
+impl StderrLog {
+  /// How long to wait for the rest of the output once the process failed.
+  pub const CLOSE_TIMEOUT: Duration = Duration::from_millis(100);
+
+  pub fn new(options: SharedStderrOptions) -> Self {
+    Self {
+      options,
+      lines: Mutex::default(),
+      closed: Mutex::new(false),
+      closed_cond: Condvar::new(),
+    }
+  }
+
+  pub fn push(&self, line: &[u8]) {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim_end_matches(['\r', '\n']);
+    let options = self.options.lock();
+    options.sink.write(line);
+    let mut lines = self.lines.lock();
+    while !lines.is_empty() && lines.len() >= options.max_lines {
+      lines.pop_front();
+    }
+    if options.max_lines > 0 {
+      lines.push_back(line.to_string());
+    }
+  }
+
+  /// Marks the end of the output.
+  pub fn close(&self) {
+    *self.closed.lock() = true;
+    self.closed_cond.notify_all();
+  }
+
+  /// Waits for the end of the output, for at most [Self::CLOSE_TIMEOUT].
+  pub fn wait(&self) {
+    let mut closed = self.closed.lock();
+    if !*closed {
+      self.closed_cond.wait_for(&mut closed, Self::CLOSE_TIMEOUT);
+    }
+  }
+
+  /// The last lines of the output.
+  pub fn tail(&self) -> Vec<String> {
+    self.lines.lock().iter().cloned().collect()
+  }
+}
@@ -0,0 +1,643 @@
+//! A fake compiler that speaks the embedded protocol in-process, for testing
+//! importers, custom functions and loggers without the dart-sass-embedded
+//! executable.
+//!
+//! A [MockCompiler] runs a script for every compilation. The script sends
+//! the requests and log events that the real compiler would, and returns
+//! the [MockResponse] of the compilation.
+//!
+//! ```
+//! use sass_embedded::{
+//!   testing::{MockCompiler, MockResponse},
+//!   StringOptions,
+//! };
+//!
+//! let sass = MockCompiler::new(|compilation| {
+//!   compilation.warn("Careful.", None);
+//!   MockResponse::css(compilation.source().unwrap().to_uppercase())
+//! })
+//! .embedded()
+//! .unwrap();
+//! let res = sass.compile_string("a {b: c}", StringOptions::default());
+//! assert_eq!(res.unwrap().css, "A {B: C}");
+//! ```
+
+use std::{
+  collections::HashMap,
+  panic::{self, AssertUnwindSafe},
+  path::Path,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+  },
+  thread,
+};
+
+use crossbeam_channel::Sender;
+use parking_lot::Mutex;
+use prost::Message;
+
+use crate::{
+  protocol::{
+    self,
+    inbound_message::{
+      self, canonicalize_response, compile_request, file_import_response,
+      function_call_response, import_response, CompileRequest,
+    },
+    outbound_message::{
+      self, compile_response, function_call_request, CanonicalizeRequest,
+      CompileResponse, FileImportRequest, FunctionCallRequest, ImportRequest,
+      LogEvent, VersionResponse,
+    },
+    InboundMessage, OutboundMessage, ProtocolError,
+  },
+  Embedded, MemoryTransport, Result, SourceSpan, Syntax, Transport, Url, Value,
+  PROTOCOL_VERSION,
+};
+
+type Script = dyn Fn(&MockCompilation) -> MockResponse + Send + Sync;
+
+/// A fake compiler that runs a script for every compilation, see the
+/// [module documentation](self).
+#[derive(Clone)]
+pub struct MockCompiler {
+  script: Arc<Script>,
+}
+
+impl std::fmt::Debug for MockCompiler {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "MockCompiler")
+  }
+}
+
+impl MockCompiler {
+  /// Creates a compiler that runs `script` for every compilation, on a
+  /// thread of its own.
+  ///
+  /// If the script panics, the compilation fails with an internal protocol
+  /// error, like it does when the real compiler crashes.
+  pub fn new(
+    script: impl Fn(&MockCompilation) -> MockResponse + Send + Sync + 'static,
+  ) -> Self {
+    Self {
+      script: Arc::new(script),
+    }
+  }
+
+  /// Starts serving a new connection, and returns the host's end of it.
+  pub fn connect(&self) -> MemoryTransport {
+    let (host, compiler) = MemoryTransport::pair();
+    let server = Server {
+      script: Arc::clone(&self.script),
+      transport: Arc::new(compiler),
+      pending: Mutex::default(),
+      next_id: AtomicU32::new(0),
+    };
+    thread::spawn(move || Arc::new(server).serve());
+    host
+  }
+
+  /// Creates an [Embedded] that compiles with this compiler, which is
+  /// reconnected whenever it's restarted.
+  pub fn embedded(self) -> Result<Embedded> {
+    Embedded::with_transport(move || Ok(self.connect()))
+  }
+}
+
+/// The result of a compilation, returned by the script of a [MockCompiler].
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+  /// The compilation succeeded.
+  Success {
+    /// The compiled CSS.
+    css: String,
+    /// The source map, if one was requested.
+    source_map: Option<String>,
+    /// The canonical URLs of the loaded stylesheets.
+    loaded_urls: Vec<Url>,
+  },
+  /// The compilation failed, e.g. because of a syntax error.
+  Failure {
+    /// The error message.
+    message: String,
+    /// Where the error occurred. Defaults to the start of the entrypoint.
+    span: Option<SourceSpan>,
+    /// The Sass stack trace, e.g. `"- 1:1  root stylesheet\n"`.
+    stack_trace: String,
+  },
+  /// The compiler reports a violation of the embedded protocol.
+  ProtocolError(String),
+  /// The compiler closes the connection, as if its process crashed.
+  Crash,
+}
+
+impl MockResponse {
+  /// A successful compilation to `css`.
+  pub fn css(css: impl Into<String>) -> Self {
+    Self::Success {
+      css: css.into(),
+      source_map: None,
+      loaded_urls: Vec::new(),
+    }
+  }
+
+  /// A compilation that failed with `message`.
+  pub fn failure(message: impl Into<String>) -> Self {
+    Self::Failure {
+      message: message.into(),
+      span: None,
+      stack_trace: String::new(),
+    }
+  }
+}
+
+/// An importer of a compilation, which the requests of a [MockCompilation]
+/// are sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockImporter {
+  /// The importer at this index of [Options::importers], where load paths
+  /// come after the importers.
+  ///
+  /// [Options::importers]: crate::Options::importers
+  Index(usize),
+  /// The [StringOptions::input_importer].
+  ///
+  /// [StringOptions::input_importer]: crate::StringOptions::input_importer
+  Input,
+}
+
+impl From<usize> for MockImporter {
+  fn from(index: usize) -> Self {
+    Self::Index(index)
+  }
+}
+
+/// A stylesheet loaded by an importer, see [MockCompilation::import].
+#[derive(Debug, Clone)]
+pub struct MockImport {
+  /// The contents of the stylesheet.
+  pub contents: String,
+  /// The syntax of the stylesheet.
+  pub syntax: Syntax,
+  /// The URL of the stylesheet in source maps.
+  pub source_map_url: Option<Url>,
+}
+
+/// A compilation run by the script of a [MockCompiler].
+///
+/// The requests block until the host responds, and return the host's
+/// error message if it responds with one.
+pub struct MockCompilation {
+  request: CompileRequest,
+  server: Arc<Server>,
+}
+
+impl std::fmt::Debug for MockCompilation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("MockCompilation")
+      .field("id", &self.request.id)
+      .finish()
+  }
+}
+
+impl MockCompilation {
+  /// The source of [Embedded::compile_string].
+  pub fn source(&self) -> Option<&str> {
+    match &self.request.input {
+      Some(compile_request::Input::String(input)) => Some(&input.source),
+      _ => None,
+    }
+  }
+
+  /// The path of [Embedded::compile].
+  pub fn path(&self) -> Option<&Path> {
+    match &self.request.input {
+      Some(compile_request::Input::Path(path)) => Some(Path::new(path)),
+      _ => None,
+    }
+  }
+
+  /// The URL of the entrypoint: its [StringOptions::url], or the URL of its
+  /// path.
+  ///
+  /// [StringOptions::url]: crate::StringOptions::url
+  pub fn url(&self) -> Option<Url> {
+    match &self.request.input {
+      Some(compile_request::Input::String(input)) => {
+        Url::parse(&input.url).ok()
+      }
+      Some(compile_request::Input::Path(path)) => {
+        Url::from_file_path(path).ok()
+      }
+      None => None,
+    }
+  }
+
+  /// Whether a source map is requested, see [Options::source_map].
+  ///
+  /// [Options::source_map]: crate::Options::source_map
+  pub fn source_map(&self) -> bool {
+    self.request.source_map
+  }
+
+  /// The signatures of the [Options::functions].
+  ///
+  /// [Options::functions]: crate::Options::functions
+  pub fn functions(&self) -> &[String] {
+    &self.request.global_functions
+  }
+
+  /// Asks an importer to canonicalize `url`.
+  pub fn canonicalize(
+    &self,
+    importer: impl Into<MockImporter>,
+    url: &str,
+    from_import: bool,
+  ) -> std::result::Result<Option<Url>, String> {
+    let importer_id = match self.importer(importer.into()) {
+      compile_request::importer::Importer::ImporterId(id) => id,
+      importer => panic!("{:?} isn't an importer.", importer),
+    };
+    let response = self.send(|id| {
+      outbound_message::Message::CanonicalizeRequest(CanonicalizeRequest {
+        id,
+        compilation_id: self.request.id,
+        importer_id,
+        url: url.to_string(),
+        from_import,
+      })
+    });
+    match response {
+      inbound_message::Message::CanonicalizeResponse(response) => {
+        match response.result {
+          Some(canonicalize_response::Result::Url(url)) => {
+            Ok(Some(Url::parse(&url).map_err(|e| e.to_string())?))
+          }
+          Some(canonicalize_response::Result::Error(e)) => Err(e),
+          None => Ok(None),
+        }
+      }
+      response => panic!("Unexpected response {:?}.", response),
+    }
+  }
+
+  /// Asks an importer to load the stylesheet at the canonical `url`.
+  pub fn import(
+    &self,
+    importer: impl Into<MockImporter>,
+    url: &Url,
+  ) -> std::result::Result<Option<MockImport>, String> {
+    let importer_id = match self.importer(importer.into()) {
+      compile_request::importer::Importer::ImporterId(id) => id,
+      importer => panic!("{:?} isn't an importer.", importer),
+    };
+    let response = self.send(|id| {
+      outbound_message::Message::ImportRequest(ImportRequest {
+        id,
+        compilation_id: self.request.id,
+        importer_id,
+        url: url.to_string(),
+      })
+    });
+    match response {
+      inbound_message::Message::ImportResponse(response) => {
+        match response.result {
+          Some(import_response::Result::Success(success)) => {
+            Ok(Some(MockImport {
+              contents: success.contents,
+              syntax: match protocol::Syntax::from_i32(success.syntax) {
+                Some(protocol::Syntax::Indented) => Syntax::Indented,
+                Some(protocol::Syntax::Css) => Syntax::Css,
+                _ => Syntax::Scss,
+              },
+              source_map_url: Url::parse(&success.source_map_url).ok(),
+            }))
+          }
+          Some(import_response::Result::Error(e)) => Err(e),
+          None => Ok(None),
+        }
+      }
+      response => panic!("Unexpected response {:?}.", response),
+    }
+  }
+
+  /// Asks a file importer to resolve `url` to a `file:` URL.
+  pub fn file_import(
+    &self,
+    importer: impl Into<MockImporter>,
+    url: &str,
+    from_import: bool,
+  ) -> std::result::Result<Option<Url>, String> {
+    let importer_id = match self.importer(importer.into()) {
+      compile_request::importer::Importer::FileImporterId(id) => id,
+      importer => panic!("{:?} isn't a file importer.", importer),
+    };
+    let response = self.send(|id| {
+      outbound_message::Message::FileImportRequest(FileImportRequest {
+        id,
+        compilation_id: self.request.id,
+        importer_id,
+        url: url.to_string(),
+        from_import,
+      })
+    });
+    match response {
+      inbound_message::Message::FileImportResponse(response) => {
+        match response.result {
+          Some(file_import_response::Result::FileUrl(url)) => {
+            Ok(Some(Url::parse(&url).map_err(|e| e.to_string())?))
+          }
+          Some(file_import_response::Result::Error(e)) => Err(e),
+          None => Ok(None),
+        }
+      }
+      response => panic!("Unexpected response {:?}.", response),
+    }
+  }
+
+  /// Calls the custom function `name` with `arguments`.
+  pub fn call_function(
+    &self,
+    name: &str,
+    arguments: Vec<Value>,
+  ) -> std::result::Result<Value, String> {
+    let response = self.send(|id| {
+      outbound_message::Message::FunctionCallRequest(FunctionCallRequest {
+        id,
+        compilation_id: self.request.id,
+        arguments: arguments.into_iter().map(Into::into).collect(),
+        identifier: Some(function_call_request::Identifier::Name(
+          name.to_string(),
+        )),
+      })
+    });
+    match response {
+      inbound_message::Message::FunctionCallResponse(response) => {
+        match response.result {
+          Some(function_call_response::Result::Success(value)) => {
+            Value::try_from(value).map_err(|e| e.to_string())
+          }
+          Some(function_call_response::Result::Error(e)) => Err(e),
+          None => Err("The function returned no value.".to_string()),
+        }
+      }
+      response => panic!("Unexpected response {:?}.", response),
+    }
+  }
+
+  /// Emits a warning, like `@warn`.
+  pub fn warn(&self, message: &str, span: Option<SourceSpan>) {
+    self.log(protocol::LogEventType::Warning, message, span);
+  }
+
+  /// Emits a deprecation warning.
+  pub fn deprecation(&self, message: &str, span: Option<SourceSpan>) {
+    self.log(protocol::LogEventType::DeprecationWarning, message, span);
+  }
+
+  /// Emits a debug message, like `@debug`.
+  pub fn debug(&self, message: &str, span: Option<SourceSpan>) {
+    self.log(protocol::LogEventType::Debug, message, span);
+  }
+
+  fn log(
+    &self,
+    kind: protocol::LogEventType,
+    message: &str,
+    span: Option<SourceSpan>,
+  ) {
+    let prefix = match kind {
+      protocol::LogEventType::Warning => "WARNING",
+      protocol::LogEventType::DeprecationWarning => "DEPRECATION WARNING",
+      protocol::LogEventType::Debug => "DEBUG",
+    };
+    self
+      .server
+      .send(outbound_message::Message::LogEvent(LogEvent {
+        compilation_id: self.request.id,
+        r#type: kind as i32,
+        message: message.to_string(),
+        span: span.map(Into::into),
+        stack_trace: String::new(),
+        formatted: format!("{}: {}\n", prefix, message),
+      }));
+  }
+
+  fn importer(
+    &self,
+    importer: MockImporter,
+  ) -> compile_request::importer::Importer {
+    let importer = match importer {
+      MockImporter::Index(index) => self
+        .request
+        .importers
+        .get(index)
+        .unwrap_or_else(|| panic!("There's no importer at {}.", index))
+        .clone(),
+      MockImporter::Input => match &self.request.input {
+        Some(compile_request::Input::String(input)) => input
+          .importer
+          .clone()
+          .expect("The compilation has no input importer."),
+        _ => panic!("The compilation has no input importer."),
+      },
+    };
+    importer.importer.expect("The importer is empty.")
+  }
+
+  /// Sends the request that `message` creates with a new id, and waits for
+  /// the host's response.
+  fn send(
+    &self,
+    message: impl FnOnce(u32) -> outbound_message::Message,
+  ) -> inbound_message::Message {
+    let id = self.server.next_id.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = crossbeam_channel::bounded(1);
+    {
+      let mut pending = self.server.pending.lock();
+      if pending.closed {
+        panic!("The host closed the connection before responding.");
+      }
+      pending.senders.insert(id, sender);
+    }
+    self.server.send(message(id));
+    receiver
+      .recv()
+      .expect("The host closed the connection before responding.")
+  }
+
+  fn respond(&self, response: MockResponse) {
+    let id = self.request.id;
+    let result = match response {
+      MockResponse::Success {
+        css,
+        source_map,
+        loaded_urls,
+      } => {
+        compile_response::Result::Success(compile_response::CompileSuccess {
+          css,
+          source_map: source_map.unwrap_or_default(),
+          loaded_urls: loaded_urls.iter().map(Url::to_string).collect(),
+        })
+      }
+      MockResponse::Failure {
+        message,
+        span,
+        stack_trace,
+      } => {
+        let span =
+          span
+            .map(Into::into)
+            .unwrap_or_else(|| protocol::SourceSpan {
+              start: Some(Default::default()),
+              end: Some(Default::default()),
+              url: self.url().map(|url| url.to_string()).unwrap_or_default(),
+              ..Default::default()
+            });
+        compile_response::Result::Failure(compile_response::CompileFailure {
+          formatted: format!("Error: {}", message),
+          message,
+          span: Some(span),
+          stack_trace,
+        })
+      }
+      MockResponse::ProtocolError(message) => {
+        return self.server.protocol_error(
+          id,
+          protocol::ProtocolErrorType::Params,
+          message,
+        )
+      }
+      MockResponse::Crash => return self.server.transport.close(),
+    };
+    self.server.send(outbound_message::Message::CompileResponse(
+      CompileResponse {
+        id,
+        result: Some(result),
+      },
+    ));
+  }
+}
+
+/// Serves one connection.
+struct Server {
+  script: Arc<Script>,
+  transport: Arc<MemoryTransport>,
+  pending: Mutex<Pending>,
+  next_id: AtomicU32,
+}
+
+/// The requests waiting for the host's response.
+#[derive(Default)]
+struct Pending {
+  /// The senders of the responses, by request id.
+  senders: HashMap<u32, Sender<inbound_message::Message>>,
+  /// Whether the host closed the connection, so no response arrives anymore.
+  closed: bool,
+}
+
+impl Server {
+  fn serve(self: Arc<Self>) {
+    while let Ok(buf) = self.transport.read() {
+      let message = match InboundMessage::decode(&buf[..]) {
+        Ok(InboundMessage {
+          message: Some(message),
+        }) => message,
+        _ => {
+          self.protocol_error(
+            u32::MAX,
+            protocol::ProtocolErrorType::Parse,
+            "Invalid message.".to_string(),
+          );
+          continue;
+        }
+      };
+      match message {
+        inbound_message::Message::VersionRequest(request) => {
+          self.send(outbound_message::Message::VersionResponse(
+            VersionResponse {
+              id: request.id,
+              protocol_version: PROTOCOL_VERSION.to_string(),
+              compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+              implementation_version: env!("CARGO_PKG_VERSION").to_string(),
+              implementation_name: "Mock Sass".to_string(),
+            },
+          ));
+        }
+        inbound_message::Message::CompileRequest(request) => {
+          let compilation = MockCompilation {
+            request,
+            server: Arc::clone(&self),
+          };
+          thread::spawn(move || {
+            let script = Arc::clone(&compilation.server.script);
+            match panic::catch_unwind(AssertUnwindSafe(|| script(&compilation)))
+            {
+              Ok(response) => compilation.respond(response),
+              Err(_) => compilation.server.protocol_error(
+                compilation.request.id,
+                protocol::ProtocolErrorType::Internal,
+                "The mock compiler's script panicked.".to_string(),
+              ),
+            }
+          });
+        }
+        inbound_message::Message::CanonicalizeResponse(response) => {
+          self.resolve(
+            response.id,
+            inbound_message::Message::CanonicalizeResponse(response),
+          );
+        }
+        inbound_message::Message::ImportResponse(response) => {
+          self.resolve(
+            response.id,
+            inbound_message::Message::ImportResponse(response),
+          );
+        }
+        inbound_message::Message::FileImportResponse(response) => {
+          self.resolve(
+            response.id,
+            inbound_message::Message::FileImportResponse(response),
+          );
+        }
+        inbound_message::Message::FunctionCallResponse(response) => {
+          self.resolve(
+            response.id,
+            inbound_message::Message::FunctionCallResponse(response),
+          );
+        }
+      }
+    }
+    // Wakes up the compilations that are waiting for the host, and fails the
+    // ones that are yet to send a request.
+    let mut pending = self.pending.lock();
+    pending.closed = true;
+    pending.senders.clear();
+  }
+
+  fn resolve(&self, id: u32, response: inbound_message::Message) {
+    if let Some(sender) = self.pending.lock().senders.remove(&id) {
+      let _ = sender.send(response);
+    }
+  }
+
+  fn send(&self, message: outbound_message::Message) {
+    let message = OutboundMessage {
+      message: Some(message),
+    };
+    let _ = self.transport.write(&message.encode_to_vec());
+  }
+
+  fn protocol_error(
+    &self,
+    id: u32,
+    kind: protocol::ProtocolErrorType,
+    message: String,
+  ) {
+    self.send(outbound_message::Message::Error(ProtocolError {
+      r#type: kind as i32,
+      id,
+      message,
+    }));
+  }
+}
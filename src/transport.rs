@@ -0,0 +1,407 @@
+use std::{
+  fmt,
+  io::{self, BufRead, BufReader, Read, Write},
+  net::{Shutdown, TcpStream, ToSocketAddrs},
+  path::{Path, PathBuf},
+  process::{Child, ChildStderr, ChildStdin, ChildStdout, ExitStatus},
+  sync::Arc,
+  thread,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use parking_lot::Mutex;
+
+use crate::{
+  stderr::{SharedStderrOptions, StderrLog},
+  varint, CompilerCommand,
+};
+
+/// A connection to a compiler that carries the messages of the embedded
+/// protocol, each of which is prefixed with its length as a varint.
+///
+/// [Embedded::with_transport] runs compilations over any transport, e.g. to
+/// share one long-lived compiler daemon between short-lived build processes.
+///
+/// [Embedded::with_transport]: crate::Embedded::with_transport
+pub trait Transport: fmt::Debug + Send + Sync {
+  /// Blocks until the next message from the compiler arrives, and returns
+  /// its encoded `OutboundMessage` without the length prefix.
+  ///
+  /// Returns an [io::ErrorKind::UnexpectedEof] error once the compiler is
+  /// gone or [Transport::close] was called.
+  fn read(&self) -> io::Result<Vec<u8>>;
+
+  /// Sends an encoded `InboundMessage` to the compiler, prefixed with its
+  /// length.
+  fn write(&self, message: &[u8]) -> io::Result<()>;
+
+  /// Closes the connection, which makes [Transport::read] return an error.
+  fn close(&self);
+
+  /// Whether the compiler is known to be gone, e.g. because its process
+  /// exited. Defaults to `false`, since a failed [Transport::read] is
+  /// noticed anyway.
+  fn has_exited(&self) -> bool {
+    false
+  }
+
+  /// The exit status of the compiler process, if it has exited. Defaults to
+  /// `None`.
+  fn exit_status(&self) -> Option<ExitStatus> {
+    None
+  }
+
+  /// The last lines of diagnostic output of the compiler, e.g. of the
+  /// stderr of its process. Defaults to none.
+  fn stderr(&self) -> Vec<String> {
+    Vec::new()
+  }
+
+  /// The last lines of diagnostic output of the compiler after the
+  /// transport failed, which may wait briefly for the rest of it. Defaults
+  /// to [Transport::stderr].
+  fn final_stderr(&self) -> Vec<String> {
+    self.stderr()
+  }
+}
+
+/// The longest message that's read, so a corrupt length prefix fails the
+/// read instead of allocating a huge buffer.
+pub(crate) const MAX_MESSAGE_LEN: usize = 256 * 1024 * 1024;
+
+/// Returns an [io::ErrorKind::InvalidData] error if a message of `len`
+/// bytes is longer than [MAX_MESSAGE_LEN].
+pub(crate) fn check_len(len: usize) -> io::Result<usize> {
+  if len > MAX_MESSAGE_LEN {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!(
+        "message of {} bytes is longer than the maximum of {} bytes",
+        len, MAX_MESSAGE_LEN
+      ),
+    ));
+  }
+  Ok(len)
+}
+
+/// Reads and writes length-prefixed messages over a pair of streams.
+#[derive(Debug)]
+struct Framed<R, W> {
+  reader: Mutex<R>,
+  writer: Mutex<W>,
+}
+
+impl<R: Read, W: Write> Framed<R, W> {
+  fn new(reader: R, writer: W) -> Self {
+    Self {
+      reader: Mutex::new(reader),
+      writer: Mutex::new(writer),
+    }
+  }
+
+  fn read(&self) -> io::Result<Vec<u8>> {
+    let mut reader = self.reader.lock();
+    let len = check_len(varint::read(&mut *reader)?)?;
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+  }
+
+  fn write(&self, message: &[u8]) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(message.len() + 5);
+    varint::write(&mut packet, message.len())?;
+    packet.extend_from_slice(message);
+    let mut writer = self.writer.lock();
+    writer.write_all(&packet)?;
+    writer.flush()
+  }
+}
+
+/// A compiler process started with a [CompilerCommand], which speaks the
+/// protocol over its stdin and stdout.
+#[derive(Debug)]
+pub struct ChildTransport {
+  child: Mutex<Child>,
+  framed: Framed<ChildStdout, ChildStdin>,
+  stderr: Arc<StderrLog>,
+}
+
+impl ChildTransport {
+  /// Starts the compiler process.
+  pub fn spawn(command: &CompilerCommand) -> io::Result<Self> {
+    Self::spawn_with_stderr(command, SharedStderrOptions::default())
+  }
+
+  pub(crate) fn spawn_with_stderr(
+    command: &CompilerCommand,
+    stderr: SharedStderrOptions,
+  ) -> io::Result<Self> {
+    let mut child = command.command().spawn()?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let stderr = Arc::new(StderrLog::new(stderr));
+    match child.stderr.take() {
+      Some(pipe) => Self::spawn_stderr_reader(pipe, Arc::clone(&stderr)),
+      // Not captured, see [CompilerStderr](crate::CompilerStderr).
+      None => stderr.close(),
+    }
+    Ok(Self {
+      child: Mutex::new(child),
+      framed: Framed::new(stdout, stdin),
+      stderr,
+    })
+  }
+
+  /// Reads stderr in the background, so the compiler never blocks on writing
+  /// to it.
+  fn spawn_stderr_reader(stderr: ChildStderr, log: Arc<StderrLog>) {
+    thread::spawn(move || {
+      let mut reader = BufReader::new(stderr);
+      let mut line = Vec::new();
+      while matches!(reader.read_until(b'\n', &mut line), Ok(n) if n > 0) {
+        log.push(&line);
+        line.clear();
+      }
+      log.close();
+    });
+  }
+}
+
+impl Transport for ChildTransport {
+  fn read(&self) -> io::Result<Vec<u8>> {
+    self.framed.read()
+  }
+
+  fn write(&self, message: &[u8]) -> io::Result<()> {
+    self.framed.write(message)
+  }
+
+  /// Kills the compiler process, which closes its stdout.
+  fn close(&self) {
+    let mut child = self.child.lock();
+    let _ = child.kill();
+    let _ = child.wait();
+  }
+
+  fn has_exited(&self) -> bool {
+    !matches!(self.child.lock().try_wait(), Ok(None))
+  }
+
+  fn exit_status(&self) -> Option<ExitStatus> {
+    self.child.lock().try_wait().ok().flatten()
+  }
+
+  fn stderr(&self) -> Vec<String> {
+    self.stderr.tail()
+  }
+
+  fn final_stderr(&self) -> Vec<String> {
+    self.stderr.wait();
+    self.stderr.tail()
+  }
+}
+
+/// A connection to a compiler daemon over TCP.
+#[derive(Debug)]
+pub struct TcpTransport {
+  framed: Framed<BufReader<TcpStream>, TcpStream>,
+}
+
+impl TcpTransport {
+  /// Connects to the compiler daemon listening on `addr`.
+  pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+    Self::new(TcpStream::connect(addr)?)
+  }
+
+  /// Uses a connected stream.
+  pub fn new(stream: TcpStream) -> io::Result<Self> {
+    stream.set_nodelay(true)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    Ok(Self {
+      framed: Framed::new(reader, stream),
+    })
+  }
+}
+
+impl Transport for TcpTransport {
+  fn read(&self) -> io::Result<Vec<u8>> {
+    self.framed.read()
+  }
+
+  fn write(&self, message: &[u8]) -> io::Result<()> {
+    self.framed.write(message)
+  }
+
+  fn close(&self) {
+    let _ = self.framed.writer.lock().shutdown(Shutdown::Both);
+  }
+}
+
+/// A connection to a compiler daemon over a Unix domain socket.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixTransport {
+  framed: Framed<
+    BufReader<std::os::unix::net::UnixStream>,
+    std::os::unix::net::UnixStream,
+  >,
+}
+
+#[cfg(unix)]
+impl UnixTransport {
+  /// Connects to the compiler daemon listening on the socket at `path`.
+  pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+    Self::new(std::os::unix::net::UnixStream::connect(path)?)
+  }
+
+  /// Uses a connected stream.
+  pub fn new(stream: std::os::unix::net::UnixStream) -> io::Result<Self> {
+    let reader = BufReader::new(stream.try_clone()?);
+    Ok(Self {
+      framed: Framed::new(reader, stream),
+    })
+  }
+}
+
+#[cfg(unix)]
+impl Transport for UnixTransport {
+  fn read(&self) -> io::Result<Vec<u8>> {
+    self.framed.read()
+  }
+
+  fn write(&self, message: &[u8]) -> io::Result<()> {
+    self.framed.write(message)
+  }
+
+  fn close(&self) {
+    let _ = self.framed.writer.lock().shutdown(Shutdown::Both);
+  }
+}
+
+/// One end of an in-memory connection, e.g. for testing a host against a
+/// fake compiler without starting a process.
+///
+/// Messages that one end writes are read by the other, without their
+/// length prefixes.
+#[derive(Debug)]
+pub struct MemoryTransport {
+  sender: Mutex<Option<Sender<Vec<u8>>>>,
+  receiver: Receiver<Vec<u8>>,
+  /// Dropped on [Transport::close], which stops a pending read.
+  closer: Mutex<Option<Sender<()>>>,
+  closed: Receiver<()>,
+}
+
+impl MemoryTransport {
+  /// Creates two connected ends.
+  pub fn pair() -> (Self, Self) {
+    let (a_sender, b_receiver) = crossbeam_channel::unbounded();
+    let (b_sender, a_receiver) = crossbeam_channel::unbounded();
+    (
+      Self::new(a_sender, a_receiver),
+      Self::new(b_sender, b_receiver),
+    )
+  }
+
+  fn new(sender: Sender<Vec<u8>>, receiver: Receiver<Vec<u8>>) -> Self {
+    let (closer, closed) = crossbeam_channel::bounded(0);
+    Self {
+      sender: Mutex::new(Some(sender)),
+      receiver,
+      closer: Mutex::new(Some(closer)),
+      closed,
+    }
+  }
+}
+
+impl Transport for MemoryTransport {
+  fn read(&self) -> io::Result<Vec<u8>> {
+    crossbeam_channel::select! {
+      recv(self.receiver) -> message => message.ok(),
+      recv(self.closed) -> _ => None,
+    }
+    .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+  }
+
+  fn write(&self, message: &[u8]) -> io::Result<()> {
+    let sender = self.sender.lock();
+    match sender.as_ref().map(|s| s.send(message.to_vec())) {
+      Some(Ok(())) => Ok(()),
+      _ => Err(io::ErrorKind::BrokenPipe.into()),
+    }
+  }
+
+  /// Closes both directions, so the other end's reads fail too.
+  fn close(&self) {
+    self.sender.lock().take();
+    self.closer.lock().take();
+  }
+}
+
+/// Opens a new transport, every time the compiler is (re)started.
+pub(crate) type Connect =
+  dyn Fn(&SharedStderrOptions) -> io::Result<Box<dyn Transport>> + Send + Sync;
+
+/// How a channel starts its compiler.
+#[derive(Clone)]
+pub(crate) struct Connector {
+  connect: Arc<Connect>,
+  /// Whether the channel checks the protocol version of every compiler it
+  /// starts.
+  handshake: bool,
+  /// The absolute working directory of the compiler, if it's not ours.
+  current_dir: Option<PathBuf>,
+}
+
+impl fmt::Debug for Connector {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Connector")
+  }
+}
+
+impl Connector {
+  pub fn command(command: CompilerCommand) -> Self {
+    Self {
+      handshake: command.get_handshake(),
+      current_dir: command
+        .get_current_dir()
+        .and_then(|dir| std::path::absolute(dir).ok()),
+      connect: Arc::new(move |stderr| {
+        let transport =
+          ChildTransport::spawn_with_stderr(&command, Arc::clone(stderr))?;
+        Ok(Box::new(transport) as Box<dyn Transport>)
+      }),
+    }
+  }
+
+  pub fn custom<T: Transport + 'static>(
+    connect: impl Fn() -> io::Result<T> + Send + Sync + 'static,
+  ) -> Self {
+    Self {
+      handshake: false,
+      current_dir: None,
+      connect: Arc::new(
+        move |_| Ok(Box::new(connect()?) as Box<dyn Transport>),
+      ),
+    }
+  }
+
+  pub fn connect(
+    &self,
+    stderr: &SharedStderrOptions,
+  ) -> io::Result<Box<dyn Transport>> {
+    (self.connect)(stderr)
+  }
+
+  /// Whether the protocol version of every compiler is checked once it's
+  /// started, see [CompilerCommand::handshake].
+  pub fn handshake(&self) -> bool {
+    self.handshake
+  }
+
+  /// The working directory of the compiler if it's not ours, see
+  /// [CompilerCommand::current_dir].
+  pub fn current_dir(&self) -> Option<&Path> {
+    self.current_dir.as_deref()
+  }
+}
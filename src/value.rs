@@ -0,0 +1,467 @@
+mod argument_list;
+mod calculation;
+mod color;
+mod function;
+mod list;
+mod map;
+mod number;
+mod string;
+mod unit;
+
+pub use argument_list::SassArgumentList;
+pub use calculation::{
+  CalculationOperation, CalculationOperator, CalculationValue, SassCalculation,
+};
+pub use color::{ColorChannels, SassColor};
+pub use function::SassFunction;
+pub use list::{ListSeparator, SassList};
+pub use map::SassMap;
+pub use number::SassNumber;
+pub use string::SassString;
+
+use std::{
+  fmt,
+  hash::{Hash, Hasher},
+};
+
+use crate::{
+  protocol::{self, value::Value as ProtoValue, SingletonValue},
+  Exception, Result,
+};
+
+/// A SassScript value, passed to and returned by custom functions.
+///
+/// Equality and hashing follow Sass semantics: numbers are compared with
+/// Sass's fuzzy precision, strings ignore their quotes, colors are compared by
+/// their RGBA channels and maps ignore the order of their entries.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value)
+#[derive(Debug, Clone)]
+pub enum Value {
+  /// A SassScript string.
+  String(SassString),
+  /// A SassScript number.
+  Number(SassNumber),
+  /// A SassScript color.
+  Color(SassColor),
+  /// A SassScript list.
+  List(SassList),
+  /// A SassScript map.
+  Map(SassMap),
+  /// A SassScript boolean.
+  Boolean(bool),
+  /// The SassScript `null` value.
+  Null,
+  /// A SassScript first-class function.
+  Function(SassFunction),
+  /// A SassScript argument list.
+  ArgumentList(SassArgumentList),
+  /// A SassScript calculation.
+  Calculation(SassCalculation),
+}
+
+/// A shared empty map, returned by [Value::assert_map] for empty lists.
+static EMPTY_MAP: SassMap = SassMap::empty();
+
+impl Value {
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#isTruthy)
+  pub fn is_truthy(&self) -> bool {
+    !matches!(self, Value::Boolean(false) | Value::Null)
+  }
+
+  /// Whether this is the SassScript `null` value.
+  pub fn is_null(&self) -> bool {
+    matches!(self, Value::Null)
+  }
+
+  /// Returns this value as a list of values: the elements of a list, the
+  /// key/value pairs of a map as two-element lists, or a single-element list
+  /// containing this value otherwise.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#asList)
+  pub fn as_list(&self) -> Vec<Value> {
+    match self {
+      Value::List(list) => list.contents().to_vec(),
+      Value::ArgumentList(list) => list.contents().to_vec(),
+      Value::Map(map) => map
+        .contents()
+        .iter()
+        .map(|(k, v)| {
+          SassList::new([k.clone(), v.clone()], ListSeparator::Space, false)
+            .into()
+        })
+        .collect(),
+      value => vec![value.clone()],
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#separator)
+  pub fn separator(&self) -> ListSeparator {
+    match self {
+      Value::List(list) => list.separator(),
+      Value::ArgumentList(list) => list.separator(),
+      Value::Map(map) if !map.is_empty() => ListSeparator::Comma,
+      _ => ListSeparator::Undecided,
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#hasBrackets)
+  pub fn has_brackets(&self) -> bool {
+    matches!(self, Value::List(list) if list.has_brackets())
+  }
+
+  /// Converts `sass_index`, a 1-based index that may be negative to count
+  /// from the end, into a 0-based index into [Value::as_list].
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#sassIndexToListIndex)
+  pub fn sass_index_to_list_index(
+    &self,
+    sass_index: &Value,
+    name: Option<&str>,
+  ) -> Result<usize> {
+    let index = sass_index.assert_number(name)?.assert_int(name)?;
+    let len = self.as_list().len() as i64;
+    if index == 0 {
+      return Err(Exception::argument("List index may not be 0.", name).into());
+    }
+    if index.abs() > len {
+      return Err(
+        Exception::argument(
+          format!(
+            "Invalid index {} for a list with {} elements.",
+            sass_index, len
+          ),
+          name,
+        )
+        .into(),
+      );
+    }
+    Ok(if index < 0 { len + index } else { index - 1 } as usize)
+  }
+
+  /// Returns this value as a boolean, or throws if it isn't one.
+  pub fn assert_boolean(&self, name: Option<&str>) -> Result<bool> {
+    match self {
+      Value::Boolean(b) => Ok(*b),
+      _ => Err(self.not_a("a boolean", name)),
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#assertCalculation)
+  pub fn assert_calculation(
+    &self,
+    name: Option<&str>,
+  ) -> Result<&SassCalculation> {
+    match self {
+      Value::Calculation(c) => Ok(c),
+      _ => Err(self.not_a("a calculation", name)),
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#assertColor)
+  pub fn assert_color(&self, name: Option<&str>) -> Result<&SassColor> {
+    match self {
+      Value::Color(c) => Ok(c),
+      _ => Err(self.not_a("a color", name)),
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#assertFunction)
+  pub fn assert_function(&self, name: Option<&str>) -> Result<&SassFunction> {
+    match self {
+      Value::Function(f) => Ok(f),
+      _ => Err(self.not_a("a function reference", name)),
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#assertMap)
+  pub fn assert_map(&self, name: Option<&str>) -> Result<&SassMap> {
+    self.try_map().ok_or_else(|| self.not_a("a map", name))
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#assertNumber)
+  pub fn assert_number(&self, name: Option<&str>) -> Result<&SassNumber> {
+    match self {
+      Value::Number(n) => Ok(n),
+      _ => Err(self.not_a("a number", name)),
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#assertString)
+  pub fn assert_string(&self, name: Option<&str>) -> Result<&SassString> {
+    match self {
+      Value::String(s) => Ok(s),
+      _ => Err(self.not_a("a string", name)),
+    }
+  }
+
+  /// Returns this value as a map if it's a map or an empty list.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/Value#tryMap)
+  pub fn try_map(&self) -> Option<&SassMap> {
+    match self {
+      Value::Map(map) => Some(map),
+      Value::List(list) if list.is_empty() => Some(&EMPTY_MAP),
+      Value::ArgumentList(list) if list.contents().is_empty() => {
+        Some(&EMPTY_MAP)
+      }
+      _ => None,
+    }
+  }
+
+  fn not_a(&self, kind: &str, name: Option<&str>) -> Box<Exception> {
+    Exception::argument(format!("{} is not {}.", self, kind), name).into()
+  }
+
+  /// Returns the list parts of this value if it's a list or argument list.
+  fn list_parts(&self) -> Option<(&[Value], ListSeparator, bool)> {
+    match self {
+      Value::List(l) => Some((l.contents(), l.separator(), l.has_brackets())),
+      Value::ArgumentList(l) => Some((l.contents(), l.separator(), false)),
+      _ => None,
+    }
+  }
+
+  /// Collects the IDs of all argument lists within this value whose keywords
+  /// were accessed.
+  pub(crate) fn accessed_argument_lists(&self, ids: &mut Vec<u32>) {
+    match self {
+      Value::ArgumentList(list) => {
+        if list.id() != 0 && list.were_keywords_accessed() {
+          ids.push(list.id());
+        }
+        for value in list.contents() {
+          value.accessed_argument_lists(ids);
+        }
+        for value in list.keywords_without_marking().values() {
+          value.accessed_argument_lists(ids);
+        }
+      }
+      Value::List(list) => {
+        for value in list.contents() {
+          value.accessed_argument_lists(ids);
+        }
+      }
+      Value::Map(map) => {
+        for (key, value) in map.contents() {
+          key.accessed_argument_lists(ids);
+          value.accessed_argument_lists(ids);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  /// Collects all host functions within this value, so that they can be
+  /// registered before the value is passed to the compiler.
+  pub(crate) fn host_functions(&self, functions: &mut Vec<SassFunction>) {
+    match self {
+      Value::Function(f) if f.is_host() => functions.push(f.clone()),
+      Value::List(list) => {
+        for value in list.contents() {
+          value.host_functions(functions);
+        }
+      }
+      Value::ArgumentList(list) => {
+        for value in list.contents() {
+          value.host_functions(functions);
+        }
+        for value in list.keywords_without_marking().values() {
+          value.host_functions(functions);
+        }
+      }
+      Value::Map(map) => {
+        for (key, value) in map.contents() {
+          key.host_functions(functions);
+          value.host_functions(functions);
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+impl PartialEq for Value {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Value::String(a), Value::String(b)) => a == b,
+      (Value::Number(a), Value::Number(b)) => a == b,
+      (Value::Color(a), Value::Color(b)) => a == b,
+      (Value::Map(a), Value::Map(b)) => a == b,
+      (Value::Boolean(a), Value::Boolean(b)) => a == b,
+      (Value::Null, Value::Null) => true,
+      (Value::Function(a), Value::Function(b)) => a == b,
+      (Value::Calculation(a), Value::Calculation(b)) => a == b,
+      // An empty map is equal to an empty list.
+      (Value::Map(map), list) | (list, Value::Map(map)) => {
+        map.is_empty()
+          && matches!(list.list_parts(), Some((contents, _, _)) if contents.is_empty())
+      }
+      (a, b) => match (a.list_parts(), b.list_parts()) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+      },
+    }
+  }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    match self {
+      Value::String(s) => s.hash(state),
+      Value::Number(n) => n.hash(state),
+      Value::Color(c) => c.hash(state),
+      Value::Map(m) if m.is_empty() => 0u64.hash(state),
+      Value::Map(m) => m.hash(state),
+      Value::Boolean(b) => b.hash(state),
+      Value::Null => 0u8.hash(state),
+      Value::Function(f) => f.hash(state),
+      Value::Calculation(c) => c.hash(state),
+      Value::List(_) | Value::ArgumentList(_) => {
+        let (contents, separator, has_brackets) = self.list_parts().unwrap();
+        if contents.is_empty() {
+          // Empty lists are equal to empty maps, so they must hash the same.
+          0u64.hash(state);
+        } else {
+          contents.hash(state);
+          separator.hash(state);
+          has_brackets.hash(state);
+        }
+      }
+    }
+  }
+}
+
+impl fmt::Display for Value {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Value::String(s) => write!(f, "{}", s),
+      Value::Number(n) => write!(f, "{}", n),
+      Value::Color(c) => write!(f, "{}", c),
+      Value::List(l) => write!(f, "{}", l),
+      Value::Map(m) => write!(f, "{}", m),
+      Value::Boolean(b) => write!(f, "{}", b),
+      Value::Null => write!(f, "null"),
+      Value::Function(func) => write!(f, "{}", func),
+      Value::ArgumentList(l) => write!(f, "{}", l),
+      Value::Calculation(c) => write!(f, "{}", c),
+    }
+  }
+}
+
+impl TryFrom<protocol::Value> for Value {
+  type Error = Box<Exception>;
+
+  fn try_from(value: protocol::Value) -> Result<Self> {
+    let value = value
+      .value
+      .ok_or_else(|| Exception::new("Value.value is mandatory"))?;
+    Ok(match value {
+      ProtoValue::String(s) => Value::String(s.into()),
+      ProtoValue::Number(n) => Value::Number(n.into()),
+      ProtoValue::RgbColor(c) => Value::Color(c.into()),
+      ProtoValue::HslColor(c) => Value::Color(c.into()),
+      ProtoValue::HwbColor(c) => Value::Color(c.into()),
+      ProtoValue::List(l) => Value::List(l.try_into()?),
+      ProtoValue::Map(m) => Value::Map(m.try_into()?),
+      ProtoValue::Singleton(s) => match SingletonValue::from_i32(s) {
+        Some(SingletonValue::True) => Value::Boolean(true),
+        Some(SingletonValue::False) => Value::Boolean(false),
+        Some(SingletonValue::Null) => Value::Null,
+        None => {
+          return Err(
+            Exception::new(format!("Unknown SingletonValue {}", s)).into(),
+          )
+        }
+      },
+      ProtoValue::CompilerFunction(f) => Value::Function(f.into()),
+      ProtoValue::HostFunction(_) => {
+        return Err(
+          Exception::new("The compiler may not send Value.host_function.")
+            .into(),
+        )
+      }
+      ProtoValue::ArgumentList(l) => Value::ArgumentList(l.try_into()?),
+      ProtoValue::Calculation(c) => Value::Calculation(c.try_into()?),
+    })
+  }
+}
+
+impl From<Value> for protocol::Value {
+  fn from(value: Value) -> Self {
+    let value = match value {
+      Value::String(s) => ProtoValue::String(s.into()),
+      Value::Number(n) => ProtoValue::Number(n.into()),
+      Value::Color(c) => c.into(),
+      Value::List(l) => ProtoValue::List(l.into()),
+      Value::Map(m) => ProtoValue::Map(m.into()),
+      Value::Boolean(true) => {
+        ProtoValue::Singleton(SingletonValue::True as i32)
+      }
+      Value::Boolean(false) => {
+        ProtoValue::Singleton(SingletonValue::False as i32)
+      }
+      Value::Null => ProtoValue::Singleton(SingletonValue::Null as i32),
+      Value::Function(f) => f.into(),
+      Value::ArgumentList(l) => ProtoValue::ArgumentList(l.into()),
+      Value::Calculation(c) => ProtoValue::Calculation(c.into()),
+    };
+    Self { value: Some(value) }
+  }
+}
+
+impl From<SassString> for Value {
+  fn from(s: SassString) -> Self {
+    Self::String(s)
+  }
+}
+
+impl From<SassNumber> for Value {
+  fn from(n: SassNumber) -> Self {
+    Self::Number(n)
+  }
+}
+
+impl From<SassColor> for Value {
+  fn from(c: SassColor) -> Self {
+    Self::Color(c)
+  }
+}
+
+impl From<SassList> for Value {
+  fn from(l: SassList) -> Self {
+    Self::List(l)
+  }
+}
+
+impl From<SassMap> for Value {
+  fn from(m: SassMap) -> Self {
+    Self::Map(m)
+  }
+}
+
+impl From<bool> for Value {
+  fn from(b: bool) -> Self {
+    Self::Boolean(b)
+  }
+}
+
+impl From<SassFunction> for Value {
+  fn from(f: SassFunction) -> Self {
+    Self::Function(f)
+  }
+}
+
+impl From<SassArgumentList> for Value {
+  fn from(l: SassArgumentList) -> Self {
+    Self::ArgumentList(l)
+  }
+}
+
+impl From<SassCalculation> for Value {
+  fn from(c: SassCalculation) -> Self {
+    Self::Calculation(c)
+  }
+}
@@ -0,0 +1,140 @@
+use std::{
+  collections::HashMap,
+  fmt,
+  hash::{Hash, Hasher},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+};
+
+use crate::{protocol, Exception, Result};
+
+use super::{list::write_list, ListSeparator, Value};
+
+/// A SassScript argument list, which represents the rest arguments passed to
+/// a function's `$args...` parameter.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassArgumentList)
+#[derive(Debug, Clone)]
+pub struct SassArgumentList {
+  id: u32,
+  contents: Vec<Value>,
+  keywords: HashMap<String, Value>,
+  separator: ListSeparator,
+  /// Shared between clones, so accessing the keywords of any copy of this
+  /// argument list is reported back to the compiler.
+  keywords_accessed: Arc<AtomicBool>,
+}
+
+impl SassArgumentList {
+  /// Creates a new argument list.
+  pub fn new(
+    contents: impl IntoIterator<Item = Value>,
+    keywords: HashMap<String, Value>,
+    separator: ListSeparator,
+  ) -> Self {
+    Self {
+      id: 0,
+      contents: contents.into_iter().collect(),
+      keywords,
+      separator,
+      keywords_accessed: Arc::new(AtomicBool::new(false)),
+    }
+  }
+
+  /// The positional arguments of this argument list.
+  pub fn contents(&self) -> &[Value] {
+    &self.contents
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassArgumentList#keywords)
+  pub fn keywords(&self) -> &HashMap<String, Value> {
+    self.keywords_accessed.store(true, Ordering::Relaxed);
+    &self.keywords
+  }
+
+  /// The separator of this argument list.
+  pub fn separator(&self) -> ListSeparator {
+    self.separator
+  }
+
+  pub(crate) fn id(&self) -> u32 {
+    self.id
+  }
+
+  pub(crate) fn were_keywords_accessed(&self) -> bool {
+    self.keywords_accessed.load(Ordering::Relaxed)
+  }
+
+  pub(crate) fn keywords_without_marking(&self) -> &HashMap<String, Value> {
+    &self.keywords
+  }
+}
+
+/// Argument lists are compared like lists, ignoring their keywords.
+impl PartialEq for SassArgumentList {
+  fn eq(&self, other: &Self) -> bool {
+    self.contents == other.contents && self.separator == other.separator
+  }
+}
+
+impl Eq for SassArgumentList {}
+
+impl Hash for SassArgumentList {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.contents.hash(state);
+    self.separator.hash(state);
+    false.hash(state);
+  }
+}
+
+impl fmt::Display for SassArgumentList {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write_list(f, &self.contents, self.separator, false)
+  }
+}
+
+impl TryFrom<protocol::value::ArgumentList> for SassArgumentList {
+  type Error = Box<Exception>;
+
+  fn try_from(list: protocol::value::ArgumentList) -> Result<Self> {
+    Ok(Self {
+      id: list.id,
+      contents: list
+        .contents
+        .into_iter()
+        .map(Value::try_from)
+        .collect::<Result<_>>()?,
+      keywords: list
+        .keywords
+        .into_iter()
+        .map(|(k, v)| Ok((k, v.try_into()?)))
+        .collect::<Result<_>>()?,
+      separator: list.separator.try_into()?,
+      keywords_accessed: Arc::new(AtomicBool::new(false)),
+    })
+  }
+}
+
+impl From<SassArgumentList> for protocol::value::ArgumentList {
+  fn from(list: SassArgumentList) -> Self {
+    if list.id != 0 {
+      // The compiler already knows the contents of its own argument lists.
+      return Self {
+        id: list.id,
+        ..Default::default()
+      };
+    }
+    Self {
+      id: 0,
+      separator: protocol::ListSeparator::from(list.separator) as i32,
+      contents: list.contents.into_iter().map(Into::into).collect(),
+      keywords: list
+        .keywords
+        .into_iter()
+        .map(|(k, v)| (k, v.into()))
+        .collect(),
+    }
+  }
+}
@@ -0,0 +1,380 @@
+use std::fmt;
+
+use crate::{
+  protocol::{
+    self,
+    value::calculation::{self, calculation_value},
+  },
+  Exception, Result,
+};
+
+use super::SassNumber;
+
+/// A SassScript calculation, such as `calc()`, `min()`, `max()` or `clamp()`.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/values/calculations)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SassCalculation {
+  name: String,
+  arguments: Vec<CalculationValue>,
+}
+
+impl SassCalculation {
+  /// Creates a value that represents `calc(argument)`.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassCalculation#calc)
+  pub fn calc(argument: impl Into<CalculationValue>) -> Self {
+    Self::new("calc", vec![argument.into()])
+  }
+
+  /// Creates a value that represents `min(arguments...)`.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassCalculation#min)
+  pub fn min(
+    arguments: impl IntoIterator<Item = impl Into<CalculationValue>>,
+  ) -> Self {
+    Self::new("min", arguments.into_iter().map(Into::into).collect())
+  }
+
+  /// Creates a value that represents `max(arguments...)`.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassCalculation#max)
+  pub fn max(
+    arguments: impl IntoIterator<Item = impl Into<CalculationValue>>,
+  ) -> Self {
+    Self::new("max", arguments.into_iter().map(Into::into).collect())
+  }
+
+  /// Creates a value that represents `clamp(min, value, max)`. `value` and
+  /// `max` may only be omitted if an earlier argument is an unquoted string
+  /// or interpolation, since it may expand to multiple arguments.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassCalculation#clamp)
+  pub fn clamp(
+    min: impl Into<CalculationValue>,
+    value: Option<CalculationValue>,
+    max: Option<CalculationValue>,
+  ) -> Result<Self> {
+    let min = min.into();
+    if (value.is_none() && !min.is_string_like())
+      || (max.is_none()
+        && !min.is_string_like()
+        && !value.as_ref().is_some_and(CalculationValue::is_string_like))
+    {
+      return Err(
+        Exception::new(
+          "Argument must be an unquoted SassString or CalculationInterpolation.",
+        )
+        .into(),
+      );
+    }
+    let arguments = [Some(min), value, max].into_iter().flatten().collect();
+    Ok(Self::new("clamp", arguments))
+  }
+
+  fn new(name: &str, arguments: Vec<CalculationValue>) -> Self {
+    Self {
+      name: name.to_string(),
+      arguments,
+    }
+  }
+
+  /// The name of this calculation, such as `"calc"`.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The arguments of this calculation.
+  pub fn arguments(&self) -> &[CalculationValue] {
+    &self.arguments
+  }
+}
+
+/// A single component of a [SassCalculation].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CalculationValue {
+  /// A number.
+  Number(SassNumber),
+  /// An unquoted string, as from a function like `var()` or `env()`.
+  String(String),
+  /// An unquoted string as created by interpolation.
+  Interpolation(String),
+  /// A binary operation.
+  Operation(Box<CalculationOperation>),
+  /// A nested calculation.
+  Calculation(SassCalculation),
+}
+
+impl CalculationValue {
+  /// Whether this is an unquoted string or interpolation, which may expand
+  /// to any number of arguments.
+  fn is_string_like(&self) -> bool {
+    matches!(self, Self::String(_) | Self::Interpolation(_))
+  }
+}
+
+impl From<SassNumber> for CalculationValue {
+  fn from(n: SassNumber) -> Self {
+    Self::Number(n)
+  }
+}
+
+impl From<CalculationOperation> for CalculationValue {
+  fn from(o: CalculationOperation) -> Self {
+    Self::Operation(Box::new(o))
+  }
+}
+
+impl From<SassCalculation> for CalculationValue {
+  fn from(c: SassCalculation) -> Self {
+    Self::Calculation(c)
+  }
+}
+
+/// A binary operation that appears in a [SassCalculation].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CalculationOperation {
+  operator: CalculationOperator,
+  left: CalculationValue,
+  right: CalculationValue,
+}
+
+impl CalculationOperation {
+  /// Creates a binary operation to use as an argument of a
+  /// [SassCalculation].
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/CalculationOperation)
+  pub fn new(
+    operator: CalculationOperator,
+    left: impl Into<CalculationValue>,
+    right: impl Into<CalculationValue>,
+  ) -> Self {
+    Self {
+      operator,
+      left: left.into(),
+      right: right.into(),
+    }
+  }
+
+  /// The operator of this operation.
+  pub fn operator(&self) -> CalculationOperator {
+    self.operator
+  }
+
+  /// The left-hand side of this operation.
+  pub fn left(&self) -> &CalculationValue {
+    &self.left
+  }
+
+  /// The right-hand side of this operation.
+  pub fn right(&self) -> &CalculationValue {
+    &self.right
+  }
+}
+
+/// An operator that appears in a [CalculationOperation].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalculationOperator {
+  /// The `+` operator.
+  Plus,
+  /// The `-` operator.
+  Minus,
+  /// The `*` operator.
+  Times,
+  /// The `/` operator.
+  Divide,
+}
+
+impl CalculationOperator {
+  fn precedence(&self) -> u8 {
+    match self {
+      Self::Plus | Self::Minus => 1,
+      Self::Times | Self::Divide => 2,
+    }
+  }
+}
+
+impl fmt::Display for CalculationOperator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Plus => "+",
+      Self::Minus => "-",
+      Self::Times => "*",
+      Self::Divide => "/",
+    })
+  }
+}
+
+impl fmt::Display for SassCalculation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}(", self.name)?;
+    for (i, argument) in self.arguments.iter().enumerate() {
+      if i > 0 {
+        write!(f, ", ")?;
+      }
+      write!(f, "{}", argument)?;
+    }
+    write!(f, ")")
+  }
+}
+
+impl fmt::Display for CalculationValue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Number(n) => write!(f, "{}", n),
+      Self::String(s) | Self::Interpolation(s) => write!(f, "{}", s),
+      Self::Operation(o) => write!(f, "{}", o),
+      Self::Calculation(c) => write!(f, "{}", c),
+    }
+  }
+}
+
+impl fmt::Display for CalculationOperation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let needs_parens = |value: &CalculationValue, right: bool| match value {
+      CalculationValue::Operation(inner) => {
+        inner.operator.precedence() < self.operator.precedence()
+          || (right
+            && inner.operator.precedence() == self.operator.precedence()
+            && matches!(
+              self.operator,
+              CalculationOperator::Minus | CalculationOperator::Divide
+            ))
+      }
+      _ => false,
+    };
+    if needs_parens(&self.left, false) {
+      write!(f, "({})", self.left)?;
+    } else {
+      write!(f, "{}", self.left)?;
+    }
+    write!(f, " {} ", self.operator)?;
+    if needs_parens(&self.right, true) {
+      write!(f, "({})", self.right)
+    } else {
+      write!(f, "{}", self.right)
+    }
+  }
+}
+
+impl From<CalculationOperator> for protocol::CalculationOperator {
+  fn from(o: CalculationOperator) -> Self {
+    match o {
+      CalculationOperator::Plus => Self::Plus,
+      CalculationOperator::Minus => Self::Minus,
+      CalculationOperator::Times => Self::Times,
+      CalculationOperator::Divide => Self::Divide,
+    }
+  }
+}
+
+impl TryFrom<i32> for CalculationOperator {
+  type Error = Box<Exception>;
+
+  fn try_from(operator: i32) -> Result<Self> {
+    match protocol::CalculationOperator::from_i32(operator) {
+      Some(protocol::CalculationOperator::Plus) => Ok(Self::Plus),
+      Some(protocol::CalculationOperator::Minus) => Ok(Self::Minus),
+      Some(protocol::CalculationOperator::Times) => Ok(Self::Times),
+      Some(protocol::CalculationOperator::Divide) => Ok(Self::Divide),
+      None => Err(
+        Exception::new(format!("Unknown CalculationOperator {}", operator))
+          .into(),
+      ),
+    }
+  }
+}
+
+impl TryFrom<protocol::value::Calculation> for SassCalculation {
+  type Error = Box<Exception>;
+
+  fn try_from(calculation: protocol::value::Calculation) -> Result<Self> {
+    Ok(Self {
+      name: calculation.name,
+      arguments: calculation
+        .arguments
+        .into_iter()
+        .map(CalculationValue::try_from)
+        .collect::<Result<_>>()?,
+    })
+  }
+}
+
+impl From<SassCalculation> for protocol::value::Calculation {
+  fn from(calculation: SassCalculation) -> Self {
+    Self {
+      name: calculation.name,
+      arguments: calculation.arguments.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+impl TryFrom<calculation::CalculationValue> for CalculationValue {
+  type Error = Box<Exception>;
+
+  fn try_from(value: calculation::CalculationValue) -> Result<Self> {
+    let value = value.value.ok_or_else(|| {
+      Exception::new("Value.Calculation.CalculationValue.value is mandatory")
+    })?;
+    Ok(match value {
+      calculation_value::Value::Number(n) => Self::Number(n.into()),
+      calculation_value::Value::String(s) => Self::String(s),
+      calculation_value::Value::Interpolation(s) => Self::Interpolation(s),
+      calculation_value::Value::Operation(o) => {
+        Self::Operation(Box::new((*o).try_into()?))
+      }
+      calculation_value::Value::Calculation(c) => {
+        Self::Calculation(c.try_into()?)
+      }
+    })
+  }
+}
+
+impl From<CalculationValue> for calculation::CalculationValue {
+  fn from(value: CalculationValue) -> Self {
+    let value = match value {
+      CalculationValue::Number(n) => calculation_value::Value::Number(n.into()),
+      CalculationValue::String(s) => calculation_value::Value::String(s),
+      CalculationValue::Interpolation(s) => {
+        calculation_value::Value::Interpolation(s)
+      }
+      CalculationValue::Operation(o) => {
+        calculation_value::Value::Operation(Box::new((*o).into()))
+      }
+      CalculationValue::Calculation(c) => {
+        calculation_value::Value::Calculation(c.into())
+      }
+    };
+    Self { value: Some(value) }
+  }
+}
+
+impl TryFrom<calculation::CalculationOperation> for CalculationOperation {
+  type Error = Box<Exception>;
+
+  fn try_from(operation: calculation::CalculationOperation) -> Result<Self> {
+    let left = operation.left.ok_or_else(|| {
+      Exception::new("Value.Calculation.CalculationOperation.left is mandatory")
+    })?;
+    let right = operation.right.ok_or_else(|| {
+      Exception::new(
+        "Value.Calculation.CalculationOperation.right is mandatory",
+      )
+    })?;
+    Ok(Self {
+      operator: operation.operator.try_into()?,
+      left: (*left).try_into()?,
+      right: (*right).try_into()?,
+    })
+  }
+}
+
+impl From<CalculationOperation> for calculation::CalculationOperation {
+  fn from(operation: CalculationOperation) -> Self {
+    Self {
+      operator: protocol::CalculationOperator::from(operation.operator) as i32,
+      left: Some(Box::new(operation.left.into())),
+      right: Some(Box::new(operation.right.into())),
+    }
+  }
+}
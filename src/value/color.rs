@@ -0,0 +1,607 @@
+use std::{
+  fmt,
+  hash::{Hash, Hasher},
+};
+
+use crate::{
+  protocol::{self, value::Value as ProtoValue},
+  Exception, Result,
+};
+
+use super::{
+  number::{format_number, fuzzy_equals, fuzzy_hash, fuzzy_round},
+  SassNumber,
+};
+
+/// The channels a [SassColor] was created with.
+#[derive(Debug, Clone, Copy)]
+enum Channels {
+  Rgb {
+    red: u32,
+    green: u32,
+    blue: u32,
+  },
+  Hsl {
+    hue: f64,
+    saturation: f64,
+    lightness: f64,
+  },
+  Hwb {
+    hue: f64,
+    whiteness: f64,
+    blackness: f64,
+  },
+}
+
+/// The channels to update in [SassColor::change], [SassColor::adjust] and
+/// [SassColor::scale]. Channels that are `None` are left as they are.
+///
+/// RGB channels may not be combined with HSL or HWB channels, and HSL
+/// channels may not be combined with HWB channels. The hue may be combined
+/// with either HSL or HWB channels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ColorChannels {
+  /// The red channel, between `0` and `255`.
+  pub red: Option<f64>,
+  /// The green channel, between `0` and `255`.
+  pub green: Option<f64>,
+  /// The blue channel, between `0` and `255`.
+  pub blue: Option<f64>,
+  /// The hue, in degrees.
+  pub hue: Option<f64>,
+  /// The saturation, between `0` and `100`.
+  pub saturation: Option<f64>,
+  /// The lightness, between `0` and `100`.
+  pub lightness: Option<f64>,
+  /// The whiteness, between `0` and `100`.
+  pub whiteness: Option<f64>,
+  /// The blackness, between `0` and `100`.
+  pub blackness: Option<f64>,
+  /// The alpha channel, between `0` and `1`.
+  pub alpha: Option<f64>,
+}
+
+/// How [SassColor::update] applies a [ColorChannels] to a color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Update {
+  Change,
+  Adjust,
+  Scale,
+}
+
+/// A SassScript color.
+///
+/// A color keeps the channels it was created with, so it's sent back to the
+/// compiler in the same form, but its RGB, HSL and HWB channels can all be
+/// read regardless of that form.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor)
+#[derive(Debug, Clone, Copy)]
+pub struct SassColor {
+  channels: Channels,
+  alpha: f64,
+}
+
+impl SassColor {
+  /// Creates a color from its red, green, blue and alpha channels.
+  pub fn rgb(red: u32, green: u32, blue: u32, alpha: f64) -> Self {
+    Self {
+      channels: Channels::Rgb { red, green, blue },
+      alpha,
+    }
+  }
+
+  /// Creates a color from its hue, saturation, lightness and alpha channels.
+  pub fn hsl(hue: f64, saturation: f64, lightness: f64, alpha: f64) -> Self {
+    Self {
+      channels: Channels::Hsl {
+        hue,
+        saturation,
+        lightness,
+      },
+      alpha,
+    }
+  }
+
+  /// Creates a color from its hue, whiteness, blackness and alpha channels.
+  pub fn hwb(hue: f64, whiteness: f64, blackness: f64, alpha: f64) -> Self {
+    Self {
+      channels: Channels::Hwb {
+        hue,
+        whiteness,
+        blackness,
+      },
+      alpha,
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#alpha)
+  pub fn alpha(&self) -> f64 {
+    self.alpha
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#red)
+  pub fn red(&self) -> u32 {
+    self.rgb_channels().0
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#green)
+  pub fn green(&self) -> u32 {
+    self.rgb_channels().1
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#blue)
+  pub fn blue(&self) -> u32 {
+    self.rgb_channels().2
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#hue)
+  pub fn hue(&self) -> f64 {
+    match self.channels {
+      Channels::Hsl { hue, .. } | Channels::Hwb { hue, .. } => {
+        hue.rem_euclid(360.0)
+      }
+      Channels::Rgb { .. } => self.hsl_channels().0,
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#saturation)
+  pub fn saturation(&self) -> f64 {
+    match self.channels {
+      Channels::Hsl { saturation, .. } => saturation,
+      _ => self.hsl_channels().1,
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#lightness)
+  pub fn lightness(&self) -> f64 {
+    match self.channels {
+      Channels::Hsl { lightness, .. } => lightness,
+      _ => self.hsl_channels().2,
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#whiteness)
+  pub fn whiteness(&self) -> f64 {
+    match self.channels {
+      Channels::Hwb { whiteness, .. } => whiteness,
+      _ => {
+        let (red, green, blue) = self.rgb_channels();
+        red.min(green).min(blue) as f64 / 255.0 * 100.0
+      }
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#blackness)
+  pub fn blackness(&self) -> f64 {
+    match self.channels {
+      Channels::Hwb { blackness, .. } => blackness,
+      _ => {
+        let (red, green, blue) = self.rgb_channels();
+        100.0 - red.max(green).max(blue) as f64 / 255.0 * 100.0
+      }
+    }
+  }
+
+  /// Returns a copy of this color with the given channels set to new values,
+  /// as Sass's `color.change()` function does.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassColor#change)
+  pub fn change(&self, channels: ColorChannels) -> Result<SassColor> {
+    self.update(channels, Update::Change)
+  }
+
+  /// Returns a copy of this color with the given amounts added to its
+  /// channels, as Sass's `color.adjust()` function does.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/modules/color#adjust)
+  pub fn adjust(&self, channels: ColorChannels) -> Result<SassColor> {
+    self.update(channels, Update::Adjust)
+  }
+
+  /// Returns a copy of this color with its channels scaled fluidly towards
+  /// their minimum or maximum, as Sass's `color.scale()` function does. Each
+  /// channel is a percentage between `-100` and `100`, and the hue can't be
+  /// scaled.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/modules/color#scale)
+  pub fn scale(&self, channels: ColorChannels) -> Result<SassColor> {
+    self.update(channels, Update::Scale)
+  }
+
+  /// Returns a copy of this color with its lightness increased by `amount`,
+  /// as Sass's `lighten()` function does.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/modules/color#lighten)
+  pub fn lighten(&self, amount: f64) -> Result<SassColor> {
+    let amount = percentage_in_range(amount, 0.0, 100.0, "amount")?;
+    Ok(SassColor::hsl(
+      self.hue(),
+      self.saturation(),
+      (self.lightness() + amount).clamp(0.0, 100.0),
+      self.alpha,
+    ))
+  }
+
+  /// Returns a copy of this color with its lightness decreased by `amount`,
+  /// as Sass's `darken()` function does.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/modules/color#darken)
+  pub fn darken(&self, amount: f64) -> Result<SassColor> {
+    let amount = percentage_in_range(amount, 0.0, 100.0, "amount")?;
+    Ok(SassColor::hsl(
+      self.hue(),
+      self.saturation(),
+      (self.lightness() - amount).clamp(0.0, 100.0),
+      self.alpha,
+    ))
+  }
+
+  /// Returns a mixture of this color and `other`, as Sass's `color.mix()`
+  /// function does. `weight` is the percentage of this color in the result,
+  /// between `0` and `100`.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/modules/color#mix)
+  pub fn mix(&self, other: &SassColor, weight: f64) -> Result<SassColor> {
+    let weight_scale =
+      percentage_in_range(weight, 0.0, 100.0, "weight")? / 100.0;
+    let normalized_weight = weight_scale * 2.0 - 1.0;
+    let alpha_distance = self.alpha - other.alpha;
+
+    let combined_weight = if normalized_weight * alpha_distance == -1.0 {
+      normalized_weight
+    } else {
+      (normalized_weight + alpha_distance)
+        / (1.0 + normalized_weight * alpha_distance)
+    };
+    let weight1 = (combined_weight + 1.0) / 2.0;
+    let weight2 = 1.0 - weight1;
+
+    let (red1, green1, blue1) = self.rgb_channels();
+    let (red2, green2, blue2) = other.rgb_channels();
+    let mix = |a: u32, b: u32| {
+      fuzzy_round(a as f64 * weight1 + b as f64 * weight2) as u32
+    };
+    Ok(SassColor::rgb(
+      mix(red1, red2),
+      mix(green1, green2),
+      mix(blue1, blue2),
+      self.alpha * weight_scale + other.alpha * (1.0 - weight_scale),
+    ))
+  }
+
+  /// Applies `channels` to this color using the given kind of update.
+  fn update(
+    &self,
+    channels: ColorChannels,
+    update: Update,
+  ) -> Result<SassColor> {
+    let ColorChannels {
+      red,
+      green,
+      blue,
+      hue,
+      saturation,
+      lightness,
+      whiteness,
+      blackness,
+      alpha,
+    } = channels;
+    let has_rgb = red.is_some() || green.is_some() || blue.is_some();
+    let has_sl = saturation.is_some() || lightness.is_some();
+    let has_wb = whiteness.is_some() || blackness.is_some();
+    if has_rgb && (has_sl || has_wb || hue.is_some()) {
+      let other = if has_wb { "HWB" } else { "HSL" };
+      return Err(
+        Exception::new(format!(
+          "RGB parameters may not be passed along with {} parameters.",
+          other
+        ))
+        .into(),
+      );
+    }
+    if has_sl && has_wb {
+      return Err(
+        Exception::new(
+          "HSL parameters may not be passed along with HWB parameters.",
+        )
+        .into(),
+      );
+    }
+    if update == Update::Scale && hue.is_some() {
+      return Err(Exception::new("No argument named $hue.").into());
+    }
+
+    // Validates a channel's value and combines it with its current value.
+    let apply = |value: Option<f64>,
+                 current: f64,
+                 max: f64,
+                 percent: bool,
+                 name: &str|
+     -> Result<f64> {
+      let value = match value {
+        Some(value) => value,
+        None => return Ok(current),
+      };
+      match update {
+        Update::Change => {
+          channel_in_range(value, 0.0, max, percent, name)?;
+          Ok(value)
+        }
+        Update::Adjust => {
+          channel_in_range(value, -max, max, percent, name)?;
+          Ok((current + value).clamp(0.0, max))
+        }
+        Update::Scale => {
+          let scale = percentage_in_range(value, -100.0, 100.0, name)? / 100.0;
+          Ok(if scale > 0.0 {
+            current + (max - current) * scale
+          } else {
+            current + current * scale
+          })
+        }
+      }
+    };
+
+    let alpha = apply(alpha, self.alpha, 1.0, false, "alpha")?;
+    let hue = hue.map(|hue| match update {
+      Update::Change => hue,
+      _ => self.hue() + hue,
+    });
+
+    if has_rgb {
+      let rgb = |value: Option<f64>, current: u32, name: &str| {
+        apply(value, current as f64, 255.0, false, name)
+          .map(|channel| fuzzy_round(channel) as u32)
+      };
+      Ok(SassColor::rgb(
+        rgb(red, self.red(), "red")?,
+        rgb(green, self.green(), "green")?,
+        rgb(blue, self.blue(), "blue")?,
+        alpha,
+      ))
+    } else if has_wb {
+      Ok(SassColor::hwb(
+        hue.unwrap_or_else(|| self.hue()),
+        apply(whiteness, self.whiteness(), 100.0, true, "whiteness")?,
+        apply(blackness, self.blackness(), 100.0, true, "blackness")?,
+        alpha,
+      ))
+    } else if has_sl || hue.is_some() {
+      Ok(SassColor::hsl(
+        hue.unwrap_or_else(|| self.hue()),
+        apply(saturation, self.saturation(), 100.0, true, "saturation")?,
+        apply(lightness, self.lightness(), 100.0, true, "lightness")?,
+        alpha,
+      ))
+    } else {
+      Ok(SassColor {
+        channels: self.channels,
+        alpha,
+      })
+    }
+  }
+
+  /// The hue, saturation and lightness of this color, computed from its red,
+  /// green and blue channels.
+  fn hsl_channels(&self) -> (f64, f64, f64) {
+    let (red, green, blue) = self.rgb_channels();
+    let red = red as f64 / 255.0;
+    let green = green as f64 / 255.0;
+    let blue = blue as f64 / 255.0;
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+
+    let hue = if max == min {
+      0.0
+    } else if max == red {
+      (60.0 * (green - blue) / delta).rem_euclid(360.0)
+    } else if max == green {
+      (120.0 + 60.0 * (blue - red) / delta).rem_euclid(360.0)
+    } else {
+      (240.0 + 60.0 * (red - green) / delta).rem_euclid(360.0)
+    };
+
+    let lightness = 50.0 * (max + min);
+
+    let saturation = if max == min {
+      0.0
+    } else if lightness < 50.0 {
+      100.0 * delta / (max + min)
+    } else {
+      100.0 * delta / (2.0 - max - min)
+    };
+
+    (hue, saturation, lightness)
+  }
+
+  /// The red, green and blue channels of this color.
+  fn rgb_channels(&self) -> (u32, u32, u32) {
+    match self.channels {
+      Channels::Rgb { red, green, blue } => (red, green, blue),
+      Channels::Hsl {
+        hue,
+        saturation,
+        lightness,
+      } => {
+        let hue = hue.rem_euclid(360.0) / 360.0;
+        let saturation = saturation / 100.0;
+        let lightness = lightness / 100.0;
+        let m2 = if lightness <= 0.5 {
+          lightness * (saturation + 1.0)
+        } else {
+          lightness + saturation - lightness * saturation
+        };
+        let m1 = lightness * 2.0 - m2;
+        (
+          to_channel(hue_to_rgb(m1, m2, hue + 1.0 / 3.0)),
+          to_channel(hue_to_rgb(m1, m2, hue)),
+          to_channel(hue_to_rgb(m1, m2, hue - 1.0 / 3.0)),
+        )
+      }
+      Channels::Hwb {
+        hue,
+        whiteness,
+        blackness,
+      } => {
+        let hue = hue.rem_euclid(360.0) / 360.0;
+        let mut whiteness = whiteness / 100.0;
+        let mut blackness = blackness / 100.0;
+        let sum = whiteness + blackness;
+        if sum > 1.0 {
+          whiteness /= sum;
+          blackness /= sum;
+        }
+        let factor = 1.0 - whiteness - blackness;
+        let channel =
+          |hue: f64| to_channel(hue_to_rgb(0.0, 1.0, hue) * factor + whiteness);
+        (
+          channel(hue + 1.0 / 3.0),
+          channel(hue),
+          channel(hue - 1.0 / 3.0),
+        )
+      }
+    }
+  }
+}
+
+/// Returns an error if a channel's `value` isn't between `min` and `max`.
+fn channel_in_range(
+  value: f64,
+  min: f64,
+  max: f64,
+  percent: bool,
+  name: &str,
+) -> Result<f64> {
+  if percent {
+    percentage_in_range(value, min, max, name)
+  } else {
+    SassNumber::new(value).assert_in_range(min, max, Some(name))
+  }
+}
+
+/// Returns an error if the percentage `value` isn't between `min` and `max`.
+fn percentage_in_range(
+  value: f64,
+  min: f64,
+  max: f64,
+  name: &str,
+) -> Result<f64> {
+  SassNumber::with_unit(value, "%").assert_in_range(min, max, Some(name))
+}
+
+/// Converts a channel between `0` and `1` to an integer between `0` and `255`.
+fn to_channel(channel: f64) -> u32 {
+  fuzzy_round(channel * 255.0).clamp(0.0, 255.0) as u32
+}
+
+/// An algorithm from the CSS3 spec: http://www.w3.org/TR/css3-color/#hsl-color.
+fn hue_to_rgb(m1: f64, m2: f64, hue: f64) -> f64 {
+  let hue = if hue < 0.0 {
+    hue + 1.0
+  } else if hue > 1.0 {
+    hue - 1.0
+  } else {
+    hue
+  };
+  if hue < 1.0 / 6.0 {
+    m1 + (m2 - m1) * hue * 6.0
+  } else if hue < 1.0 / 2.0 {
+    m2
+  } else if hue < 2.0 / 3.0 {
+    m1 + (m2 - m1) * (2.0 / 3.0 - hue) * 6.0
+  } else {
+    m1
+  }
+}
+
+/// Colors are equal if their red, green, blue and alpha channels are equal,
+/// regardless of which channels they were created with.
+impl PartialEq for SassColor {
+  fn eq(&self, other: &Self) -> bool {
+    self.rgb_channels() == other.rgb_channels()
+      && fuzzy_equals(self.alpha, other.alpha)
+  }
+}
+
+impl Eq for SassColor {}
+
+impl Hash for SassColor {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.rgb_channels().hash(state);
+    fuzzy_hash(self.alpha, state);
+  }
+}
+
+impl fmt::Display for SassColor {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (red, green, blue) = self.rgb_channels();
+    if fuzzy_equals(self.alpha, 1.0) {
+      write!(f, "#{:02x}{:02x}{:02x}", red, green, blue)
+    } else {
+      write!(
+        f,
+        "rgba({}, {}, {}, {})",
+        red,
+        green,
+        blue,
+        format_number(self.alpha)
+      )
+    }
+  }
+}
+
+impl From<protocol::value::RgbColor> for SassColor {
+  fn from(c: protocol::value::RgbColor) -> Self {
+    Self::rgb(c.red, c.green, c.blue, c.alpha)
+  }
+}
+
+impl From<protocol::value::HslColor> for SassColor {
+  fn from(c: protocol::value::HslColor) -> Self {
+    Self::hsl(c.hue, c.saturation, c.lightness, c.alpha)
+  }
+}
+
+impl From<protocol::value::HwbColor> for SassColor {
+  fn from(c: protocol::value::HwbColor) -> Self {
+    Self::hwb(c.hue, c.whiteness, c.blackness, c.alpha)
+  }
+}
+
+impl From<SassColor> for ProtoValue {
+  fn from(c: SassColor) -> Self {
+    let alpha = c.alpha;
+    match c.channels {
+      Channels::Rgb { red, green, blue } => {
+        ProtoValue::RgbColor(protocol::value::RgbColor {
+          red,
+          green,
+          blue,
+          alpha,
+        })
+      }
+      Channels::Hsl {
+        hue,
+        saturation,
+        lightness,
+      } => ProtoValue::HslColor(protocol::value::HslColor {
+        hue,
+        saturation,
+        lightness,
+        alpha,
+      }),
+      Channels::Hwb {
+        hue,
+        whiteness,
+        blackness,
+      } => ProtoValue::HwbColor(protocol::value::HwbColor {
+        hue,
+        whiteness,
+        blackness,
+        alpha,
+      }),
+    }
+  }
+}
@@ -0,0 +1,146 @@
+use std::{
+  fmt,
+  hash::{Hash, Hasher},
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+  },
+};
+
+use crate::{
+  protocol::{self, value::Value as ProtoValue},
+  CustomFunction,
+};
+
+/// The next ID to use for a host function. IDs are unique across all
+/// compilations, as required by the embedded protocol.
+static NEXT_HOST_FUNCTION_ID: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone)]
+enum Kind {
+  Compiler {
+    id: u32,
+  },
+  Host {
+    id: u32,
+    signature: String,
+    callback: Arc<dyn CustomFunction>,
+  },
+}
+
+/// A SassScript first-class function.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassFunction)
+#[derive(Clone)]
+pub struct SassFunction {
+  kind: Kind,
+}
+
+impl SassFunction {
+  /// Creates a new first-class function with the given signature, such as
+  /// `"mix($color1, $color2, $weight: 50%)"`, that's implemented in Rust.
+  pub fn new(
+    signature: impl Into<String>,
+    callback: impl CustomFunction + 'static,
+  ) -> Self {
+    Self {
+      kind: Kind::Host {
+        id: NEXT_HOST_FUNCTION_ID.fetch_add(1, Ordering::Relaxed),
+        signature: signature.into(),
+        callback: Arc::new(callback),
+      },
+    }
+  }
+
+  /// The signature of this function, if it was defined in the host.
+  pub fn signature(&self) -> Option<&str> {
+    match &self.kind {
+      Kind::Compiler { .. } => None,
+      Kind::Host { signature, .. } => Some(signature),
+    }
+  }
+
+  pub(crate) fn is_host(&self) -> bool {
+    matches!(self.kind, Kind::Host { .. })
+  }
+
+  pub(crate) fn id(&self) -> u32 {
+    match &self.kind {
+      Kind::Compiler { id } | Kind::Host { id, .. } => *id,
+    }
+  }
+
+  pub(crate) fn callback(&self) -> Option<Arc<dyn CustomFunction>> {
+    match &self.kind {
+      Kind::Compiler { .. } => None,
+      Kind::Host { callback, .. } => Some(Arc::clone(callback)),
+    }
+  }
+}
+
+impl fmt::Debug for SassFunction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.kind {
+      Kind::Compiler { id } => {
+        f.debug_struct("SassFunction").field("id", id).finish()
+      }
+      Kind::Host { id, signature, .. } => f
+        .debug_struct("SassFunction")
+        .field("id", id)
+        .field("signature", signature)
+        .finish(),
+    }
+  }
+}
+
+/// Functions are only equal to themselves.
+impl PartialEq for SassFunction {
+  fn eq(&self, other: &Self) -> bool {
+    self.is_host() == other.is_host() && self.id() == other.id()
+  }
+}
+
+impl Eq for SassFunction {}
+
+impl Hash for SassFunction {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.is_host().hash(state);
+    self.id().hash(state);
+  }
+}
+
+impl fmt::Display for SassFunction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.kind {
+      Kind::Compiler { id } => write!(f, "get-function(<compiler #{}>)", id),
+      Kind::Host { signature, .. } => {
+        let name = signature.split_once('(').map_or(&signature[..], |s| s.0);
+        write!(f, "get-function(\"{}\")", name.trim())
+      }
+    }
+  }
+}
+
+impl From<protocol::value::CompilerFunction> for SassFunction {
+  fn from(f: protocol::value::CompilerFunction) -> Self {
+    Self {
+      kind: Kind::Compiler { id: f.id },
+    }
+  }
+}
+
+impl From<SassFunction> for ProtoValue {
+  fn from(f: SassFunction) -> Self {
+    match f.kind {
+      Kind::Compiler { id } => {
+        ProtoValue::CompilerFunction(protocol::value::CompilerFunction { id })
+      }
+      Kind::Host { id, signature, .. } => {
+        ProtoValue::HostFunction(protocol::value::HostFunction {
+          id,
+          signature,
+        })
+      }
+    }
+  }
+}
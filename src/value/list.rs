@@ -0,0 +1,205 @@
+use std::fmt;
+
+use crate::{protocol, Exception, Result};
+
+use super::Value;
+
+/// The separator used to join the elements of a list.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/modules#ListSeparator)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ListSeparator {
+  /// Elements are separated by commas.
+  #[default]
+  Comma,
+  /// Elements are separated by spaces.
+  Space,
+  /// Elements are separated by slashes.
+  Slash,
+  /// The list has no separator yet, because it has fewer than two elements.
+  Undecided,
+}
+
+impl From<ListSeparator> for protocol::ListSeparator {
+  fn from(s: ListSeparator) -> Self {
+    match s {
+      ListSeparator::Comma => Self::Comma,
+      ListSeparator::Space => Self::Space,
+      ListSeparator::Slash => Self::Slash,
+      ListSeparator::Undecided => Self::Undecided,
+    }
+  }
+}
+
+impl TryFrom<i32> for ListSeparator {
+  type Error = Box<Exception>;
+
+  fn try_from(separator: i32) -> Result<Self> {
+    match protocol::ListSeparator::from_i32(separator) {
+      Some(protocol::ListSeparator::Comma) => Ok(Self::Comma),
+      Some(protocol::ListSeparator::Space) => Ok(Self::Space),
+      Some(protocol::ListSeparator::Slash) => Ok(Self::Slash),
+      Some(protocol::ListSeparator::Undecided) => Ok(Self::Undecided),
+      None => Err(
+        Exception::new(format!("Unknown ListSeparator {}", separator)).into(),
+      ),
+    }
+  }
+}
+
+impl ListSeparator {
+  /// The string used to join the elements of a list with this separator.
+  pub(crate) fn joiner(&self) -> &'static str {
+    match self {
+      Self::Comma => ", ",
+      Self::Space | Self::Undecided => " ",
+      Self::Slash => " / ",
+    }
+  }
+}
+
+/// A SassScript list.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassList)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SassList {
+  contents: Vec<Value>,
+  separator: ListSeparator,
+  has_brackets: bool,
+}
+
+impl SassList {
+  /// Creates a new list.
+  pub fn new(
+    contents: impl IntoIterator<Item = Value>,
+    separator: ListSeparator,
+    has_brackets: bool,
+  ) -> Self {
+    Self {
+      contents: contents.into_iter().collect(),
+      separator,
+      has_brackets,
+    }
+  }
+
+  /// Creates an empty list.
+  pub fn empty(separator: ListSeparator, has_brackets: bool) -> Self {
+    Self::new(Vec::new(), separator, has_brackets)
+  }
+
+  /// The elements of this list.
+  pub fn contents(&self) -> &[Value] {
+    &self.contents
+  }
+
+  /// The number of elements in this list.
+  pub fn len(&self) -> usize {
+    self.contents.len()
+  }
+
+  /// Whether this list has no elements.
+  pub fn is_empty(&self) -> bool {
+    self.contents.is_empty()
+  }
+
+  /// Returns the element at `index`, or `None` if it's out of bounds.
+  pub fn get(&self, index: usize) -> Option<&Value> {
+    self.contents.get(index)
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassList#separator)
+  pub fn separator(&self) -> ListSeparator {
+    self.separator
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassList#hasBrackets)
+  pub fn has_brackets(&self) -> bool {
+    self.has_brackets
+  }
+}
+
+/// Writes a list the way Sass's `meta.inspect()` would.
+pub(crate) fn write_list(
+  f: &mut fmt::Formatter<'_>,
+  contents: &[Value],
+  separator: ListSeparator,
+  has_brackets: bool,
+) -> fmt::Result {
+  let (open, close) = if has_brackets { ("[", "]") } else { ("(", ")") };
+  if contents.is_empty() {
+    return write!(f, "{}{}", open, close);
+  }
+  let single_comma = contents.len() == 1 && separator == ListSeparator::Comma;
+  if has_brackets || single_comma {
+    write!(f, "{}", open)?;
+  }
+  for (i, value) in contents.iter().enumerate() {
+    if i > 0 {
+      write!(f, "{}", separator.joiner())?;
+    }
+    if element_needs_parens(separator, value) {
+      write!(f, "({})", value)?;
+    } else {
+      write!(f, "{}", value)?;
+    }
+  }
+  if single_comma {
+    write!(f, ",")?;
+  }
+  if has_brackets || single_comma {
+    write!(f, "{}", close)?;
+  }
+  Ok(())
+}
+
+/// Returns whether `value` needs parentheses as an element of a list with
+/// the given separator.
+fn element_needs_parens(separator: ListSeparator, value: &Value) -> bool {
+  let (len, inner, brackets) = match value {
+    Value::List(l) => (l.contents.len(), l.separator, l.has_brackets),
+    Value::ArgumentList(l) => (l.contents().len(), l.separator(), false),
+    _ => return false,
+  };
+  if len < 2 || brackets {
+    return false;
+  }
+  match separator {
+    ListSeparator::Comma => inner == ListSeparator::Comma,
+    ListSeparator::Slash => {
+      inner == ListSeparator::Comma || inner == ListSeparator::Slash
+    }
+    _ => inner != ListSeparator::Undecided,
+  }
+}
+
+impl fmt::Display for SassList {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write_list(f, &self.contents, self.separator, self.has_brackets)
+  }
+}
+
+impl TryFrom<protocol::value::List> for SassList {
+  type Error = Box<Exception>;
+
+  fn try_from(list: protocol::value::List) -> Result<Self> {
+    Ok(Self {
+      contents: list
+        .contents
+        .into_iter()
+        .map(Value::try_from)
+        .collect::<Result<_>>()?,
+      separator: list.separator.try_into()?,
+      has_brackets: list.has_brackets,
+    })
+  }
+}
+
+impl From<SassList> for protocol::value::List {
+  fn from(list: SassList) -> Self {
+    Self {
+      separator: protocol::ListSeparator::from(list.separator) as i32,
+      has_brackets: list.has_brackets,
+      contents: list.contents.into_iter().map(Into::into).collect(),
+    }
+  }
+}
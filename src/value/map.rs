@@ -0,0 +1,155 @@
+use std::{
+  collections::hash_map::DefaultHasher,
+  fmt,
+  hash::{Hash, Hasher},
+};
+
+use crate::{protocol, Exception, Result};
+
+use super::{ListSeparator, Value};
+
+/// A SassScript map.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassMap)
+#[derive(Debug, Clone, Default)]
+pub struct SassMap {
+  contents: Vec<(Value, Value)>,
+}
+
+impl SassMap {
+  /// Creates a new map from its key/value pairs. If a key appears more than
+  /// once, the last value wins but the key keeps its first position.
+  pub fn new(contents: impl IntoIterator<Item = (Value, Value)>) -> Self {
+    let mut map = Self::empty();
+    for (key, value) in contents {
+      match map.contents.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, v)) => *v = value,
+        None => map.contents.push((key, value)),
+      }
+    }
+    map
+  }
+
+  /// Creates an empty map.
+  pub const fn empty() -> Self {
+    Self {
+      contents: Vec::new(),
+    }
+  }
+
+  /// The key/value pairs of this map, in insertion order.
+  pub fn contents(&self) -> &[(Value, Value)] {
+    &self.contents
+  }
+
+  /// The keys of this map, in insertion order.
+  pub fn keys(&self) -> impl Iterator<Item = &Value> {
+    self.contents.iter().map(|(k, _)| k)
+  }
+
+  /// The values of this map, in insertion order.
+  pub fn values(&self) -> impl Iterator<Item = &Value> {
+    self.contents.iter().map(|(_, v)| v)
+  }
+
+  /// The number of entries in this map.
+  pub fn len(&self) -> usize {
+    self.contents.len()
+  }
+
+  /// Whether this map has no entries.
+  pub fn is_empty(&self) -> bool {
+    self.contents.is_empty()
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassMap#get)
+  pub fn get(&self, key: &Value) -> Option<&Value> {
+    self.contents.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+  }
+}
+
+/// Maps are equal if they have the same entries, regardless of order.
+impl PartialEq for SassMap {
+  fn eq(&self, other: &Self) -> bool {
+    self.contents.len() == other.contents.len()
+      && self.contents.iter().all(|(k, v)| other.get(k) == Some(v))
+  }
+}
+
+impl Eq for SassMap {}
+
+impl Hash for SassMap {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    // Combine entries commutatively so the hash doesn't depend on order.
+    let combined = self.contents.iter().fold(0u64, |acc, entry| {
+      let mut hasher = DefaultHasher::new();
+      entry.hash(&mut hasher);
+      acc.wrapping_add(hasher.finish())
+    });
+    combined.hash(state);
+  }
+}
+
+impl fmt::Display for SassMap {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let write_element = |f: &mut fmt::Formatter<'_>, value: &Value| {
+      let needs_parens = matches!(
+        value,
+        Value::List(l) if l.len() > 1
+          && l.separator() == ListSeparator::Comma
+          && !l.has_brackets()
+      );
+      if needs_parens {
+        write!(f, "({})", value)
+      } else {
+        write!(f, "{}", value)
+      }
+    };
+    write!(f, "(")?;
+    for (i, (key, value)) in self.contents.iter().enumerate() {
+      if i > 0 {
+        write!(f, ", ")?;
+      }
+      write_element(f, key)?;
+      write!(f, ": ")?;
+      write_element(f, value)?;
+    }
+    write!(f, ")")
+  }
+}
+
+impl TryFrom<protocol::value::Map> for SassMap {
+  type Error = Box<Exception>;
+
+  fn try_from(map: protocol::value::Map) -> Result<Self> {
+    let contents = map
+      .entries
+      .into_iter()
+      .map(|entry| {
+        let key = entry
+          .key
+          .ok_or_else(|| Exception::new("Value.Map.Entry.key is mandatory"))?;
+        let value = entry.value.ok_or_else(|| {
+          Exception::new("Value.Map.Entry.value is mandatory")
+        })?;
+        Ok((key.try_into()?, value.try_into()?))
+      })
+      .collect::<Result<_>>()?;
+    Ok(Self { contents })
+  }
+}
+
+impl From<SassMap> for protocol::value::Map {
+  fn from(map: SassMap) -> Self {
+    Self {
+      entries: map
+        .contents
+        .into_iter()
+        .map(|(key, value)| protocol::value::map::Entry {
+          key: Some(key.into()),
+          value: Some(value.into()),
+        })
+        .collect(),
+    }
+  }
+}
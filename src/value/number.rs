@@ -0,0 +1,731 @@
+use std::{
+  fmt,
+  hash::{Hash, Hasher},
+};
+
+use crate::{protocol, Exception, Result};
+
+use super::unit;
+
+/// The number of decimal digits of precision Sass uses for numbers.
+pub(crate) const PRECISION: i32 = 10;
+
+/// The maximum difference between two numbers that Sass considers equal.
+pub(crate) const EPSILON: f64 = 1e-11;
+
+/// Returns whether `a` and `b` are equal up to Sass's [PRECISION].
+pub(crate) fn fuzzy_equals(a: f64, b: f64) -> bool {
+  a == b || (a - b).abs() <= EPSILON
+}
+
+/// Returns a hash code for `n` that's consistent with [fuzzy_equals].
+pub(crate) fn fuzzy_hash<H: Hasher>(n: f64, state: &mut H) {
+  if n.is_finite() {
+    ((n * 1e11).round() as i64).hash(state);
+  } else {
+    n.to_bits().hash(state);
+  }
+}
+
+/// Returns `n` as an integer if it's fuzzy-equal to one.
+pub(crate) fn fuzzy_as_int(n: f64) -> Option<i64> {
+  if !n.is_finite() {
+    return None;
+  }
+  let rounded = n.round();
+  fuzzy_equals(n, rounded).then_some(rounded as i64)
+}
+
+/// Rounds `n` to the nearest integer, treating numbers fuzzy-equal to `.5`
+/// as `.5`.
+pub(crate) fn fuzzy_round(n: f64) -> f64 {
+  let fraction = n.rem_euclid(1.0);
+  if n > 0.0 {
+    if fraction < 0.5 && !fuzzy_equals(fraction, 0.5) {
+      n.floor()
+    } else {
+      n.ceil()
+    }
+  } else if fraction < 0.5 || fuzzy_equals(fraction, 0.5) {
+    n.floor()
+  } else {
+    n.ceil()
+  }
+}
+
+/// Formats `n` the way Sass would write it to CSS.
+pub(crate) fn format_number(n: f64) -> String {
+  if n.is_nan() {
+    return "NaN".to_string();
+  }
+  if n.is_infinite() {
+    return if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+  }
+  if let Some(int) = fuzzy_as_int(n) {
+    return int.to_string();
+  }
+  let formatted = format!("{:.*}", PRECISION as usize, n);
+  let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+  if formatted == "-0" {
+    "0".to_string()
+  } else {
+    formatted.to_string()
+  }
+}
+
+/// A SassScript number.
+///
+/// Units are converted using the conversions defined in the CSS Values and
+/// Units spec, and arithmetic raises the same errors Dart Sass would.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber)
+#[derive(Debug, Clone)]
+pub struct SassNumber {
+  value: f64,
+  numerator_units: Vec<String>,
+  denominator_units: Vec<String>,
+}
+
+impl SassNumber {
+  /// Creates a unitless number.
+  pub fn new(value: f64) -> Self {
+    Self::with_units(value, Vec::<String>::new(), Vec::<String>::new())
+  }
+
+  /// Creates a number with a single numerator unit.
+  pub fn with_unit(value: f64, unit: impl Into<String>) -> Self {
+    Self::with_units(value, vec![unit.into()], Vec::<String>::new())
+  }
+
+  /// Creates a number with the given numerator and denominator units.
+  /// Compatible numerator and denominator units are cancelled out, so
+  /// `with_units(1.0, ["in"], ["px"])` is the unitless number `96`.
+  pub fn with_units(
+    value: f64,
+    numerator_units: impl IntoIterator<Item = impl Into<String>>,
+    denominator_units: impl IntoIterator<Item = impl Into<String>>,
+  ) -> Self {
+    Self::multiply_units(
+      value,
+      numerator_units.into_iter().map(Into::into).collect(),
+      Vec::new(),
+      Vec::new(),
+      denominator_units.into_iter().map(Into::into).collect(),
+    )
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#value)
+  pub fn value(&self) -> f64 {
+    self.value
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#numeratorUnits)
+  pub fn numerator_units(&self) -> &[String] {
+    &self.numerator_units
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#denominatorUnits)
+  pub fn denominator_units(&self) -> &[String] {
+    &self.denominator_units
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#hasUnits)
+  pub fn has_units(&self) -> bool {
+    !self.numerator_units.is_empty() || !self.denominator_units.is_empty()
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#hasUnit)
+  pub fn has_unit(&self, unit: &str) -> bool {
+    self.numerator_units.len() == 1
+      && self.denominator_units.is_empty()
+      && self.numerator_units[0] == unit
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#compatibleWithUnit)
+  pub fn compatible_with_unit(&self, unit: &str) -> bool {
+    self.numerator_units.len() == 1
+      && self.denominator_units.is_empty()
+      && unit::conversion_factor(&self.numerator_units[0], unit).is_some()
+  }
+
+  /// Returns whether this number can be converted to `other`'s units, or
+  /// either of them is unitless.
+  pub fn is_comparable_to(&self, other: &SassNumber) -> bool {
+    !self.has_units()
+      || !other.has_units()
+      || self.convert_value_to_match(other, None, None).is_ok()
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#isInt)
+  pub fn is_int(&self) -> bool {
+    fuzzy_as_int(self.value).is_some()
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#asInt)
+  pub fn as_int(&self) -> Option<i64> {
+    fuzzy_as_int(self.value)
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#assertInt)
+  pub fn assert_int(&self, name: Option<&str>) -> Result<i64> {
+    self.as_int().ok_or_else(|| {
+      Exception::argument(format!("{} is not an int.", self), name).into()
+    })
+  }
+
+  /// Returns the value of this number if it's fuzzy-within `min` and `max`,
+  /// clamping it to the range if it's just outside of it.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#assertInRange)
+  pub fn assert_in_range(
+    &self,
+    min: f64,
+    max: f64,
+    name: Option<&str>,
+  ) -> Result<f64> {
+    if fuzzy_equals(self.value, min) {
+      Ok(min)
+    } else if fuzzy_equals(self.value, max) {
+      Ok(max)
+    } else if self.value > min && self.value < max {
+      Ok(self.value)
+    } else {
+      let unit = self.unit_string();
+      Err(
+        Exception::argument(
+          format!(
+            "Expected {} to be within {}{} and {}{}.",
+            self,
+            format_number(min),
+            unit,
+            format_number(max),
+            unit
+          ),
+          name,
+        )
+        .into(),
+      )
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#assertUnit)
+  pub fn assert_unit(&self, unit: &str, name: Option<&str>) -> Result<&Self> {
+    if self.has_unit(unit) {
+      Ok(self)
+    } else {
+      Err(
+        Exception::argument(
+          format!("Expected {} to have unit \"{}\".", self, unit),
+          name,
+        )
+        .into(),
+      )
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#assertNoUnits)
+  pub fn assert_no_units(&self, name: Option<&str>) -> Result<&Self> {
+    if self.has_units() {
+      Err(
+        Exception::argument(
+          format!("Expected {} to have no units.", self),
+          name,
+        )
+        .into(),
+      )
+    } else {
+      Ok(self)
+    }
+  }
+
+  /// Returns a copy of this number converted to the given units. Throws if
+  /// the units are incompatible, or if exactly one of the numbers is
+  /// unitless.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#convert)
+  pub fn convert(
+    &self,
+    numerator_units: &[&str],
+    denominator_units: &[&str],
+    name: Option<&str>,
+  ) -> Result<SassNumber> {
+    let value = self.convert_value(numerator_units, denominator_units, name)?;
+    Ok(Self::from_parts(value, numerator_units, denominator_units))
+  }
+
+  /// Like [SassNumber::convert], but only returns the converted value.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#convertValue)
+  pub fn convert_value(
+    &self,
+    numerator_units: &[&str],
+    denominator_units: &[&str],
+    name: Option<&str>,
+  ) -> Result<f64> {
+    self.coerce_or_convert_value(
+      numerator_units,
+      denominator_units,
+      false,
+      name,
+      None,
+    )
+  }
+
+  /// Returns a copy of this number converted to the units of `other`.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#convertToMatch)
+  pub fn convert_to_match(
+    &self,
+    other: &SassNumber,
+    name: Option<&str>,
+    other_name: Option<&str>,
+  ) -> Result<SassNumber> {
+    let value = self.convert_value_to_match(other, name, other_name)?;
+    Ok(other.with_value(value))
+  }
+
+  /// Like [SassNumber::convert_to_match], but only returns the converted
+  /// value.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#convertValueToMatch)
+  pub fn convert_value_to_match(
+    &self,
+    other: &SassNumber,
+    name: Option<&str>,
+    other_name: Option<&str>,
+  ) -> Result<f64> {
+    self.coerce_or_convert_value(
+      &other.numerator_strs(),
+      &other.denominator_strs(),
+      false,
+      name,
+      Some((other, other_name)),
+    )
+  }
+
+  /// Returns a copy of this number converted to the given units. Unlike
+  /// [SassNumber::convert], a unitless number is treated as though it has
+  /// the new units, and the new units may be empty.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#coerce)
+  pub fn coerce(
+    &self,
+    numerator_units: &[&str],
+    denominator_units: &[&str],
+    name: Option<&str>,
+  ) -> Result<SassNumber> {
+    let value = self.coerce_value(numerator_units, denominator_units, name)?;
+    Ok(Self::from_parts(value, numerator_units, denominator_units))
+  }
+
+  /// Like [SassNumber::coerce], but only returns the coerced value.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#coerceValue)
+  pub fn coerce_value(
+    &self,
+    numerator_units: &[&str],
+    denominator_units: &[&str],
+    name: Option<&str>,
+  ) -> Result<f64> {
+    self.coerce_or_convert_value(
+      numerator_units,
+      denominator_units,
+      true,
+      name,
+      None,
+    )
+  }
+
+  /// Returns a copy of this number coerced to the units of `other`.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#coerceToMatch)
+  pub fn coerce_to_match(
+    &self,
+    other: &SassNumber,
+    name: Option<&str>,
+    other_name: Option<&str>,
+  ) -> Result<SassNumber> {
+    let value = self.coerce_value_to_match(other, name, other_name)?;
+    Ok(other.with_value(value))
+  }
+
+  /// Like [SassNumber::coerce_to_match], but only returns the coerced value.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassNumber#coerceValueToMatch)
+  pub fn coerce_value_to_match(
+    &self,
+    other: &SassNumber,
+    name: Option<&str>,
+    other_name: Option<&str>,
+  ) -> Result<f64> {
+    self.coerce_or_convert_value(
+      &other.numerator_strs(),
+      &other.denominator_strs(),
+      true,
+      name,
+      Some((other, other_name)),
+    )
+  }
+
+  /// Adds `other` to this number, as Sass's `+` operator does.
+  pub fn plus(&self, other: &SassNumber) -> Result<SassNumber> {
+    self.operate(other, |a, b| a + b)
+  }
+
+  /// Subtracts `other` from this number, as Sass's `-` operator does.
+  pub fn minus(&self, other: &SassNumber) -> Result<SassNumber> {
+    self.operate(other, |a, b| a - b)
+  }
+
+  /// Multiplies this number by `other`, as Sass's `*` operator does.
+  pub fn times(&self, other: &SassNumber) -> Result<SassNumber> {
+    Ok(Self::multiply_units(
+      self.value * other.value,
+      self.numerator_units.clone(),
+      self.denominator_units.clone(),
+      other.numerator_units.clone(),
+      other.denominator_units.clone(),
+    ))
+  }
+
+  /// Divides this number by `other`, as Sass's `math.div()` function does.
+  pub fn divided_by(&self, other: &SassNumber) -> Result<SassNumber> {
+    Ok(Self::multiply_units(
+      self.value / other.value,
+      self.numerator_units.clone(),
+      self.denominator_units.clone(),
+      other.denominator_units.clone(),
+      other.numerator_units.clone(),
+    ))
+  }
+
+  /// Returns the remainder of dividing this number by `other`, as Sass's `%`
+  /// operator does. The result has the same sign as `other`.
+  pub fn modulo(&self, other: &SassNumber) -> Result<SassNumber> {
+    self.operate(other, |a, b| {
+      if a.is_infinite() {
+        f64::NAN
+      } else if b.is_infinite() {
+        if a.is_sign_negative() == b.is_sign_negative() {
+          a
+        } else {
+          f64::NAN
+        }
+      } else if b == 0.0 {
+        f64::NAN
+      } else if b > 0.0 {
+        a.rem_euclid(b)
+      } else {
+        let result = a.rem_euclid(b);
+        if result == 0.0 {
+          0.0
+        } else {
+          result + b
+        }
+      }
+    })
+  }
+
+  /// Returns this number negated, as Sass's unary `-` operator does.
+  pub fn unary_minus(&self) -> SassNumber {
+    self.with_value(-self.value)
+  }
+
+  /// Returns whether this number is greater than `other`, as Sass's `>`
+  /// operator does.
+  pub fn greater_than(&self, other: &SassNumber) -> Result<bool> {
+    self.compare(other, |a, b| a > b && !fuzzy_equals(a, b))
+  }
+
+  /// Returns whether this number is greater than or equal to `other`, as
+  /// Sass's `>=` operator does.
+  pub fn greater_than_or_equals(&self, other: &SassNumber) -> Result<bool> {
+    self.compare(other, |a, b| a > b || fuzzy_equals(a, b))
+  }
+
+  /// Returns whether this number is less than `other`, as Sass's `<`
+  /// operator does.
+  pub fn less_than(&self, other: &SassNumber) -> Result<bool> {
+    self.compare(other, |a, b| a < b && !fuzzy_equals(a, b))
+  }
+
+  /// Returns whether this number is less than or equal to `other`, as Sass's
+  /// `<=` operator does.
+  pub fn less_than_or_equals(&self, other: &SassNumber) -> Result<bool> {
+    self.compare(other, |a, b| a < b || fuzzy_equals(a, b))
+  }
+
+  /// Returns the unit string of this number, such as `"px*em/s"`.
+  pub(crate) fn unit_string(&self) -> String {
+    unit_string(&self.numerator_strs(), &self.denominator_strs())
+  }
+
+  /// Returns a number with the same units as this one and the given value.
+  fn with_value(&self, value: f64) -> SassNumber {
+    SassNumber {
+      value,
+      numerator_units: self.numerator_units.clone(),
+      denominator_units: self.denominator_units.clone(),
+    }
+  }
+
+  fn from_parts(
+    value: f64,
+    numerator_units: &[&str],
+    denominator_units: &[&str],
+  ) -> SassNumber {
+    SassNumber {
+      value,
+      numerator_units: numerator_units.iter().map(|u| u.to_string()).collect(),
+      denominator_units: denominator_units
+        .iter()
+        .map(|u| u.to_string())
+        .collect(),
+    }
+  }
+
+  fn numerator_strs(&self) -> Vec<&str> {
+    self.numerator_units.iter().map(String::as_str).collect()
+  }
+
+  fn denominator_strs(&self) -> Vec<&str> {
+    self.denominator_units.iter().map(String::as_str).collect()
+  }
+
+  /// Applies `operation` to the values of two numbers whose units must be
+  /// compatible, converting `other` to this number's units.
+  fn operate(
+    &self,
+    other: &SassNumber,
+    operation: impl Fn(f64, f64) -> f64,
+  ) -> Result<SassNumber> {
+    if self.has_units() {
+      let other_value = self.coerce_operand(other)?;
+      Ok(self.with_value(operation(self.value, other_value)))
+    } else {
+      Ok(other.with_value(operation(self.value, other.value)))
+    }
+  }
+
+  fn compare(
+    &self,
+    other: &SassNumber,
+    comparison: impl Fn(f64, f64) -> bool,
+  ) -> Result<bool> {
+    let other_value = self.coerce_operand(other)?;
+    Ok(comparison(self.value, other_value))
+  }
+
+  /// Coerces the value of `other` to this number's units for use as the
+  /// right-hand side of a binary operation.
+  fn coerce_operand(&self, other: &SassNumber) -> Result<f64> {
+    other.coerce_value_to_match(self, None, None).map_err(|_| {
+      Exception::new(format!("{} and {} have incompatible units.", self, other))
+        .into()
+    })
+  }
+
+  fn coerce_or_convert_value(
+    &self,
+    numerator_units: &[&str],
+    denominator_units: &[&str],
+    coerce_unitless: bool,
+    name: Option<&str>,
+    other: Option<(&SassNumber, Option<&str>)>,
+  ) -> Result<f64> {
+    if self.numerator_strs() == numerator_units
+      && self.denominator_strs() == denominator_units
+    {
+      return Ok(self.value);
+    }
+    let other_has_units =
+      !numerator_units.is_empty() || !denominator_units.is_empty();
+    if coerce_unitless && (!self.has_units() || !other_has_units) {
+      return Ok(self.value);
+    }
+
+    let compatibility_error = || -> Box<Exception> {
+      let message = if let Some((other, other_name)) = other {
+        let mut message = format!("{} and ", self);
+        if let Some(other_name) = other_name {
+          message.push_str(&format!("${}: ", other_name));
+        }
+        message.push_str(&format!("{} have incompatible units", other));
+        if !self.has_units() || !other_has_units {
+          message.push_str(" (one has units and the other doesn't)");
+        }
+        message.push('.');
+        message
+      } else if !other_has_units {
+        format!("Expected {} to have no units.", self)
+      } else if let (Some((ty, units)), 1, 0) = (
+        numerator_units.first().and_then(|u| unit::unit_type(u)),
+        numerator_units.len(),
+        denominator_units.len(),
+      ) {
+        let article = if ty.starts_with(['a', 'e', 'i', 'o', 'u']) {
+          "an"
+        } else {
+          "a"
+        };
+        format!(
+          "Expected {} to have {} {} unit ({}).",
+          self,
+          article,
+          ty,
+          units.join(", ")
+        )
+      } else {
+        let unit = unit_string(numerator_units, denominator_units);
+        format!(
+          "Expected {} to have unit{} {}.",
+          self,
+          if unit.contains('*') { "s" } else { "" },
+          unit
+        )
+      };
+      Exception::argument(message, name).into()
+    };
+
+    let mut value = self.value;
+    let mut old_numerators = self.numerator_strs();
+    for new in numerator_units {
+      let index = old_numerators
+        .iter()
+        .position(|old| unit::conversion_factor(old, new).is_some())
+        .ok_or_else(compatibility_error)?;
+      let old = old_numerators.remove(index);
+      value *= unit::conversion_factor(old, new).unwrap();
+    }
+    let mut old_denominators = self.denominator_strs();
+    for new in denominator_units {
+      let index = old_denominators
+        .iter()
+        .position(|old| unit::conversion_factor(old, new).is_some())
+        .ok_or_else(compatibility_error)?;
+      let old = old_denominators.remove(index);
+      value /= unit::conversion_factor(old, new).unwrap();
+    }
+    if !old_numerators.is_empty() || !old_denominators.is_empty() {
+      return Err(compatibility_error());
+    }
+    Ok(value)
+  }
+
+  /// Multiplies a number with the units `numerators1 / denominators1` by a
+  /// number with the units `numerators2 / denominators2`, cancelling out any
+  /// compatible numerator and denominator units.
+  fn multiply_units(
+    mut value: f64,
+    numerators1: Vec<String>,
+    denominators1: Vec<String>,
+    numerators2: Vec<String>,
+    denominators2: Vec<String>,
+  ) -> SassNumber {
+    let mut numerators = Vec::new();
+    let mut denominators1 = denominators1;
+    let mut denominators2 = denominators2;
+    let mut cancel = |numerator: String, denominators: &mut Vec<String>| {
+      let factor = denominators.iter().enumerate().find_map(|(i, d)| {
+        unit::conversion_factor(&numerator, d).map(|factor| (i, factor))
+      });
+      match factor {
+        Some((i, factor)) => {
+          denominators.remove(i);
+          value *= factor;
+        }
+        None => numerators.push(numerator),
+      }
+    };
+    for numerator in numerators1 {
+      cancel(numerator, &mut denominators2);
+    }
+    for numerator in numerators2 {
+      cancel(numerator, &mut denominators1);
+    }
+    denominators1.append(&mut denominators2);
+    SassNumber {
+      value,
+      numerator_units: numerators,
+      denominator_units: denominators1,
+    }
+  }
+
+  fn canonical_value(&self) -> f64 {
+    let numerators: f64 = self
+      .numerator_units
+      .iter()
+      .map(|u| unit::canonical_multiplier(u))
+      .product();
+    let denominators: f64 = self
+      .denominator_units
+      .iter()
+      .map(|u| unit::canonical_multiplier(u))
+      .product();
+    self.value * numerators / denominators
+  }
+}
+
+/// Returns a unit string such as `"px*em/s"` for the given units.
+fn unit_string(numerators: &[&str], denominators: &[&str]) -> String {
+  let numerator = numerators.join("*");
+  match denominators.len() {
+    0 => numerator,
+    1 if numerators.is_empty() => format!("{}^-1", denominators[0]),
+    _ if numerators.is_empty() => format!("({})^-1", denominators.join("*")),
+    _ => format!("{}/{}", numerator, denominators.join("/")),
+  }
+}
+
+/// Numbers are equal if they have compatible units and their values are
+/// fuzzy-equal once converted to the same units.
+impl PartialEq for SassNumber {
+  fn eq(&self, other: &Self) -> bool {
+    if self.numerator_units.len() != other.numerator_units.len()
+      || self.denominator_units.len() != other.denominator_units.len()
+    {
+      return false;
+    }
+    if !self.has_units() {
+      return fuzzy_equals(self.value, other.value);
+    }
+    match other.convert_value_to_match(self, None, None) {
+      Ok(value) => fuzzy_equals(self.value, value),
+      Err(_) => false,
+    }
+  }
+}
+
+impl Eq for SassNumber {}
+
+impl Hash for SassNumber {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    fuzzy_hash(self.canonical_value(), state);
+  }
+}
+
+impl fmt::Display for SassNumber {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", format_number(self.value), self.unit_string())
+  }
+}
+
+impl From<protocol::value::Number> for SassNumber {
+  fn from(n: protocol::value::Number) -> Self {
+    Self {
+      value: n.value,
+      numerator_units: n.numerators,
+      denominator_units: n.denominators,
+    }
+  }
+}
+
+impl From<SassNumber> for protocol::value::Number {
+  fn from(n: SassNumber) -> Self {
+    Self {
+      value: n.value,
+      numerators: n.numerator_units,
+      denominators: n.denominator_units,
+    }
+  }
+}
@@ -0,0 +1,116 @@
+use std::{
+  fmt,
+  hash::{Hash, Hasher},
+};
+
+use crate::protocol;
+
+/// A SassScript string.
+///
+/// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassString)
+#[derive(Debug, Clone)]
+pub struct SassString {
+  text: String,
+  quoted: bool,
+}
+
+impl SassString {
+  /// Creates a new quoted string.
+  pub fn new(text: impl Into<String>) -> Self {
+    Self {
+      text: text.into(),
+      quoted: true,
+    }
+  }
+
+  /// Creates a new unquoted string.
+  pub fn unquoted(text: impl Into<String>) -> Self {
+    Self {
+      text: text.into(),
+      quoted: false,
+    }
+  }
+
+  /// Creates an empty string.
+  pub fn empty(quoted: bool) -> Self {
+    Self {
+      text: String::new(),
+      quoted,
+    }
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassString#text)
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassString#hasQuotes)
+  pub fn has_quotes(&self) -> bool {
+    self.quoted
+  }
+
+  /// The number of Unicode code points in this string, which is what Sass
+  /// uses as the string's length.
+  ///
+  /// More information: [Sass documentation](https://sass-lang.com/documentation/js-api/classes/SassString#sassLength)
+  pub fn sass_length(&self) -> usize {
+    self.text.chars().count()
+  }
+}
+
+/// Strings are equal if their text is equal, regardless of quotes.
+impl PartialEq for SassString {
+  fn eq(&self, other: &Self) -> bool {
+    self.text == other.text
+  }
+}
+
+impl Eq for SassString {}
+
+impl Hash for SassString {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.text.hash(state);
+  }
+}
+
+impl fmt::Display for SassString {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.quoted {
+      let quote = if self.text.contains('"') && !self.text.contains('\'') {
+        '\''
+      } else {
+        '"'
+      };
+      write!(f, "{}", quote)?;
+      for c in self.text.chars() {
+        match c {
+          '\\' => write!(f, "\\\\")?,
+          '\n' => write!(f, "\\a ")?,
+          c if c == quote => write!(f, "\\{}", c)?,
+          c => write!(f, "{}", c)?,
+        }
+      }
+      write!(f, "{}", quote)
+    } else {
+      write!(f, "{}", self.text)
+    }
+  }
+}
+
+impl From<protocol::value::String> for SassString {
+  fn from(s: protocol::value::String) -> Self {
+    Self {
+      text: s.text,
+      quoted: s.quoted,
+    }
+  }
+}
+
+impl From<SassString> for protocol::value::String {
+  fn from(s: SassString) -> Self {
+    Self {
+      text: s.text,
+      quoted: s.quoted,
+    }
+  }
+}
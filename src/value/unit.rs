@@ -0,0 +1,71 @@
+//! Conversions between the units Sass knows how to convert.
+//!
+//! More information: [CSS Values and Units](https://www.w3.org/TR/css-values-4/#compat)
+
+use std::f64::consts::PI;
+
+/// Each unit Sass can convert, along with its type and how many of the
+/// type's canonical unit it's worth.
+const UNITS: &[(&str, &str, f64)] = &[
+  // Length, canonicalized to `px`.
+  ("in", "length", 96.0),
+  ("cm", "length", 96.0 / 2.54),
+  ("mm", "length", 96.0 / 25.4),
+  ("q", "length", 96.0 / 101.6),
+  ("pt", "length", 4.0 / 3.0),
+  ("pc", "length", 16.0),
+  ("px", "length", 1.0),
+  // Angle, canonicalized to `deg`.
+  ("deg", "angle", 1.0),
+  ("grad", "angle", 360.0 / 400.0),
+  ("rad", "angle", 180.0 / PI),
+  ("turn", "angle", 360.0),
+  // Time, canonicalized to `s`.
+  ("s", "time", 1.0),
+  ("ms", "time", 1.0 / 1000.0),
+  // Frequency, canonicalized to `Hz`.
+  ("Hz", "frequency", 1.0),
+  ("kHz", "frequency", 1000.0),
+  // Pixel density, canonicalized to `dppx`.
+  ("dpi", "pixel density", 1.0 / 96.0),
+  ("dpcm", "pixel density", 2.54 / 96.0),
+  ("dppx", "pixel density", 1.0),
+];
+
+fn lookup(unit: &str) -> Option<(&'static str, f64)> {
+  UNITS
+    .iter()
+    .find(|(u, _, _)| *u == unit)
+    .map(|(_, ty, factor)| (*ty, *factor))
+}
+
+/// Returns the number of `to`s per `from`, or `None` if they aren't
+/// compatible.
+pub(crate) fn conversion_factor(from: &str, to: &str) -> Option<f64> {
+  if from == to {
+    return Some(1.0);
+  }
+  let (from_type, from_factor) = lookup(from)?;
+  let (to_type, to_factor) = lookup(to)?;
+  (from_type == to_type).then(|| from_factor / to_factor)
+}
+
+/// Returns how many of its type's canonical unit `unit` is worth, or `1` for
+/// units Sass can't convert.
+pub(crate) fn canonical_multiplier(unit: &str) -> f64 {
+  lookup(unit).map_or(1.0, |(_, factor)| factor)
+}
+
+/// Returns the name of the type of `unit` and all units of that type, if
+/// Sass knows how to convert it.
+pub(crate) fn unit_type(
+  unit: &str,
+) -> Option<(&'static str, Vec<&'static str>)> {
+  let (ty, _) = lookup(unit)?;
+  let units = UNITS
+    .iter()
+    .filter(|(_, t, _)| *t == ty)
+    .map(|(u, _, _)| *u)
+    .collect();
+  Some((ty, units))
+}
@@ -1,27 +1,69 @@
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 
-pub fn read<R: Read>(readable: &mut R) -> usize {
-  let mut value = 0;
-  let mut bits = 0;
+/// Decodes a varint one byte at a time, failing once it doesn't fit in a
+/// `usize` instead of shifting past it.
+#[derive(Default)]
+struct Decoder {
+  value: u64,
+  bits: u32,
+}
+
+impl Decoder {
+  /// Adds the next byte, and returns the value once it's complete.
+  fn push(&mut self, byte: u8) -> io::Result<Option<usize>> {
+    let payload = u64::from(byte & 0x7f);
+    // A u64 takes at most 10 bytes, the last of which holds a single bit.
+    if self.bits >= u64::BITS || (payload << self.bits) >> self.bits != payload
+    {
+      return Err(overflow());
+    }
+    self.value |= payload << self.bits;
+    self.bits += 7;
+    if byte < 0x80 {
+      usize::try_from(self.value)
+        .map(Some)
+        .map_err(|_| overflow())
+    } else {
+      Ok(None)
+    }
+  }
+}
+
+fn overflow() -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, "varint is too long")
+}
+
+pub fn read<R: Read>(readable: &mut R) -> io::Result<usize> {
+  let mut decoder = Decoder::default();
   loop {
     let buf = &mut [0];
-    readable.read_exact(buf).unwrap();
-    let byte = buf[0];
-    value |= ((byte & 0x7f) as usize) << bits;
-    bits += 7;
-    if byte < 0x80 {
-      break;
+    readable.read_exact(buf)?;
+    if let Some(value) = decoder.push(buf[0])? {
+      return Ok(value);
     }
   }
-  value
 }
 
-pub fn write<W: Write>(writeable: &mut W, mut value: usize) {
+pub fn write<W: Write>(writeable: &mut W, mut value: usize) -> io::Result<()> {
   let mut bytes = Vec::<u8>::new();
   while value >= 0x80 {
     bytes.push(0x80 | (value & 0x7f) as u8);
     value >>= 7;
   }
   bytes.push(value as u8);
-  writeable.write_all(&bytes).unwrap();
+  writeable.write_all(&bytes)
+}
+
+#[cfg(feature = "async")]
+pub async fn read_async<R: tokio::io::AsyncRead + Unpin>(
+  readable: &mut R,
+) -> io::Result<usize> {
+  use tokio::io::AsyncReadExt;
+
+  let mut decoder = Decoder::default();
+  loop {
+    if let Some(value) = decoder.push(readable.read_u8().await?)? {
+      return Ok(value);
+    }
+  }
 }
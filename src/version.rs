@@ -0,0 +1,74 @@
+use semver::Version;
+
+use crate::{
+  protocol::outbound_message::VersionResponse, Exception, ExceptionKind, Result,
+};
+
+/// The version of the embedded protocol that this crate speaks, which
+/// `protocol.rs` was generated from.
+pub const PROTOCOL_VERSION: &str = "1.2.0";
+
+/// The versions that the compiler reports, see [Embedded::version].
+///
+/// [Embedded::version]: crate::Embedded::version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+  /// The version of the embedded protocol that the compiler speaks.
+  pub protocol_version: Version,
+  /// The version of the embedded compiler package.
+  pub compiler_version: Version,
+  /// The version of the Sass implementation that the compiler wraps.
+  pub implementation_version: Version,
+  /// The name of the Sass implementation, e.g. `"Dart Sass"`.
+  pub implementation_name: String,
+}
+
+impl VersionInfo {
+  /// Returns an [ExceptionKind::Version] exception unless the compiler speaks
+  /// a version of the embedded protocol that's compatible with
+  /// [PROTOCOL_VERSION]: the same major version, and not an older one.
+  pub fn check_compatible(&self) -> Result<()> {
+    let supported = Version::parse(PROTOCOL_VERSION).unwrap();
+    let version = &self.protocol_version;
+    if version.major != supported.major || version < &supported {
+      return Err(Box::new(
+        Exception::new(format!(
+          "The compiler speaks version {} of the embedded protocol, but \
+           version {}.x (at least {}) is required. Use a compiler that \
+           supports it, or a version of this crate that matches the compiler.",
+          version, supported.major, supported
+        ))
+        .with_kind(ExceptionKind::Version),
+      ));
+    }
+    Ok(())
+  }
+}
+
+impl TryFrom<VersionResponse> for VersionInfo {
+  type Error = Box<Exception>;
+
+  fn try_from(response: VersionResponse) -> Result<Self> {
+    Ok(Self {
+      protocol_version: parse("protocol", &response.protocol_version)?,
+      compiler_version: parse("compiler", &response.compiler_version)?,
+      implementation_version: parse(
+        "implementation",
+        &response.implementation_version,
+      )?,
+      implementation_name: response.implementation_name,
+    })
+  }
+}
+
+fn parse(name: &str, version: &str) -> Result<Version> {
+  Version::parse(version).map_err(|e| {
+    Box::new(
+      Exception::new(format!(
+        "The compiler reported an invalid {} version {:?}: {}",
+        name, version, e
+      ))
+      .with_kind(ExceptionKind::Version),
+    )
+  })
+}
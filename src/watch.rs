@@ -0,0 +1,226 @@
+use std::{
+  collections::{BTreeSet, HashMap, HashSet},
+  fs,
+  path::{Path, PathBuf},
+  time::Duration,
+};
+
+use crossbeam_channel::Receiver;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+  CancellationToken, CompileResult, Exception, ExceptionKind, Result,
+};
+
+/// Options for [Embedded::watch_with](crate::Embedded::watch_with).
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+  /// How long to wait for more changes once a file changed, so a burst of
+  /// changes, e.g. of a `git checkout`, recompiles each entrypoint once.
+  /// Defaults to 50 milliseconds.
+  pub debounce: Duration,
+  /// Stops watching once it's cancelled. Without one, watching only stops
+  /// if the file watcher can't be started.
+  pub cancellation_token: Option<CancellationToken>,
+}
+
+impl Default for WatchOptions {
+  fn default() -> Self {
+    Self {
+      debounce: Duration::from_millis(50),
+      cancellation_token: None,
+    }
+  }
+}
+
+/// The files each entrypoint depends on, and the other way around.
+#[derive(Debug, Default)]
+struct DependencyGraph {
+  /// The files each entrypoint loaded, by the index of the entrypoint.
+  dependencies: Vec<HashSet<PathBuf>>,
+  /// The indices of the entrypoints that loaded each file.
+  dependents: HashMap<PathBuf, BTreeSet<usize>>,
+}
+
+impl DependencyGraph {
+  fn set(&mut self, entry: usize, dependencies: HashSet<PathBuf>) {
+    if self.dependencies.len() <= entry {
+      self.dependencies.resize_with(entry + 1, HashSet::new);
+    }
+    for file in &self.dependencies[entry] {
+      if let Some(dependents) = self.dependents.get_mut(file) {
+        dependents.remove(&entry);
+        if dependents.is_empty() {
+          self.dependents.remove(file);
+        }
+      }
+    }
+    for file in &dependencies {
+      self
+        .dependents
+        .entry(file.clone())
+        .or_default()
+        .insert(entry);
+    }
+    self.dependencies[entry] = dependencies;
+  }
+
+  /// The entrypoints that depend on any of `files`, in order.
+  fn affected<'a>(
+    &self,
+    files: impl IntoIterator<Item = &'a PathBuf>,
+  ) -> BTreeSet<usize> {
+    files
+      .into_iter()
+      .filter_map(|file| self.dependents.get(file))
+      .flatten()
+      .copied()
+      .collect()
+  }
+
+  /// The directories of all the files, which are watched instead of the
+  /// files themselves, so files that are replaced on save are still seen.
+  fn directories(&self) -> HashSet<PathBuf> {
+    self
+      .dependents
+      .keys()
+      .filter_map(|file| file.parent())
+      .map(Path::to_path_buf)
+      .collect()
+  }
+}
+
+/// Compiles `entries`, and recompiles the ones that are affected whenever
+/// one of the files they loaded changes, until watching is cancelled.
+pub(crate) fn watch(
+  entries: &[PathBuf],
+  mut compile: impl FnMut(&Path) -> Result<CompileResult>,
+  mut callback: impl FnMut(&Path, Result<CompileResult>),
+  options: &WatchOptions,
+) -> Result<()> {
+  let (sender, events) = crossbeam_channel::unbounded();
+  let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+  let mut watched = HashSet::new();
+  let mut graph = DependencyGraph::default();
+  let never = crossbeam_channel::never();
+  let cancelled = options
+    .cancellation_token
+    .as_ref()
+    .map_or(&never, CancellationToken::receiver);
+
+  let mut build = |affected: BTreeSet<usize>, graph: &mut DependencyGraph| {
+    for index in affected {
+      let entry = &entries[index];
+      let result = compile(entry);
+      let mut dependencies = HashSet::from([normalize(entry)]);
+      match &result {
+        Ok(result) => dependencies.extend(
+          result
+            .loaded_urls
+            .iter()
+            .filter_map(|url| url.to_file_path().ok())
+            .map(|path| normalize(&path)),
+        ),
+        // Keeps watching what the entrypoint loaded before, so fixing the
+        // error recompiles it.
+        Err(_) => dependencies
+          .extend(graph.dependencies.get(index).into_iter().flatten().cloned()),
+      }
+      graph.set(index, dependencies);
+      callback(entry, result);
+    }
+  };
+
+  build((0..entries.len()).collect(), &mut graph);
+  loop {
+    sync_directories(&mut watcher, &mut watched, graph.directories());
+    let mut changed = match next_change(&events, cancelled, &graph, None) {
+      Some(changed) => changed,
+      None => return Ok(()),
+    };
+    // Waits until files stop changing.
+    loop {
+      match next_change(&events, cancelled, &graph, Some(options.debounce)) {
+        Some(more) if more.is_empty() => break,
+        Some(more) => changed.extend(more),
+        None => return Ok(()),
+      }
+    }
+    build(changed, &mut graph);
+  }
+}
+
+/// Waits for a change that affects an entrypoint, and returns the affected
+/// entrypoints, or `None` once watching stops.
+///
+/// With a `timeout`, returns an empty set if nothing changes in time.
+fn next_change(
+  events: &Receiver<notify::Result<notify::Event>>,
+  cancelled: &Receiver<()>,
+  graph: &DependencyGraph,
+  timeout: Option<Duration>,
+) -> Option<BTreeSet<usize>> {
+  let timeout = timeout
+    .map(crossbeam_channel::after)
+    .unwrap_or_else(crossbeam_channel::never);
+  loop {
+    let event = crossbeam_channel::select! {
+      recv(cancelled) -> _ => return None,
+      recv(events) -> event => event.ok()?,
+      recv(timeout) -> _ => return Some(BTreeSet::new()),
+    };
+    let affected = match event {
+      Ok(event) if matches!(event.kind, EventKind::Access(_)) => continue,
+      Ok(event) => {
+        let paths: Vec<_> = event.paths.iter().map(|p| normalize(p)).collect();
+        graph.affected(&paths)
+      }
+      // The watcher may have missed changes, so everything is rebuilt.
+      Err(_) => (0..graph.dependencies.len()).collect(),
+    };
+    if !affected.is_empty() {
+      return Some(affected);
+    }
+  }
+}
+
+/// Watches the `directories` that aren't watched yet, and stops watching the
+/// ones that are no longer needed.
+fn sync_directories(
+  watcher: &mut RecommendedWatcher,
+  watched: &mut HashSet<PathBuf>,
+  directories: HashSet<PathBuf>,
+) {
+  for dir in watched.difference(&directories) {
+    let _ = watcher.unwatch(dir);
+  }
+  // A directory that doesn't exist can't be watched, and is retried after
+  // the next change.
+  watched.retain(|dir| directories.contains(dir));
+  for dir in directories {
+    if !watched.contains(&dir)
+      && watcher.watch(&dir, RecursiveMode::NonRecursive).is_ok()
+    {
+      watched.insert(dir);
+    }
+  }
+}
+
+/// Resolves symlinks in `path`, or in its directory if it doesn't exist, so
+/// the paths of file events and loaded URLs can be compared.
+fn normalize(path: &Path) -> PathBuf {
+  if let Ok(path) = fs::canonicalize(path) {
+    return path;
+  }
+  match (path.parent().map(fs::canonicalize), path.file_name()) {
+    (Some(Ok(dir)), Some(name)) => dir.join(name),
+    _ => path.to_path_buf(),
+  }
+}
+
+fn watch_error(error: notify::Error) -> Box<Exception> {
+  Exception::new(format!("Failed to watch files: {}", error))
+    .with_kind(ExceptionKind::Io)
+    .set_source(error)
+    .into()
+}
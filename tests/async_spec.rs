@@ -0,0 +1,450 @@
+#![cfg(feature = "async")]
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::{sync::Arc, time::Duration};
+
+use helpers::{exe_path, Sandbox};
+use parking_lot::Mutex;
+use sass_embedded::{
+  async_trait, AsyncEmbedded, AsyncFileImporter, AsyncImporter, AsyncLogger,
+  ImporterOptions, ImporterResult, LoggerWarnOptions, Result, Sass,
+  StringOptionsBuilder, Syntax, Url,
+};
+
+#[tokio::test]
+async fn compiles_a_string() {
+  let sass = AsyncEmbedded::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string("a {b: c}", Default::default())
+    .await
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: c;\n}");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn fails_instead_of_hanging_when_the_compiler_exits() {
+  // `true` exits right away without reading or writing anything.
+  let sass = AsyncEmbedded::new("true").unwrap();
+  let err = sass
+    .compile_string("a {b: c}", Default::default())
+    .await
+    .unwrap_err();
+  let source = std::error::Error::source(&*err).unwrap();
+  assert!(source.is::<std::io::Error>());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn attaches_stderr_to_exceptions() {
+  use std::os::unix::fs::PermissionsExt;
+
+  let sandbox = Sandbox::default();
+  let path = sandbox.path().join("compiler.sh");
+  sandbox.write(&path, "#!/bin/sh\nhead -c 1 >/dev/null\necho boom >&2\n");
+  std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+    .unwrap();
+  let sass = AsyncEmbedded::new(path).unwrap();
+  let err = sass
+    .compile_string("a {b: c}", Default::default())
+    .await
+    .unwrap_err();
+  assert_eq!(err.compiler_stderr(), ["boom"]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn times_out_and_cancels() {
+  use std::os::unix::fs::PermissionsExt;
+
+  use sass_embedded::{CancellationToken, StringOptionsBuilder};
+
+  let sandbox = Sandbox::default();
+  let path = sandbox.path().join("compiler.sh");
+  sandbox.write(&path, "#!/bin/sh\ncat >/dev/null\n");
+  std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+    .unwrap();
+  let sass = AsyncEmbedded::new(path).unwrap();
+  let err = sass
+    .compile_string(
+      "a {b: c}",
+      StringOptionsBuilder::new()
+        .timeout(Duration::from_millis(50))
+        .build(),
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.message(), "Compilation timed out after 50ms.");
+
+  let token = CancellationToken::new();
+  let canceller = token.clone();
+  tokio::spawn(async move {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    canceller.cancel();
+  });
+  let err = sass
+    .compile_string(
+      "a {b: c}",
+      StringOptionsBuilder::new()
+        .cancellation_token(token)
+        .build(),
+    )
+    .await
+    .unwrap_err();
+  assert_eq!(err.message(), "Compilation was cancelled.");
+}
+
+#[tokio::test]
+async fn compiles_a_file() {
+  let sandbox = Sandbox::default();
+  sandbox.write(sandbox.path().join("input.scss"), "a {b: c}");
+  let sass = AsyncEmbedded::new(exe_path()).unwrap();
+  let res = sass
+    .compile(sandbox.path().join("input.scss"), Default::default())
+    .await
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: c;\n}");
+}
+
+#[tokio::test]
+async fn uses_an_async_importer() {
+  #[derive(Debug)]
+  struct MyImporter;
+
+  #[async_trait]
+  impl AsyncImporter for MyImporter {
+    async fn canonicalize(
+      &self,
+      url: &str,
+      _: &ImporterOptions,
+    ) -> Result<Option<Url>> {
+      tokio::task::yield_now().await;
+      Ok(Some(Url::parse(&format!("u:{url}")).unwrap()))
+    }
+
+    async fn load(
+      &self,
+      canonical_url: &Url,
+    ) -> Result<Option<ImporterResult>> {
+      tokio::task::yield_now().await;
+      let color = canonical_url.path();
+      Ok(Some(ImporterResult {
+        contents: format!(".{color} {{color: {color}}}"),
+        syntax: Syntax::Scss,
+        source_map_url: None,
+      }))
+    }
+  }
+
+  let sass = AsyncEmbedded::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "@import \"orange\";",
+      StringOptionsBuilder::default()
+        .async_importer(MyImporter)
+        .build(),
+    )
+    .await
+    .unwrap();
+  assert_eq!(res.css, ".orange {\n  color: orange;\n}");
+}
+
+#[tokio::test]
+async fn uses_an_async_file_importer() {
+  #[derive(Debug)]
+  struct MyImporter(Url);
+
+  #[async_trait]
+  impl AsyncFileImporter for MyImporter {
+    async fn find_file_url(
+      &self,
+      _: &str,
+      _: &ImporterOptions,
+    ) -> Result<Option<Url>> {
+      tokio::time::sleep(Duration::from_millis(1)).await;
+      Ok(Some(self.0.clone()))
+    }
+  }
+
+  let sandbox = Sandbox::default();
+  sandbox.write(sandbox.path().join("_other.scss"), "a {from: importer}");
+  let url = Url::from_file_path(sandbox.path().join("other")).unwrap();
+  let sass = AsyncEmbedded::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "@import \"other\";",
+      StringOptionsBuilder::default()
+        .async_file_importer(MyImporter(url))
+        .build(),
+    )
+    .await
+    .unwrap();
+  assert_eq!(res.css, "a {\n  from: importer;\n}");
+}
+
+#[tokio::test]
+async fn uses_an_async_logger() {
+  #[derive(Debug, Default)]
+  struct MyLogger(Arc<Mutex<Vec<String>>>);
+
+  #[async_trait]
+  impl AsyncLogger for MyLogger {
+    async fn warn(&self, message: &str, _: &LoggerWarnOptions) {
+      self.0.lock().push(message.to_string());
+    }
+  }
+
+  let messages = Arc::new(Mutex::new(Vec::new()));
+  let sass = AsyncEmbedded::new(exe_path()).unwrap();
+  sass
+    .compile_string(
+      "@warn heck",
+      StringOptionsBuilder::default()
+        .async_logger(MyLogger(Arc::clone(&messages)))
+        .build(),
+    )
+    .await
+    .unwrap();
+  assert_eq!(*messages.lock(), vec!["heck".to_string()]);
+}
+
+#[test]
+fn rejects_async_importers_in_a_sync_compilation() {
+  #[derive(Debug)]
+  struct MyImporter;
+
+  #[async_trait]
+  impl AsyncFileImporter for MyImporter {
+    async fn find_file_url(
+      &self,
+      _: &str,
+      _: &ImporterOptions,
+    ) -> Result<Option<Url>> {
+      Ok(None)
+    }
+  }
+
+  let sass = Sass::new(exe_path()).unwrap();
+  let err = sass
+    .compile_string(
+      "a {b: c}",
+      StringOptionsBuilder::default()
+        .async_file_importer(MyImporter)
+        .build(),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.message(),
+    "Async importers and loggers can only be used with AsyncEmbedded."
+  );
+}
+
+#[cfg(feature = "testing")]
+mod transport {
+  use std::net::{SocketAddr, TcpListener, TcpStream};
+
+  use sass_embedded::{
+    testing::{MockCompiler, MockResponse},
+    AsyncTcpTransport, AsyncTransport, ExceptionKind, MemoryTransport,
+    RestartPolicy, TcpTransport, Transport,
+  };
+
+  use super::*;
+
+  /// Serves `compiler` on a local TCP port, by relaying between every
+  /// accepted connection and a connection of its own to the mock.
+  fn serve_over_tcp(compiler: MockCompiler) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+      for stream in listener.incoming() {
+        let tcp: Arc<dyn Transport> =
+          Arc::new(TcpTransport::new(stream.unwrap()).unwrap());
+        let memory: Arc<dyn Transport> = Arc::new(compiler.connect());
+        relay(Arc::clone(&tcp), Arc::clone(&memory));
+        relay(memory, tcp);
+      }
+    });
+    addr
+  }
+
+  fn relay(from: Arc<dyn Transport>, to: Arc<dyn Transport>) {
+    std::thread::spawn(move || {
+      while let Ok(message) = from.read() {
+        if to.write(&message).is_err() {
+          break;
+        }
+      }
+      to.close();
+    });
+  }
+
+  #[derive(Debug)]
+  struct ColorImporter;
+
+  #[async_trait]
+  impl AsyncImporter for ColorImporter {
+    async fn canonicalize(
+      &self,
+      url: &str,
+      _: &ImporterOptions,
+    ) -> Result<Option<Url>> {
+      tokio::task::yield_now().await;
+      Ok(Some(Url::parse(&format!("color:{url}")).unwrap()))
+    }
+
+    async fn load(
+      &self,
+      canonical_url: &Url,
+    ) -> Result<Option<ImporterResult>> {
+      let color = canonical_url.path();
+      Ok(Some(ImporterResult {
+        contents: format!(".{color} {{color: {color}}}"),
+        syntax: Syntax::Scss,
+        source_map_url: None,
+      }))
+    }
+  }
+
+  #[tokio::test]
+  async fn compiles_over_an_in_memory_transport() {
+    let compiler = MockCompiler::new(|compilation| {
+      MockResponse::css(compilation.source().unwrap())
+    });
+    let sass =
+      AsyncEmbedded::with_transport(move || Ok(compiler.connect())).unwrap();
+    let res = sass
+      .compile_string("a {b: c}", Default::default())
+      .await
+      .unwrap();
+    assert_eq!(res.css, "a {b: c}");
+  }
+
+  #[tokio::test]
+  async fn uses_an_async_importer_over_an_in_memory_transport() {
+    let compiler = MockCompiler::new(|compilation| {
+      let url = compilation
+        .canonicalize(0, "orange", true)
+        .unwrap()
+        .unwrap();
+      assert_eq!(url.as_str(), "color:orange");
+      MockResponse::css(compilation.import(0, &url).unwrap().unwrap().contents)
+    });
+    let sass =
+      AsyncEmbedded::with_transport(move || Ok(compiler.connect())).unwrap();
+    let res = sass
+      .compile_string(
+        "@import \"orange\";",
+        StringOptionsBuilder::default()
+          .async_importer(ColorImporter)
+          .build(),
+      )
+      .await
+      .unwrap();
+    assert_eq!(res.css, ".orange {color: orange}");
+  }
+
+  #[tokio::test]
+  async fn fails_pending_compilations_once_the_transport_closes() {
+    let (host, compiler) = MemoryTransport::pair();
+    let host = Mutex::new(Some(host));
+    let sass = AsyncEmbedded::with_transport(move || {
+      host
+        .lock()
+        .take()
+        .ok_or_else(|| std::io::ErrorKind::NotConnected.into())
+    })
+    .unwrap();
+    std::thread::spawn(move || {
+      // Closes the connection on the compile request.
+      let _ = sass_embedded::Transport::read(&compiler);
+      sass_embedded::Transport::close(&compiler);
+    });
+    let err = sass
+      .compile_string("a {b: c}", Default::default())
+      .await
+      .unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Io);
+    assert_eq!(
+      err.message(),
+      "The compiler exited unexpectedly: unexpected end of file"
+    );
+  }
+
+  #[tokio::test]
+  async fn compiles_over_an_async_tcp_transport() {
+    let addr = serve_over_tcp(MockCompiler::new(|compilation| {
+      MockResponse::css(compilation.source().unwrap())
+    }));
+    let sass = AsyncEmbedded::with_async_transport(move || {
+      AsyncTcpTransport::from_std(TcpStream::connect(addr)?)
+    })
+    .unwrap();
+    let res = sass
+      .compile_string("a {b: c}", Default::default())
+      .await
+      .unwrap();
+    assert_eq!(res.css, "a {b: c}");
+  }
+
+  #[tokio::test]
+  async fn closing_an_async_tcp_transport_stops_a_pending_read() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let transport = AsyncTcpTransport::connect(listener.local_addr().unwrap())
+      .await
+      .unwrap();
+    let _daemon = listener.accept().await.unwrap();
+    let transport = Arc::new(transport);
+    let reader = Arc::clone(&transport);
+    let read = tokio::spawn(async move { reader.read().await });
+    tokio::task::yield_now().await;
+    transport.close();
+    let err = read.await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+  }
+
+  /// A compiler that's gone as soon as it's connected.
+  #[derive(Debug)]
+  struct DeadTransport;
+
+  #[async_trait]
+  impl AsyncTransport for DeadTransport {
+    async fn read(&self) -> std::io::Result<Vec<u8>> {
+      Err(std::io::ErrorKind::UnexpectedEof.into())
+    }
+
+    async fn write(&self, _: &[u8]) -> std::io::Result<()> {
+      Err(std::io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn close(&self) {}
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn gives_up_restarting_a_compiler_that_dies_right_away() {
+    let sass = AsyncEmbedded::with_async_transport(|| Ok(DeadTransport))
+      .unwrap()
+      .with_restart_policy(RestartPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+      });
+    for _ in 0..10 {
+      let err = sass
+        .compile_string("a {b: c}", Default::default())
+        .await
+        .unwrap_err();
+      if err.kind() == ExceptionKind::Spawn {
+        assert_eq!(
+          err.message(),
+          "The compiler crashed after 2 restarts in a row, giving up."
+        );
+        return;
+      }
+      tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("never gave up restarting the compiler");
+  }
+}
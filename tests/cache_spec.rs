@@ -0,0 +1,298 @@
+use std::{
+  cell::Cell,
+  fs::{self, File},
+  path::Path,
+  time::{Duration, SystemTime},
+};
+
+use sass_embedded::{
+  CompileCache, CompileResult, Exception, Freshness, Options, OptionsBuilder,
+  OutputStyle, Result, Url,
+};
+
+/// Counts the compilations, each of which loads `loaded_urls`.
+struct Compiler {
+  count: Cell<usize>,
+  loaded_urls: Vec<Url>,
+}
+
+impl Compiler {
+  fn new(loaded_urls: Vec<Url>) -> Self {
+    Self {
+      count: Cell::new(0),
+      loaded_urls,
+    }
+  }
+
+  fn compile(&self, _path: &Path, _options: Options) -> Result<CompileResult> {
+    self.count.set(self.count.get() + 1);
+    Ok(CompileResult {
+      css: format!("/* {} */", self.count.get()),
+      loaded_urls: self.loaded_urls.clone(),
+      source_map: None,
+    })
+  }
+}
+
+fn touch(path: &Path, contents: &str, modified: SystemTime) {
+  fs::write(path, contents).unwrap();
+  File::options()
+    .write(true)
+    .open(path)
+    .unwrap()
+    .set_modified(modified)
+    .unwrap();
+}
+
+#[test]
+fn reuses_results_while_dependencies_are_unchanged() {
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  fs::write(&entry, "a {b: c}").unwrap();
+  let compiler = Compiler::new(vec![Url::from_file_path(&entry).unwrap()]);
+  let cache = CompileCache::new();
+  for _ in 0..3 {
+    let res = cache
+      .get_or_compile(&entry, Options::default(), |p, o| compiler.compile(p, o))
+      .unwrap();
+    assert_eq!(res.css, "/* 1 */");
+  }
+  assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn recompiles_once_the_mtime_changes() {
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  let time = SystemTime::now() - Duration::from_secs(60);
+  touch(&entry, "a {b: c}", time);
+  let compiler = Compiler::new(vec![Url::from_file_path(&entry).unwrap()]);
+  let cache = CompileCache::new();
+  let compile = || {
+    cache
+      .get_or_compile(&entry, Options::default(), |p, o| compiler.compile(p, o))
+      .unwrap()
+      .css
+  };
+  assert_eq!(compile(), "/* 1 */");
+  touch(&entry, "a {b: d}", time + Duration::from_secs(1));
+  assert_eq!(compile(), "/* 2 */");
+  assert_eq!(compile(), "/* 2 */");
+}
+
+#[test]
+fn recompiles_once_the_content_hash_changes() {
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  let time = SystemTime::now() - Duration::from_secs(60);
+  touch(&entry, "a {b: c}", time);
+  let compiler = Compiler::new(vec![Url::from_file_path(&entry).unwrap()]);
+  let cache = CompileCache::with_freshness(Freshness::ContentHash);
+  let compile = || {
+    cache
+      .get_or_compile(&entry, Options::default(), |p, o| compiler.compile(p, o))
+      .unwrap()
+      .css
+  };
+  assert_eq!(compile(), "/* 1 */");
+  // Same size and modification time.
+  touch(&entry, "a {b: d}", time);
+  assert_eq!(compile(), "/* 2 */");
+  touch(&entry, "a {b: d}", time + Duration::from_secs(1));
+  assert_eq!(compile(), "/* 2 */");
+}
+
+#[test]
+fn recompiles_once_a_dependency_is_deleted() {
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  let partial = dir.path().join("_partial.scss");
+  fs::write(&entry, "@use 'partial'").unwrap();
+  fs::write(&partial, "a {b: c}").unwrap();
+  let compiler = Compiler::new(vec![
+    Url::from_file_path(&entry).unwrap(),
+    Url::from_file_path(&partial).unwrap(),
+  ]);
+  let cache = CompileCache::new();
+  let compile = || {
+    cache
+      .get_or_compile(&entry, Options::default(), |p, o| compiler.compile(p, o))
+      .unwrap()
+      .css
+  };
+  assert_eq!(compile(), "/* 1 */");
+  fs::remove_file(&partial).unwrap();
+  assert_eq!(compile(), "/* 2 */");
+}
+
+#[test]
+fn does_not_cache_results_whose_entrypoint_changed_during_the_compile() {
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  let time = SystemTime::now() - Duration::from_secs(60);
+  touch(&entry, "a {b: c}", time);
+  let compiler = Compiler::new(vec![Url::from_file_path(&entry).unwrap()]);
+  let cache = CompileCache::new();
+  let res = cache
+    .get_or_compile(&entry, Options::default(), |p, o| {
+      let res = compiler.compile(p, o);
+      touch(&entry, "a {b: d}", time + Duration::from_secs(1));
+      res
+    })
+    .unwrap();
+  assert_eq!(res.css, "/* 1 */");
+  assert!(cache.is_empty());
+  let compile = || {
+    cache
+      .get_or_compile(&entry, Options::default(), |p, o| compiler.compile(p, o))
+      .unwrap()
+      .css
+  };
+  assert_eq!(compile(), "/* 2 */");
+  assert_eq!(compile(), "/* 2 */");
+}
+
+#[test]
+fn does_not_cache_results_whose_dependencies_changed_during_the_compile() {
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  let partial = dir.path().join("_partial.scss");
+  let time = SystemTime::now() - Duration::from_secs(60);
+  touch(&entry, "@use 'partial'", time);
+  touch(&partial, "a {b: c}", time);
+  let compiler = Compiler::new(vec![
+    Url::from_file_path(&entry).unwrap(),
+    Url::from_file_path(&partial).unwrap(),
+  ]);
+  let cache = CompileCache::with_freshness(Freshness::ContentHash);
+  let compile = || {
+    cache
+      .get_or_compile(&entry, Options::default(), |p, o| compiler.compile(p, o))
+      .unwrap()
+      .css
+  };
+  assert_eq!(compile(), "/* 1 */");
+  touch(&partial, "a {b: d}", time);
+  // Edited again while it's recompiled, keeping its modification time.
+  cache
+    .get_or_compile(&entry, Options::default(), |p, o| {
+      let res = compiler.compile(p, o);
+      touch(&partial, "a {b: e}", time);
+      res
+    })
+    .unwrap();
+  assert_eq!(compile(), "/* 3 */");
+  assert_eq!(compile(), "/* 3 */");
+}
+
+#[test]
+fn does_not_cache_results_whose_new_dependencies_changed_during_the_compile() {
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  let partial = dir.path().join("_partial.scss");
+  let time = SystemTime::now() - Duration::from_secs(60);
+  touch(&entry, "@use 'partial'", time);
+  let compiler = Compiler::new(vec![
+    Url::from_file_path(&entry).unwrap(),
+    Url::from_file_path(&partial).unwrap(),
+  ]);
+  let cache = CompileCache::new();
+  cache
+    .get_or_compile(&entry, Options::default(), |p, o| {
+      let res = compiler.compile(p, o);
+      touch(
+        &partial,
+        "a {b: c}",
+        SystemTime::now() + Duration::from_secs(1),
+      );
+      res
+    })
+    .unwrap();
+  assert!(cache.is_empty());
+}
+
+#[test]
+fn keys_results_on_the_options() {
+  let compiler = Compiler::new(Vec::new());
+  let cache = CompileCache::new();
+  let compile = |options: Options| {
+    cache
+      .get_or_compile("style.scss", options, |p, o| compiler.compile(p, o))
+      .unwrap()
+      .css
+  };
+  assert_eq!(compile(Options::default()), "/* 1 */");
+  let compressed = || {
+    OptionsBuilder::default()
+      .style(OutputStyle::Compressed)
+      .build()
+  };
+  assert_eq!(compile(compressed()), "/* 2 */");
+  assert_eq!(compile(compressed()), "/* 2 */");
+  assert_eq!(compile(Options::default()), "/* 1 */");
+  assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn invalidates_importer_urls_through_the_hook() {
+  let url = Url::parse("u:theme").unwrap();
+  let compiler = Compiler::new(vec![url.clone()]);
+  let cache = CompileCache::new();
+  let compile = || {
+    cache
+      .get_or_compile("style.scss", Options::default(), |p, o| {
+        compiler.compile(p, o)
+      })
+      .unwrap()
+      .css
+  };
+  assert_eq!(compile(), "/* 1 */");
+  assert_eq!(compile(), "/* 1 */");
+  cache.invalidate(&Url::parse("u:other").unwrap());
+  assert_eq!(compile(), "/* 1 */");
+  cache.invalidate(&url);
+  assert_eq!(compile(), "/* 2 */");
+  cache.invalidate_if(|url| url.scheme() == "u");
+  assert!(cache.is_empty());
+  assert_eq!(compile(), "/* 3 */");
+}
+
+#[test]
+fn does_not_cache_failures() {
+  let cache = CompileCache::new();
+  let err = cache
+    .get_or_compile("style.scss", Options::default(), |_, _| {
+      Err(Exception::new("nope").into())
+    })
+    .unwrap_err();
+  assert_eq!(err.message(), "nope");
+  assert!(cache.is_empty());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn compiles_with_an_embedded_compiler() {
+  use sass_embedded::testing::{MockCompiler, MockResponse};
+
+  let dir = tempfile::tempdir().unwrap();
+  let entry = dir.path().join("style.scss");
+  fs::write(&entry, "a {b: c}").unwrap();
+  let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+  let counter = std::sync::Arc::clone(&calls);
+  let sass = MockCompiler::new(move |compilation| {
+    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    MockResponse::Success {
+      css: "a {\n  b: c;\n}".to_string(),
+      source_map: None,
+      loaded_urls: vec![compilation.url().unwrap()],
+    }
+  })
+  .embedded()
+  .unwrap();
+  let cache = CompileCache::new();
+  for _ in 0..2 {
+    let res = cache.compile(&sass, &entry, Options::default()).unwrap();
+    assert_eq!(res.css, "a {\n  b: c;\n}");
+  }
+  assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
@@ -0,0 +1,86 @@
+use sass_embedded::{
+  CalculationOperation, CalculationOperator, CalculationValue, SassCalculation,
+  SassNumber,
+};
+
+fn px(n: f64) -> SassNumber {
+  SassNumber::with_unit(n, "px")
+}
+
+mod construction {
+  use super::*;
+
+  #[test]
+  fn calc() {
+    let calculation = SassCalculation::calc(CalculationOperation::new(
+      CalculationOperator::Plus,
+      px(1.0),
+      CalculationValue::String("var(--x)".to_string()),
+    ));
+    assert_eq!(calculation.name(), "calc");
+    assert_eq!(calculation.to_string(), "calc(1px + var(--x))");
+  }
+
+  #[test]
+  fn min_and_max() {
+    let min = SassCalculation::min([px(1.0), px(2.0)]);
+    assert_eq!(min.name(), "min");
+    assert_eq!(min.arguments().len(), 2);
+    assert_eq!(SassCalculation::max([px(1.0)]).to_string(), "max(1px)");
+  }
+
+  #[test]
+  fn clamp() {
+    let clamp = SassCalculation::clamp(
+      px(1.0),
+      Some(px(2.0).into()),
+      Some(px(3.0).into()),
+    )
+    .unwrap();
+    assert_eq!(clamp.to_string(), "clamp(1px, 2px, 3px)");
+  }
+
+  #[test]
+  fn clamp_allows_omitted_arguments_after_a_string() {
+    let clamp = SassCalculation::clamp(
+      CalculationValue::Interpolation("1px, 2px".to_string()),
+      Some(px(3.0).into()),
+      None,
+    )
+    .unwrap();
+    assert_eq!(clamp.arguments().len(), 2);
+  }
+
+  #[test]
+  fn clamp_requires_all_arguments_otherwise() {
+    let err = SassCalculation::clamp(px(1.0), None, None).unwrap_err();
+    assert_eq!(
+      err.message(),
+      "Argument must be an unquoted SassString or CalculationInterpolation."
+    );
+    assert!(
+      SassCalculation::clamp(px(1.0), Some(px(2.0).into()), None).is_err()
+    );
+  }
+}
+
+mod inspection {
+  use super::*;
+
+  #[test]
+  fn exposes_the_operator_tree() {
+    let calculation = SassCalculation::calc(CalculationOperation::new(
+      CalculationOperator::Times,
+      CalculationOperation::new(CalculationOperator::Minus, px(3.0), px(1.0)),
+      SassNumber::new(2.0),
+    ));
+    let operation = match &calculation.arguments()[0] {
+      CalculationValue::Operation(o) => o,
+      _ => panic!("expected an operation"),
+    };
+    assert_eq!(operation.operator(), CalculationOperator::Times);
+    assert_eq!(operation.right(), &SassNumber::new(2.0).into());
+    assert!(matches!(operation.left(), CalculationValue::Operation(_)));
+    assert_eq!(calculation.to_string(), "calc((3px - 1px) * 2)");
+  }
+}
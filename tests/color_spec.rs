@@ -0,0 +1,208 @@
+use sass_embedded::{ColorChannels, SassColor};
+
+fn red() -> SassColor {
+  SassColor::rgb(255, 0, 0, 1.0)
+}
+
+mod channels {
+  use super::*;
+
+  #[test]
+  fn converts_rgb_to_hsl_and_hwb() {
+    let color = SassColor::rgb(18, 52, 86, 1.0);
+    assert_eq!(color.hue().round(), 210.0);
+    assert_eq!(color.saturation().round(), 65.0);
+    assert_eq!(color.lightness().round(), 20.0);
+    assert_eq!(color.whiteness().round(), 7.0);
+    assert_eq!(color.blackness().round(), 66.0);
+  }
+
+  #[test]
+  fn converts_hsl_to_rgb() {
+    let color = SassColor::hsl(120.0, 100.0, 25.0, 1.0);
+    assert_eq!((color.red(), color.green(), color.blue()), (0, 128, 0));
+    assert_eq!(color.hue(), 120.0);
+  }
+
+  #[test]
+  fn converts_hwb_to_rgb() {
+    let color = SassColor::hwb(0.0, 20.0, 40.0, 1.0);
+    assert_eq!((color.red(), color.green(), color.blue()), (153, 51, 51));
+    assert_eq!(color.whiteness(), 20.0);
+  }
+
+  #[test]
+  fn normalizes_the_hue() {
+    assert_eq!(SassColor::hsl(-90.0, 50.0, 50.0, 1.0).hue(), 270.0);
+    assert_eq!(SassColor::hwb(400.0, 0.0, 0.0, 1.0).hue(), 40.0);
+  }
+}
+
+mod change {
+  use super::*;
+
+  #[test]
+  fn changes_rgb_channels() {
+    let color = red()
+      .change(ColorChannels {
+        blue: Some(128.0),
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(color, SassColor::rgb(255, 0, 128, 1.0));
+  }
+
+  #[test]
+  fn changes_hsl_channels() {
+    let color = red()
+      .change(ColorChannels {
+        hue: Some(120.0),
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(color, SassColor::rgb(0, 255, 0, 1.0));
+  }
+
+  #[test]
+  fn changes_alpha() {
+    let color = red()
+      .change(ColorChannels {
+        alpha: Some(0.5),
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(color.alpha(), 0.5);
+    assert_eq!(color.red(), 255);
+  }
+
+  #[test]
+  fn rejects_out_of_range_channels() {
+    let err = red()
+      .change(ColorChannels {
+        red: Some(256.0),
+        ..Default::default()
+      })
+      .unwrap_err();
+    assert_eq!(err.message(), "$red: Expected 256 to be within 0 and 255.");
+    let err = red()
+      .change(ColorChannels {
+        lightness: Some(101.0),
+        ..Default::default()
+      })
+      .unwrap_err();
+    assert_eq!(
+      err.message(),
+      "$lightness: Expected 101% to be within 0% and 100%."
+    );
+  }
+
+  #[test]
+  fn rejects_mixed_color_spaces() {
+    let err = red()
+      .change(ColorChannels {
+        red: Some(1.0),
+        lightness: Some(1.0),
+        ..Default::default()
+      })
+      .unwrap_err();
+    assert_eq!(
+      err.message(),
+      "RGB parameters may not be passed along with HSL parameters."
+    );
+    let err = red()
+      .change(ColorChannels {
+        saturation: Some(1.0),
+        blackness: Some(1.0),
+        ..Default::default()
+      })
+      .unwrap_err();
+    assert_eq!(
+      err.message(),
+      "HSL parameters may not be passed along with HWB parameters."
+    );
+  }
+}
+
+mod adjust {
+  use super::*;
+
+  #[test]
+  fn adds_to_channels_and_clamps() {
+    let color = SassColor::rgb(100, 200, 0, 0.5)
+      .adjust(ColorChannels {
+        red: Some(10.0),
+        green: Some(100.0),
+        alpha: Some(-0.75),
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(color, SassColor::rgb(110, 255, 0, 0.0));
+  }
+
+  #[test]
+  fn rotates_the_hue() {
+    let color = red()
+      .adjust(ColorChannels {
+        hue: Some(-120.0),
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(color, SassColor::rgb(0, 0, 255, 1.0));
+  }
+}
+
+mod scale {
+  use super::*;
+
+  #[test]
+  fn scales_towards_the_limits() {
+    let color = SassColor::hsl(0.0, 100.0, 40.0, 1.0)
+      .scale(ColorChannels {
+        lightness: Some(50.0),
+        saturation: Some(-50.0),
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(color.lightness(), 70.0);
+    assert_eq!(color.saturation(), 50.0);
+  }
+
+  #[test]
+  fn rejects_the_hue() {
+    let err = red()
+      .scale(ColorChannels {
+        hue: Some(10.0),
+        ..Default::default()
+      })
+      .unwrap_err();
+    assert_eq!(err.message(), "No argument named $hue.");
+  }
+}
+
+mod functions {
+  use super::*;
+
+  #[test]
+  fn lightens_and_darkens() {
+    let color = SassColor::hsl(0.0, 100.0, 50.0, 1.0);
+    assert_eq!(color.lighten(20.0).unwrap().lightness(), 70.0);
+    assert_eq!(color.darken(60.0).unwrap().lightness(), 0.0);
+    assert!(color.lighten(101.0).is_err());
+  }
+
+  #[test]
+  fn mixes_colors() {
+    let blue = SassColor::rgb(0, 0, 255, 1.0);
+    assert_eq!(
+      red().mix(&blue, 50.0).unwrap(),
+      SassColor::rgb(128, 0, 128, 1.0)
+    );
+    assert_eq!(
+      red().mix(&blue, 25.0).unwrap(),
+      SassColor::rgb(64, 0, 191, 1.0)
+    );
+    let transparent = SassColor::rgb(0, 0, 255, 0.0);
+    let mixed = red().mix(&transparent, 50.0).unwrap();
+    assert_eq!(mixed, SassColor::rgb(255, 0, 0, 0.5));
+  }
+}
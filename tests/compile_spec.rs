@@ -3,8 +3,8 @@ mod helpers;
 
 use helpers::{exe_path, Sandbox, ToUrl};
 use sass_embedded::{
-  Options, OptionsBuilder, OutputStyle, Sass, StringOptions,
-  StringOptionsBuilder, Syntax, Url,
+  ExceptionKind, Options, OptionsBuilder, OutputStyle, RenderOptions, Report,
+  Sass, SourceMap, StringOptions, StringOptionsBuilder, Syntax, Url,
 };
 use serde_json::json;
 
@@ -19,7 +19,7 @@ mod compile_string {
 
       #[test]
       fn compiles_scss_by_default() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string("$a: b; c {d: $a}", StringOptions::default())
           .unwrap();
@@ -28,7 +28,7 @@ mod compile_string {
 
       #[test]
       fn compiles_scss_with_explicit_syntax() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "$a: b; c {d: $a}",
@@ -40,7 +40,7 @@ mod compile_string {
 
       #[test]
       fn compiles_indented_syntax_with_explicit_syntax() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "a\n  b: c",
@@ -54,7 +54,7 @@ mod compile_string {
 
       #[test]
       fn compiles_plain_css_with_explicit_syntax() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "a {b: c}",
@@ -66,7 +66,7 @@ mod compile_string {
 
       #[test]
       fn does_not_take_its_syntax_from_the_url_s_extension() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "a {b: c}",
@@ -84,7 +84,7 @@ mod compile_string {
 
       #[test]
       fn is_empty_with_no_url() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string("a {b: c}", StringOptions::default())
           .unwrap();
@@ -94,7 +94,7 @@ mod compile_string {
       #[test]
       fn contains_the_url_if_one_is_passed() {
         let url = Url::parse("file:///foo.scss").unwrap();
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "a {b: c}",
@@ -110,7 +110,7 @@ mod compile_string {
         let url = sandbox.path().join("input.scss").to_url();
         sandbox.write(sandbox.path().join("_other.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "@use \"other\"",
@@ -131,7 +131,7 @@ mod compile_string {
           .write(sandbox.path().join("_midstream.scss"), "@use \"upstream\"")
           .write(sandbox.path().join("_upstream.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "@use \"midstream\"",
@@ -160,7 +160,7 @@ mod compile_string {
             .write(sandbox.path().join("_right.scss"), "@use \"upstream\"")
             .write(sandbox.path().join("_upstream.scss"), "a {b: c}");
 
-          let mut sass = Sass::new(exe_path()).unwrap();
+          let sass = Sass::new(exe_path()).unwrap();
           let res = sass
             .compile_string(
               "@use \"left\"; @use \"right\"",
@@ -187,7 +187,7 @@ mod compile_string {
             .write(sandbox.path().join("_right.scss"), "@use \"upstream\"")
             .write(sandbox.path().join("_upstream.scss"), "a {b: c}");
 
-          let mut sass = Sass::new(exe_path()).unwrap();
+          let sass = Sass::new(exe_path()).unwrap();
           let res = sass
             .compile_string(
               "@import \"left\"; @import \"right\"",
@@ -212,7 +212,7 @@ mod compile_string {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("foo/bar/_other.scss"), "a {b: c}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let res = sass
         .compile_string(
           "@use \"other\";",
@@ -232,7 +232,7 @@ mod compile_string {
         let sandbox = Sandbox::default();
         sandbox.write(sandbox.path().join("foo/bar/_other.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "@use \"other\";",
@@ -249,7 +249,7 @@ mod compile_string {
         let sandbox = Sandbox::default();
         sandbox.write(sandbox.path().join("foo/bar/_other.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "@use \"bar/other\";",
@@ -266,7 +266,7 @@ mod compile_string {
         let sandbox = Sandbox::default();
         sandbox.write(sandbox.path().join("bar/_other.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "@use \"other\";",
@@ -290,7 +290,7 @@ mod compile_string {
             "a {b: load path}",
           );
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "@use \"other\";",
@@ -310,7 +310,7 @@ mod compile_string {
           .write(sandbox.path().join("earlier/_other.scss"), "a {b: earlier}")
           .write(sandbox.path().join("later/_other.scss"), "a {b: later}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "@use \"other\";",
@@ -326,7 +326,7 @@ mod compile_string {
 
     #[test]
     fn recognizes_the_expanded_output_style() {
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let res = sass
         .compile_string(
           "a {b: c}",
@@ -343,7 +343,7 @@ mod compile_string {
 
       #[test]
       fn does_not_include_one_by_default() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string("a {b: c}", StringOptions::default())
           .unwrap();
@@ -352,7 +352,7 @@ mod compile_string {
 
       #[test]
       fn includes_one_if_source_map_is_true() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "a {b: c}",
@@ -371,7 +371,7 @@ mod compile_string {
       #[test]
       fn includes_one_with_source_content_if_source_map_include_sources_is_true(
       ) {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "a {b: c}",
@@ -388,6 +388,29 @@ mod compile_string {
         assert!(source_map["sourcesContent"].is_array());
         assert!(!source_map["sourcesContent"].as_array().unwrap().is_empty());
       }
+
+      #[test]
+      fn parses_into_a_source_map() {
+        let sass = Sass::new(exe_path()).unwrap();
+        let res = sass
+          .compile_string(
+            "a {b: c}",
+            StringOptionsBuilder::default()
+              .url(Url::parse("file:///style.scss").unwrap())
+              .source_map(true)
+              .build(),
+          )
+          .unwrap();
+        let source_map = res.parse_source_map().unwrap().unwrap();
+        assert_eq!(source_map.sources, ["file:///style.scss"]);
+        // `  b: c;` comes from `b: c`.
+        let original = source_map.lookup(1, 2).unwrap();
+        assert_eq!((original.line, original.column), (0, 3));
+        assert_eq!(
+          SourceMap::parse(&source_map.to_json()).unwrap(),
+          source_map
+        );
+      }
     }
 
     mod charset {
@@ -395,7 +418,7 @@ mod compile_string {
 
       #[test]
       fn emits_at_charset_utf_8_or_bom_for_non_ascii_css_by_default() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string("a {b: あ;}", StringOptions::default())
           .unwrap();
@@ -404,7 +427,7 @@ mod compile_string {
 
       #[test]
       fn does_not_emit_at_charset_or_bom_if_charset_is_false() {
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile_string(
             "a {b: あ;}",
@@ -421,7 +444,7 @@ mod compile_string {
 
     #[test]
     fn requires_plain_css_with_explicit_syntax() {
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let err = sass
         .compile_string(
           "$a: b; c {d: $a}",
@@ -430,6 +453,38 @@ mod compile_string {
         .unwrap_err();
       assert_eq!(err.span().unwrap().start.line, 0);
       assert!(err.span().unwrap().url.is_none());
+      assert_eq!(err.kind(), ExceptionKind::CompileFailure);
+    }
+
+    #[test]
+    fn renders_as_a_report() {
+      let sass = Sass::new(exe_path()).unwrap();
+      let err = sass
+        .compile_string("a {b: $c}", StringOptions::default())
+        .unwrap_err();
+      let rendered = Report::from(&*err).render(&RenderOptions::default());
+      assert!(rendered.starts_with("error: Undefined variable.\n"));
+      assert!(rendered.contains("1 │ a {b: $c}\n  │       ^^\n"));
+      assert!(rendered.contains(" = at - 1:7  root stylesheet\n"));
+    }
+
+    #[test]
+    fn parses_the_stack_trace() {
+      let sass = Sass::new(exe_path()).unwrap();
+      let err = sass
+        .compile_string(
+          "@mixin a {b: $c}\nd {@include a}",
+          StringOptions::default(),
+        )
+        .unwrap_err();
+      let frames = err.stack_frames();
+      assert_eq!(frames.len(), 2);
+      assert_eq!((frames[0].line, frames[0].member.as_str()), (0, "a()"));
+      assert_eq!(
+        (frames[1].line, frames[1].member.as_str()),
+        (1, "root stylesheet")
+      );
+      assert!(frames.iter().all(|frame| frame.url.is_none()));
     }
 
     #[test]
@@ -437,7 +492,7 @@ mod compile_string {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("_other.scss"), "a {b: c}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let err = sass
         .compile_string("@use \"./other\"", StringOptions::default())
         .unwrap_err();
@@ -450,7 +505,7 @@ mod compile_string {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("_other.scss"), "a {b: c}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let err = sass
         .compile_string(
           "@use \"./other\"",
@@ -474,7 +529,7 @@ mod compile_string {
         let sandbox = Sandbox::default();
         let url = sandbox.path().join("foo.scss").to_url();
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let err = sass
           .compile_string(
             "a {b:",
@@ -490,7 +545,7 @@ mod compile_string {
         let sandbox = Sandbox::default();
         let url = sandbox.path().join("foo.scss").to_url();
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let err = sass
           .compile_string(
             "@error \"oh no\"",
@@ -506,7 +561,7 @@ mod compile_string {
         let sandbox = Sandbox::default();
         let url = sandbox.path().join("foo.scss").to_url();
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let err = sass
           .compile_string(
             "@use \"sass:math\"; @use \"sass:math\"",
@@ -531,7 +586,7 @@ mod compile {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("input.scss"), "$a: b; c {d: $a}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let res = sass
         .compile(sandbox.path().join("input.scss"), Options::default())
         .unwrap();
@@ -543,7 +598,7 @@ mod compile {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("input.asdf"), "$a: b; c {d: $a}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let res = sass
         .compile(sandbox.path().join("input.asdf"), Options::default())
         .unwrap();
@@ -555,7 +610,7 @@ mod compile {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("input.sass"), "a\n  b: c");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let res = sass
         .compile(sandbox.path().join("input.sass"), Options::default())
         .unwrap();
@@ -567,7 +622,7 @@ mod compile {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("input.css"), "a {b: c}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let res = sass
         .compile(sandbox.path().join("input.css"), Options::default())
         .unwrap();
@@ -582,7 +637,7 @@ mod compile {
         let sandbox = Sandbox::default();
         sandbox.write(sandbox.path().join("input.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile(sandbox.path().join("input.scss"), Options::default())
           .unwrap();
@@ -597,7 +652,7 @@ mod compile {
         let sandbox = Sandbox::default();
         sandbox.write(sandbox.path().join("input.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile(sandbox.path().join("input.scss"), Options::default())
           .unwrap();
@@ -614,7 +669,7 @@ mod compile {
           .write(sandbox.path().join("input.scss"), "@use \"other\"")
           .write(sandbox.path().join("_other.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile(sandbox.path().join("input.scss"), Options::default())
           .unwrap();
@@ -635,7 +690,7 @@ mod compile {
         .write(sandbox.path().join("foo/bar/input.scss"), "@use \"other\"")
         .write(sandbox.path().join("foo/bar/_other.scss"), "a {b: c}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let res = sass
         .compile(
           sandbox.path().join("foo/bar/input.scss"),
@@ -655,7 +710,7 @@ mod compile {
           .write(sandbox.path().join("input.scss"), "@use \"other\"")
           .write(sandbox.path().join("foo/bar/_other.scss"), "a {b: c}");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile(
             sandbox.path().join("input.scss"),
@@ -678,7 +733,7 @@ mod compile {
             "a {b: load path}",
           );
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let res = sass
           .compile(
             sandbox.path().join("url/input.scss"),
@@ -700,7 +755,7 @@ mod compile {
       let sandbox = Sandbox::default();
       sandbox.write(sandbox.path().join("input.css"), "$a: b; c {d: $a}");
 
-      let mut sass = Sass::new(exe_path()).unwrap();
+      let sass = Sass::new(exe_path()).unwrap();
       let err = sass
         .compile(sandbox.path().join("input.css"), Options::default())
         .unwrap_err();
@@ -719,7 +774,7 @@ mod compile {
         let sandbox = Sandbox::default();
         sandbox.write(sandbox.path().join("input.css"), "a {b:");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let err = sass
           .compile(sandbox.path().join("input.css"), Options::default())
           .unwrap_err();
@@ -735,7 +790,7 @@ mod compile {
         let sandbox = Sandbox::default();
         sandbox.write(sandbox.path().join("input.css"), "@error \"oh no\"");
 
-        let mut sass = Sass::new(exe_path()).unwrap();
+        let sass = Sass::new(exe_path()).unwrap();
         let err = sass
           .compile(sandbox.path().join("input.css"), Options::default())
           .unwrap_err();
@@ -748,3 +803,356 @@ mod compile {
     }
   }
 }
+
+mod concurrency {
+  use std::{sync::Arc, thread};
+
+  use super::*;
+
+  #[test]
+  fn is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Sass>();
+  }
+
+  #[test]
+  fn compiles_in_parallel_on_one_compiler() {
+    let sass = Arc::new(Sass::new(exe_path()).unwrap());
+    let handles: Vec<_> = (0..16)
+      .map(|i| {
+        let sass = Arc::clone(&sass);
+        thread::spawn(move || {
+          sass
+            .compile_string(
+              format!("a {{b: {i} + 1}}"),
+              StringOptions::default(),
+            )
+            .unwrap()
+            .css
+        })
+      })
+      .collect();
+    for (i, handle) in handles.into_iter().enumerate() {
+      assert_eq!(handle.join().unwrap(), format!("a {{\n  b: {};\n}}", i + 1));
+    }
+  }
+
+  #[test]
+  fn reports_errors_per_compilation() {
+    let sass = Arc::new(Sass::new(exe_path()).unwrap());
+    let ok = {
+      let sass = Arc::clone(&sass);
+      thread::spawn(move || {
+        sass.compile_string("a {b: c}", StringOptions::default())
+      })
+    };
+    let err = {
+      let sass = Arc::clone(&sass);
+      thread::spawn(move || {
+        sass.compile_string("a {b: }", StringOptions::default())
+      })
+    };
+    assert!(ok.join().unwrap().is_ok());
+    assert!(err.join().unwrap().is_err());
+  }
+}
+
+#[cfg(unix)]
+mod compiler_failure {
+  use std::{error::Error, sync::Arc, thread, time::Duration};
+
+  use parking_lot::Mutex;
+  use sass_embedded::{
+    CompilerCommand, CompilerStderr, Exception, RestartPolicy, StderrOptions,
+    StderrSink,
+  };
+
+  use super::*;
+
+  #[test]
+  fn fails_instead_of_hanging_when_the_compiler_exits() {
+    // `true` exits right away without reading or writing anything.
+    let sass = Sass::new("true").unwrap();
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert!(err.source().unwrap().is::<std::io::Error>());
+    assert_eq!(err.kind(), ExceptionKind::Io);
+  }
+
+  #[test]
+  fn fails_to_spawn_a_missing_compiler() {
+    let err = Sass::new("path/to/nowhere").unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Spawn);
+    assert!(err.source().unwrap().is::<std::io::Error>());
+  }
+
+  fn crash(sass: &Sass) -> Exception {
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    // Give the dispatcher time to notice the compiler exited.
+    thread::sleep(Duration::from_millis(50));
+    *err
+  }
+
+  #[test]
+  fn restarts_the_compiler_with_backoff() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sass = Sass::new("true")
+      .unwrap()
+      .with_restart_policy(RestartPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(10),
+      })
+      .on_compiler_event({
+        let events = Arc::clone(&events);
+        move |event| events.lock().push(format!("{:?}", event))
+      });
+    crash(&sass);
+    crash(&sass);
+    crash(&sass);
+    let err = crash(&sass);
+    assert_eq!(
+      err.message(),
+      "The compiler crashed after 2 restarts in a row, giving up."
+    );
+    let events = events.lock();
+    assert!(events[0].starts_with("Crashed { status: Some("));
+    assert_eq!(events[1], "Restarted { attempt: 1 }");
+    assert_eq!(events[3], "Restarted { attempt: 2 }");
+    assert_eq!(events[5], "GaveUp { attempts: 2 }");
+  }
+
+  /// A compiler that writes two lines to stderr and exits once it receives a
+  /// request.
+  fn failing_compiler(sandbox: &Sandbox) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = sandbox.path().join("compiler.sh");
+    sandbox.write(
+      &path,
+      "#!/bin/sh\nhead -c 1 >/dev/null\necho first >&2\necho second >&2\n",
+    );
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+      .unwrap();
+    path
+  }
+
+  #[test]
+  fn attaches_stderr_to_exceptions() {
+    let sandbox = Sandbox::default();
+    let sass = Sass::new(failing_compiler(&sandbox)).unwrap();
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert_eq!(err.compiler_stderr(), ["first", "second"]);
+    assert_eq!(sass.stderr(), ["first", "second"]);
+  }
+
+  #[test]
+  fn sends_stderr_to_the_sink() {
+    let sandbox = Sandbox::default();
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sass = Sass::new(failing_compiler(&sandbox)).unwrap().with_stderr(
+      StderrOptions {
+        sink: StderrSink::Callback(Box::new({
+          let lines = Arc::clone(&lines);
+          move |line| lines.lock().push(line.to_string())
+        })),
+        max_lines: 1,
+      },
+    );
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert_eq!(*lines.lock(), ["first", "second"]);
+    assert_eq!(err.compiler_stderr(), ["second"]);
+  }
+
+  #[test]
+  fn does_not_restart_with_the_never_policy() {
+    let sass = Sass::new("true")
+      .unwrap()
+      .with_restart_policy(RestartPolicy::never());
+    crash(&sass);
+    let err = crash(&sass);
+    assert_eq!(
+      err.message(),
+      "The compiler crashed, and restarting it is disabled."
+    );
+    assert_eq!(err.kind(), ExceptionKind::Spawn);
+  }
+  #[test]
+  fn starts_the_compiler_with_a_command() {
+    let sandbox = Sandbox::default();
+    let sass = Sass::with_command(
+      CompilerCommand::new("sh")
+        .args([
+          "-c",
+          "head -c 1 >/dev/null; echo \"$0 $1 $FOO ${HOME:-none}\" >&2; pwd -P >&2",
+          "name",
+          "--embedded",
+        ])
+        .env("FOO", "bar")
+        .env_remove("HOME")
+        .current_dir(sandbox.path()),
+    )
+    .unwrap();
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert_eq!(
+      err.compiler_stderr(),
+      [
+        "name --embedded bar none".to_string(),
+        sandbox.path().canonicalize().unwrap().display().to_string(),
+      ]
+    );
+  }
+
+  #[test]
+  fn sends_absolute_paths_to_a_compiler_in_another_directory() {
+    let sandbox = Sandbox::default();
+    // Echoes the printable parts of the request to stderr.
+    let sass = Sass::with_command(
+      CompilerCommand::new("sh")
+        .args([
+          "-c",
+          "dd bs=65536 count=1 2>/dev/null | tr -cs '[:print:]' '\\n' >&2",
+        ])
+        .current_dir(sandbox.path()),
+    )
+    .unwrap();
+    let err = sass
+      .compile(
+        "styles/app.scss",
+        OptionsBuilder::default().load_path("vendor").build(),
+      )
+      .unwrap_err();
+    let cwd = std::env::current_dir().unwrap();
+    for path in [cwd.join("styles/app.scss"), cwd.join("vendor")] {
+      let path = path.to_str().unwrap();
+      assert!(
+        err.compiler_stderr().iter().any(|line| line.contains(path)),
+        "{path} not in {:?}",
+        err.compiler_stderr()
+      );
+    }
+  }
+
+  #[test]
+  fn does_not_capture_stderr_when_it_is_discarded() {
+    let sass = Sass::with_command(
+      CompilerCommand::new("sh")
+        .args(["-c", "echo hidden >&2"])
+        .stderr(CompilerStderr::Null),
+    )
+    .unwrap();
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert!(err.compiler_stderr().is_empty());
+  }
+}
+
+#[cfg(unix)]
+mod interruption {
+  use std::{
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+  };
+
+  use sass_embedded::{CancellationToken, RestartPolicy};
+
+  use super::*;
+
+  /// A compiler that never responds.
+  fn hanging_compiler(sandbox: &Sandbox) -> PathBuf {
+    let path = sandbox.path().join("compiler.sh");
+    sandbox.write(&path, "#!/bin/sh\ncat >/dev/null\n");
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+      .unwrap();
+    path
+  }
+
+  #[test]
+  fn times_out() {
+    let sandbox = Sandbox::default();
+    let sass = Sass::new(hanging_compiler(&sandbox)).unwrap();
+    let start = Instant::now();
+    let err = sass
+      .compile_string(
+        "a {b: c}",
+        StringOptionsBuilder::new()
+          .timeout(Duration::from_millis(100))
+          .build(),
+      )
+      .unwrap_err();
+    assert_eq!(err.message(), "Compilation timed out after 100ms.");
+    assert_eq!(err.kind(), ExceptionKind::Timeout);
+    assert!(start.elapsed() < Duration::from_secs(5));
+  }
+
+  #[test]
+  fn cancels_from_another_thread() {
+    let sandbox = Sandbox::default();
+    let sass = Sass::new(hanging_compiler(&sandbox)).unwrap();
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    thread::spawn(move || {
+      thread::sleep(Duration::from_millis(50));
+      canceller.cancel();
+    });
+    let err = sass
+      .compile_string(
+        "a {b: c}",
+        StringOptionsBuilder::new()
+          .cancellation_token(token)
+          .build(),
+      )
+      .unwrap_err();
+    assert_eq!(err.message(), "Compilation was cancelled.");
+    assert_eq!(err.kind(), ExceptionKind::Cancelled);
+  }
+
+  #[test]
+  fn cancels_with_an_already_cancelled_token() {
+    let sandbox = Sandbox::default();
+    let sass = Sass::new(hanging_compiler(&sandbox)).unwrap();
+    let token = CancellationToken::new();
+    token.cancel();
+    assert!(token.is_cancelled());
+    let err = sass
+      .compile_string(
+        "a {b: c}",
+        StringOptionsBuilder::new()
+          .cancellation_token(token)
+          .build(),
+      )
+      .unwrap_err();
+    assert_eq!(err.message(), "Compilation was cancelled.");
+  }
+
+  #[test]
+  fn replaces_the_compiler_regardless_of_the_restart_policy() {
+    let sandbox = Sandbox::default();
+    let sass = Sass::new(hanging_compiler(&sandbox))
+      .unwrap()
+      .with_restart_policy(RestartPolicy::never());
+    for _ in 0..2 {
+      let err = sass
+        .compile_string(
+          "a {b: c}",
+          StringOptionsBuilder::new()
+            .timeout(Duration::from_millis(50))
+            .build(),
+        )
+        .unwrap_err();
+      assert_eq!(err.message(), "Compilation timed out after 50ms.");
+    }
+  }
+}
@@ -0,0 +1,170 @@
+use sass_embedded::{
+  RenderOptions, Report, Severity, SourceLocation, SourceSpan, Url,
+};
+
+fn location(line: usize, column: usize) -> SourceLocation {
+  SourceLocation {
+    offset: 0,
+    line,
+    column,
+  }
+}
+
+fn span(
+  context: Option<&str>,
+  start: (usize, usize),
+  end: (usize, usize),
+  text: &str,
+) -> SourceSpan {
+  SourceSpan {
+    context: context.map(ToString::to_string),
+    start: location(start.0, start.1),
+    end: location(end.0, end.1),
+    url: Some(Url::parse("file:///style.scss").unwrap()),
+    text: text.to_string(),
+  }
+}
+
+mod render {
+  use super::*;
+
+  #[test]
+  fn single_line() {
+    let report = Report::new(Severity::Error, "Undefined variable.").label(
+      span(Some("a {b: $c}"), (0, 6), (0, 8), "$c"),
+      Some("not found"),
+    );
+    assert_eq!(
+      report.render(&RenderOptions::default()),
+      "\
+error: Undefined variable.
+  ┌─ /style.scss:1:7
+  │
+1 │ a {b: $c}
+  │       ^^ not found
+"
+    );
+  }
+
+  #[test]
+  fn multi_line() {
+    let report = Report::new(Severity::Warning, "Too long.").label(
+      span(Some("a {\n  b: c;\n}"), (9, 0), (11, 1), "a {\n  b: c;\n}"),
+      Some("here"),
+    );
+    assert_eq!(
+      report.render(&RenderOptions {
+        unicode: false,
+        ..Default::default()
+      }),
+      "\
+warning: Too long.
+   ,- /style.scss:10:1
+   |
+10 | a {
+   | ^^^
+11 |   b: c;
+   |   ^^^^^
+12 | }
+   | ^ here
+"
+    );
+  }
+
+  #[test]
+  fn without_context() {
+    let report = Report::new(Severity::Debug, "Hi.")
+      .label(span(None, (2, 4), (2, 7), "foo"), None::<String>);
+    assert_eq!(
+      report.render(&RenderOptions::default()),
+      "\
+debug: Hi.
+  ┌─ /style.scss:3:5
+  │
+3 │     foo
+  │     ^^^
+"
+    );
+  }
+
+  #[test]
+  fn expands_tabs() {
+    let report = Report::new(Severity::Error, "Oops.")
+      .label(span(Some("\ta: b"), (0, 1), (0, 2), "a"), None::<String>);
+    assert!(report
+      .render(&RenderOptions::default())
+      .contains("1 │     a: b\n  │     ^\n"));
+  }
+
+  #[test]
+  fn multiple_labels_and_notes() {
+    let report = Report::new(Severity::Error, "Conflict.")
+      .label(span(Some("a"), (0, 0), (0, 1), "a"), Some("first"))
+      .label(span(Some("b"), (99, 0), (99, 1), "b"), Some("second"))
+      .note("Pick one.");
+    assert_eq!(
+      report.render(&RenderOptions::default()),
+      "\
+error: Conflict.
+    ┌─ /style.scss:1:1
+    │
+  1 │ a
+    │ ^ first
+    ┌─ /style.scss:100:1
+    │
+100 │ b
+    │ ^ second
+    = Pick one.
+"
+    );
+  }
+
+  #[test]
+  fn wraps_to_width() {
+    let report = Report::new(Severity::Error, "one two three four five six")
+      .note("seven eight nine ten eleven twelve");
+    assert_eq!(
+      report.render(&RenderOptions {
+        width: Some(27),
+        ..Default::default()
+      }),
+      "\
+error: one two three four
+       five six
+ = seven eight nine ten
+   eleven twelve
+"
+    );
+  }
+
+  #[test]
+  fn colors() {
+    let report = Report::new(Severity::Warning, "Careful.");
+    let rendered = report.render(&RenderOptions {
+      color: true,
+      ..Default::default()
+    });
+    assert!(rendered.contains("\u{1b}[1;33mwarning\u{1b}[0m"));
+  }
+}
+
+#[cfg(feature = "miette")]
+mod miette {
+  use ::miette::Diagnostic;
+
+  use super::*;
+
+  #[test]
+  fn labels_the_primary_span_within_its_context() {
+    let report = Report::new(Severity::Warning, "Careful.")
+      .label(span(Some("a {b: $c}"), (0, 6), (0, 8), "$c"), Some("here"))
+      .note("Really.");
+    assert_eq!(report.severity(), Some(::miette::Severity::Warning));
+    let labels: Vec<_> = report.labels().unwrap().collect();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].offset(), 6);
+    assert_eq!(labels[0].len(), 2);
+    assert_eq!(labels[0].label(), Some("here"));
+    assert_eq!(report.help().unwrap().to_string(), "Really.");
+  }
+}
@@ -0,0 +1,106 @@
+#![cfg(unix)]
+
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::{env, fs, os::unix::fs::PermissionsExt, path::Path};
+
+use helpers::Sandbox;
+use sass_embedded::{DiscoveryOptions, ExceptionKind};
+
+fn executable(sandbox: &Sandbox, path: &str) -> std::path::PathBuf {
+  let path = sandbox.path().join(path);
+  sandbox.write(&path, "#!/bin/sh");
+  fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+  path
+}
+
+fn options(root: &Path) -> DiscoveryOptions {
+  DiscoveryOptions {
+    env_var: None,
+    search_path: false,
+    node_modules_root: Some(root.join("project/src")),
+    cache_dir: Some(root.join("cache")),
+  }
+}
+
+#[test]
+fn finds_the_path_in_the_env_var() {
+  let sandbox = Sandbox::default();
+  let path = executable(&sandbox, "bin/dart-sass-embedded");
+  env::set_var("SASS_EMBEDDED_PATH_SPEC_FILE", &path);
+  env::set_var("SASS_EMBEDDED_PATH_SPEC_DIR", sandbox.path().join("bin"));
+  for var in [
+    "SASS_EMBEDDED_PATH_SPEC_FILE",
+    "SASS_EMBEDDED_PATH_SPEC_DIR",
+  ] {
+    let found = DiscoveryOptions {
+      env_var: Some(var.into()),
+      ..options(sandbox.path())
+    }
+    .find()
+    .unwrap();
+    assert_eq!(found, path);
+  }
+}
+
+#[test]
+fn finds_the_executable_vendored_in_node_modules() {
+  let sandbox = Sandbox::default();
+  let path = executable(
+    &sandbox,
+    "project/node_modules/sass-embedded/dist/lib/src/vendor/\
+     dart-sass-embedded/dart-sass-embedded",
+  );
+  assert_eq!(options(sandbox.path()).find().unwrap(), path);
+}
+
+#[test]
+fn finds_the_executable_in_the_cache_dir() {
+  let sandbox = Sandbox::default();
+  let path = executable(&sandbox, "cache/sass_embedded/dart-sass-embedded");
+  assert_eq!(options(sandbox.path()).find().unwrap(), path);
+}
+
+#[test]
+fn skips_files_that_are_not_executable() {
+  let sandbox = Sandbox::default();
+  sandbox.write(sandbox.path().join("cache/dart-sass-embedded"), "");
+  assert!(options(sandbox.path()).find().is_err());
+}
+
+#[test]
+fn reports_every_location_it_tried() {
+  let sandbox = Sandbox::default();
+  fs::create_dir_all(
+    sandbox
+      .path()
+      .join("node_modules/sass-embedded-linux-x64/dist/lib/src/vendor"),
+  )
+  .unwrap();
+  let err = DiscoveryOptions {
+    env_var: Some("SASS_EMBEDDED_PATH_SPEC_UNSET".into()),
+    ..options(sandbox.path())
+  }
+  .find()
+  .unwrap_err();
+  assert_eq!(err.kind(), ExceptionKind::Spawn);
+  let vendor = sandbox
+    .path()
+    .join("node_modules/sass-embedded-linux-x64/dist/lib/src/vendor");
+  assert_eq!(
+    err.message(),
+    format!(
+      "Couldn't find the dart-sass-embedded executable. Tried:\n  \
+       - $SASS_EMBEDDED_PATH_SPEC_UNSET (not set)\n  \
+       - {}/dart-sass-embedded/dart-sass-embedded\n  \
+       - {}/dart-sass-embedded\n  \
+       - {}/cache/sass_embedded/dart-sass-embedded\n  \
+       - {}/cache/dart-sass-embedded",
+      vendor.display(),
+      vendor.display(),
+      sandbox.path().display(),
+      sandbox.path().display(),
+    )
+  );
+}
@@ -0,0 +1,313 @@
+#![cfg(feature = "disk-cache")]
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::atomic::{AtomicUsize, Ordering},
+  thread,
+  time::Duration,
+};
+
+use sass_embedded::{
+  CompileResult, DiskCache, Options, OptionsBuilder, OutputStyle, Result, Url,
+  Version, VersionInfo,
+};
+
+fn dart_sass(version: &str) -> VersionInfo {
+  VersionInfo {
+    protocol_version: Version::new(1, 2, 0),
+    compiler_version: Version::parse(version).unwrap(),
+    implementation_version: Version::parse(version).unwrap(),
+    implementation_name: "Dart Sass".to_string(),
+  }
+}
+
+/// Counts the compilations of an entrypoint that lists the files it loads,
+/// one per line, and compiles to their contents.
+#[derive(Default)]
+struct Compiler {
+  count: AtomicUsize,
+}
+
+impl Compiler {
+  fn compile(&self, path: &Path, _options: Options) -> Result<CompileResult> {
+    self.count.fetch_add(1, Ordering::SeqCst);
+    let dir = path.parent().unwrap();
+    let mut css = String::new();
+    let mut loaded_urls = vec![Url::from_file_path(path).unwrap()];
+    for name in fs::read_to_string(path).unwrap().lines() {
+      css.push_str(&fs::read_to_string(dir.join(name)).unwrap());
+      loaded_urls.push(Url::from_file_path(dir.join(name)).unwrap());
+    }
+    Ok(CompileResult {
+      css,
+      loaded_urls,
+      source_map: Some(format!("{{\"sources\":{}}}", path.display())),
+    })
+  }
+
+  fn count(&self) -> usize {
+    self.count.load(Ordering::SeqCst)
+  }
+}
+
+struct Sandbox {
+  dir: tempfile::TempDir,
+}
+
+impl Sandbox {
+  fn new() -> Self {
+    Self {
+      dir: tempfile::tempdir().unwrap(),
+    }
+  }
+
+  fn write(&self, name: &str, contents: &str) -> PathBuf {
+    let path = self.dir.path().join(name);
+    fs::write(&path, contents).unwrap();
+    path
+  }
+
+  fn cache(&self) -> DiskCache {
+    DiskCache::new(self.dir.path().join("cache")).unwrap()
+  }
+}
+
+fn compile(
+  cache: &DiskCache,
+  compiler: &Compiler,
+  path: &Path,
+  options: Options,
+  version: &VersionInfo,
+) -> CompileResult {
+  cache
+    .get_or_compile(path, options, version, |p, o| compiler.compile(p, o))
+    .unwrap()
+}
+
+#[test]
+fn reuses_results_across_instances() {
+  let sandbox = Sandbox::new();
+  sandbox.write("_a.scss", "a {b: c}");
+  let entry = sandbox.write("style.scss", "_a.scss");
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  let first = compile(
+    &sandbox.cache(),
+    &compiler,
+    &entry,
+    Options::default(),
+    &version,
+  );
+  // As if in another process.
+  let second = compile(
+    &sandbox.cache(),
+    &compiler,
+    &entry,
+    Options::default(),
+    &version,
+  );
+  assert_eq!(compiler.count(), 1);
+  assert_eq!(second.css, "a {b: c}");
+  assert_eq!(second.css, first.css);
+  assert_eq!(second.source_map, first.source_map);
+  assert_eq!(second.loaded_urls, first.loaded_urls);
+  assert!(sandbox.cache().size() > 0);
+}
+
+#[test]
+fn recompiles_once_a_loaded_file_changes() {
+  let sandbox = Sandbox::new();
+  sandbox.write("_a.scss", "a {b: c}");
+  let entry = sandbox.write("style.scss", "_a.scss");
+  let cache = sandbox.cache();
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  sandbox.write("_a.scss", "a {b: d}");
+  let res = compile(&cache, &compiler, &entry, Options::default(), &version);
+  assert_eq!(res.css, "a {b: d}");
+  assert_eq!(compiler.count(), 2);
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  assert_eq!(compiler.count(), 2);
+}
+
+#[test]
+fn keys_results_on_the_entrypoint_options_and_compiler() {
+  let sandbox = Sandbox::new();
+  let entry = sandbox.write("style.scss", "");
+  let cache = sandbox.cache();
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  let compressed = || {
+    OptionsBuilder::default()
+      .style(OutputStyle::Compressed)
+      .build()
+  };
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  compile(&cache, &compiler, &entry, compressed(), &version);
+  assert_eq!(compiler.count(), 2);
+  compile(
+    &cache,
+    &compiler,
+    &entry,
+    Options::default(),
+    &dart_sass("1.63.0"),
+  );
+  assert_eq!(compiler.count(), 3);
+  sandbox.write("_empty.scss", "");
+  sandbox.write("style.scss", "_empty.scss");
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  assert_eq!(compiler.count(), 4);
+  compile(
+    &cache,
+    &compiler,
+    &entry,
+    compressed(),
+    &dart_sass("1.63.0"),
+  );
+  assert_eq!(compiler.count(), 5);
+}
+
+#[test]
+fn does_not_store_results_with_importer_urls() {
+  let sandbox = Sandbox::new();
+  let entry = sandbox.write("style.scss", "");
+  let cache = sandbox.cache();
+  let count = AtomicUsize::new(0);
+  for _ in 0..2 {
+    cache
+      .get_or_compile(
+        &entry,
+        Options::default(),
+        &dart_sass("1.62.0"),
+        |_, _| {
+          count.fetch_add(1, Ordering::SeqCst);
+          Ok(CompileResult {
+            css: String::new(),
+            loaded_urls: vec![Url::parse("u:theme").unwrap()],
+            source_map: None,
+          })
+        },
+      )
+      .unwrap();
+  }
+  assert_eq!(count.load(Ordering::SeqCst), 2);
+  assert_eq!(cache.size(), 0);
+}
+
+#[test]
+fn does_not_store_results_whose_entrypoint_changed_during_the_compile() {
+  let sandbox = Sandbox::new();
+  sandbox.write("_a.scss", "a {b: c}");
+  let entry = sandbox.write("style.scss", "_a.scss");
+  let cache = sandbox.cache();
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  cache
+    .get_or_compile(&entry, Options::default(), &version, |p, o| {
+      let res = compiler.compile(p, o);
+      sandbox.write("style.scss", "_a.scss\n_a.scss");
+      res
+    })
+    .unwrap();
+  assert_eq!(cache.size(), 0);
+}
+
+#[test]
+fn does_not_store_results_whose_files_changed_during_the_compile() {
+  let sandbox = Sandbox::new();
+  sandbox.write("_a.scss", "a {b: c}");
+  let entry = sandbox.write("style.scss", "_a.scss");
+  let cache = sandbox.cache();
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  sandbox.write("_a.scss", "a {b: d}");
+  let res = cache
+    .get_or_compile(&entry, Options::default(), &version, |p, o| {
+      let res = compiler.compile(p, o);
+      sandbox.write("_a.scss", "a {b: e}");
+      res
+    })
+    .unwrap();
+  assert_eq!(res.css, "a {b: d}");
+  let res = compile(&cache, &compiler, &entry, Options::default(), &version);
+  assert_eq!(res.css, "a {b: e}");
+  assert_eq!(compiler.count(), 3);
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  assert_eq!(compiler.count(), 3);
+}
+
+#[test]
+fn evicts_the_least_recently_used_entries() {
+  let sandbox = Sandbox::new();
+  let entries: Vec<_> = ["a", "b", "c"]
+    .iter()
+    .map(|name| {
+      sandbox.write(&format!("_{}.scss", name), &name.repeat(1000));
+      sandbox.write(&format!("{}.scss", name), &format!("_{}.scss", name))
+    })
+    .collect();
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  let cache = sandbox.cache();
+  compile(&cache, &compiler, &entries[0], Options::default(), &version);
+  let entry_size = cache.size();
+  let cache = cache.with_max_size(entry_size * 2 + entry_size / 2);
+  thread::sleep(Duration::from_millis(20));
+  compile(&cache, &compiler, &entries[1], Options::default(), &version);
+  thread::sleep(Duration::from_millis(20));
+  // Uses a, so b is the least recently used entry.
+  compile(&cache, &compiler, &entries[0], Options::default(), &version);
+  thread::sleep(Duration::from_millis(20));
+  compile(&cache, &compiler, &entries[2], Options::default(), &version);
+  assert_eq!(compiler.count(), 3);
+  assert!(cache.size() <= entry_size * 2 + entry_size / 2);
+
+  compile(&cache, &compiler, &entries[0], Options::default(), &version);
+  compile(&cache, &compiler, &entries[2], Options::default(), &version);
+  assert_eq!(compiler.count(), 3);
+  compile(&cache, &compiler, &entries[1], Options::default(), &version);
+  assert_eq!(compiler.count(), 4);
+}
+
+#[test]
+fn clears_every_entry() {
+  let sandbox = Sandbox::new();
+  let entry = sandbox.write("style.scss", "");
+  let cache = sandbox.cache();
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  cache.clear().unwrap();
+  assert_eq!(cache.size(), 0);
+  compile(&cache, &compiler, &entry, Options::default(), &version);
+  assert_eq!(compiler.count(), 2);
+}
+
+#[test]
+fn shares_the_directory_between_concurrent_writers() {
+  let sandbox = Sandbox::new();
+  sandbox.write("_a.scss", &"a {b: c}\n".repeat(1000));
+  let entry = sandbox.write("style.scss", "_a.scss");
+  let compiler = Compiler::default();
+  let version = dart_sass("1.62.0");
+  thread::scope(|scope| {
+    for _ in 0..8 {
+      scope.spawn(|| {
+        let cache = sandbox.cache();
+        for _ in 0..10 {
+          let res =
+            compile(&cache, &compiler, &entry, Options::default(), &version);
+          assert_eq!(res.css.len(), 9000);
+        }
+      });
+    }
+  });
+  let leftovers: Vec<_> = fs::read_dir(sandbox.dir.path().join("cache"))
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name())
+    .collect();
+  assert_eq!(leftovers.len(), 1, "{:?}", leftovers);
+}
@@ -0,0 +1,309 @@
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+  },
+};
+
+use helpers::exe_path;
+use sass_embedded::{
+  Exception, ListSeparator, Sass, SassFunction, SassList, SassNumber,
+  SassString, StringOptionsBuilder, Value,
+};
+
+#[test]
+fn passes_an_argument_to_a_custom_function_and_uses_its_return_value() {
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "a {b: foo(bar)}",
+      StringOptionsBuilder::default()
+        .function("foo($arg)", |args: &[Value]| {
+          assert_eq!(args, &[Value::String(SassString::unquoted("bar"))]);
+          Ok(SassString::unquoted("result").into())
+        })
+        .build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: result;\n}");
+}
+
+#[test]
+fn passes_no_arguments_to_a_custom_function() {
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "a {b: foo()}",
+      StringOptionsBuilder::default()
+        .function("foo()", |args: &[Value]| {
+          assert!(args.is_empty());
+          Ok(Value::Null)
+        })
+        .build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "");
+}
+
+#[test]
+fn passes_multiple_arguments_to_a_custom_function() {
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "a {b: foo(x, y, z)}",
+      StringOptionsBuilder::default()
+        .function("foo($arg1, $arg2, $arg3)", |args: &[Value]| {
+          assert_eq!(
+            args,
+            &[
+              Value::String(SassString::unquoted("x")),
+              Value::String(SassString::unquoted("y")),
+              Value::String(SassString::unquoted("z")),
+            ]
+          );
+          Ok(SassNumber::new(1.0).into())
+        })
+        .build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: 1;\n}");
+}
+
+#[test]
+fn passes_a_default_argument_value() {
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "a {b: foo()}",
+      StringOptionsBuilder::default()
+        .function("foo($arg: 1)", |args: &[Value]| {
+          assert_eq!(args, &[Value::Number(SassNumber::new(1.0))]);
+          Ok(SassNumber::with_unit(2.0, "px").into())
+        })
+        .build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: 2px;\n}");
+}
+
+mod dash_insensitive {
+  use super::*;
+
+  #[test]
+  fn defined_with_dashes_and_called_with_underscores() {
+    let sass = Sass::new(exe_path()).unwrap();
+    let res = sass
+      .compile_string(
+        "a {b: foo_bar()}",
+        StringOptionsBuilder::default()
+          .function("foo-bar()", |_: &[Value]| Ok(SassNumber::new(1.0).into()))
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "a {\n  b: 1;\n}");
+  }
+
+  #[test]
+  fn defined_with_underscores_and_called_with_dashes() {
+    let sass = Sass::new(exe_path()).unwrap();
+    let res = sass
+      .compile_string(
+        "a {b: foo-bar()}",
+        StringOptionsBuilder::default()
+          .function("foo_bar()", |_: &[Value]| Ok(SassNumber::new(1.0).into()))
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "a {\n  b: 1;\n}");
+  }
+}
+
+mod rest_arguments {
+  use super::*;
+
+  #[test]
+  fn passes_positional_arguments_as_an_argument_list() {
+    let sass = Sass::new(exe_path()).unwrap();
+    let res = sass
+      .compile_string(
+        "a {b: foo(x, y, z)}",
+        StringOptionsBuilder::default()
+          .function("foo($args...)", |args: &[Value]| {
+            match &args[0] {
+              Value::ArgumentList(list) => {
+                assert_eq!(list.contents().len(), 3);
+                assert_eq!(list.separator(), ListSeparator::Comma);
+              }
+              _ => panic!("expected an argument list"),
+            }
+            Ok(Value::Null)
+          })
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "");
+  }
+
+  #[test]
+  fn allows_keyword_arguments_that_were_accessed() {
+    let sass = Sass::new(exe_path()).unwrap();
+    let res = sass
+      .compile_string(
+        "a {b: foo($bar: baz)}",
+        StringOptionsBuilder::default()
+          .function("foo($args...)", |args: &[Value]| match &args[0] {
+            Value::ArgumentList(list) => {
+              Ok(list.keywords().get("bar").unwrap().clone())
+            }
+            _ => panic!("expected an argument list"),
+          })
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "a {\n  b: baz;\n}");
+  }
+
+  #[test]
+  fn throws_an_error_for_keyword_arguments_that_were_not_accessed() {
+    let sass = Sass::new(exe_path()).unwrap();
+    let err = sass
+      .compile_string(
+        "a {b: foo($bar: baz)}",
+        StringOptionsBuilder::default()
+          .function("foo($args...)", |_: &[Value]| Ok(Value::Null))
+          .build(),
+      )
+      .unwrap_err();
+    assert!(err.message().contains("No argument named $bar."));
+  }
+}
+
+#[test]
+fn returns_a_list() {
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "a {b: foo()}",
+      StringOptionsBuilder::default()
+        .function("foo()", |_: &[Value]| {
+          Ok(
+            SassList::new(
+              [
+                SassNumber::new(1.0).into(),
+                SassNumber::new(2.0).into(),
+                SassNumber::new(3.0).into(),
+              ],
+              ListSeparator::Space,
+              false,
+            )
+            .into(),
+          )
+        })
+        .build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: 1 2 3;\n}");
+}
+
+#[test]
+fn returns_a_host_function_that_can_be_called() {
+  let calls = Arc::new(AtomicU32::new(0));
+  let counter = Arc::clone(&calls);
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "@use 'sass:meta'; a {b: meta.call(foo(), 1)}",
+      StringOptionsBuilder::default()
+        .function("foo()", move |_: &[Value]| {
+          let counter = Arc::clone(&counter);
+          Ok(
+            SassFunction::new("bar($arg)", move |args: &[Value]| {
+              counter.fetch_add(1, Ordering::SeqCst);
+              Ok(args[0].clone())
+            })
+            .into(),
+          )
+        })
+        .build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: 1;\n}");
+  assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn passes_functions_set_as_a_map() {
+  let mut functions: HashMap<String, sass_embedded::BoxCustomFunction> =
+    HashMap::new();
+  functions.insert(
+    "foo()".to_string(),
+    Box::new(|_: &[Value]| Ok(SassNumber::new(1.0).into())),
+  );
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "a {b: foo()}",
+      StringOptionsBuilder::default().functions(functions).build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: 1;\n}");
+}
+
+mod error {
+  use super::*;
+
+  #[test]
+  fn wraps_an_error_returned_by_the_function() {
+    let sass = Sass::new(exe_path()).unwrap();
+    let err = sass
+      .compile_string(
+        "a {b: foo()}",
+        StringOptionsBuilder::default()
+          .function("foo()", |_: &[Value]| Err(Exception::new("heck").into()))
+          .build(),
+      )
+      .unwrap_err();
+    assert!(err.message().contains("heck"));
+    assert_eq!(err.span().unwrap().start.line, 0);
+  }
+
+  #[test]
+  fn fails_for_an_invalid_signature() {
+    let sass = Sass::new(exe_path()).unwrap();
+    let err = sass
+      .compile_string(
+        "a {b: foo()}",
+        StringOptionsBuilder::default()
+          .function("foo(", |_: &[Value]| Ok(Value::Null))
+          .build(),
+      )
+      .unwrap_err();
+    assert!(!err.message().is_empty());
+  }
+}
+
+#[test]
+fn returns_arguments_unchanged() {
+  let sass = Sass::new(exe_path()).unwrap();
+  let res = sass
+    .compile_string(
+      "a {b: foo(hsl(0, 100%, 50%), (c: d), [1, 2], null)}",
+      StringOptionsBuilder::default()
+        .function("foo($args...)", |args: &[Value]| {
+          let list = args[0].as_list();
+          assert_eq!(list.len(), 4);
+          assert_eq!(list[0].assert_color(None).unwrap().alpha(), 1.0);
+          assert!(list[1].assert_map(None).is_ok());
+          assert!(list[2].has_brackets());
+          assert!(list[3].is_null());
+          Ok(list[0].clone())
+        })
+        .build(),
+    )
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: red;\n}");
+}
@@ -5,30 +5,32 @@ use std::{
 };
 
 use gag::BufferRedirect;
-use sass_embedded::Url;
+use sass_embedded::{DiscoveryOptions, Url};
 use tempfile::TempDir;
 
 // #[test]
 // fn version_smoke() {
-//   let mut sass = Sass::new(exe_path()).unwrap();
+//   let sass = Sass::new(exe_path()).unwrap();
 //   let info = sass.info().unwrap();
 //   // !!! the crate's version should be the same as the embedded's version !!!
 //   // once the crate stable...
 //   assert_eq!(info, "sass-embedded\t#1.54.8");
 // }
 
-#[cfg(target_family = "windows")]
-pub fn exe_path() -> std::path::PathBuf {
-  std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR")))
+/// The compiler downloaded by `npm run setup`, or else the one found by
+/// [DiscoveryOptions].
+pub fn exe_path() -> PathBuf {
+  let ext = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
     .join("ext/sass/sass-embedded")
-    .join("dart-sass-embedded.bat")
-}
-
-#[cfg(target_family = "unix")]
-pub fn exe_path() -> std::path::PathBuf {
-  std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR")))
-    .join("ext/sass/sass-embedded")
-    .join("dart-sass-embedded")
+    .join(if cfg!(windows) {
+      "dart-sass-embedded.bat"
+    } else {
+      "dart-sass-embedded"
+    });
+  if ext.exists() {
+    return ext;
+  }
+  DiscoveryOptions::default().find().unwrap_or(ext)
 }
 
 #[derive(Debug)]
@@ -49,6 +51,7 @@ impl Sandbox {
     self.temp.path()
   }
 
+  #[allow(dead_code)]
   pub fn write(&self, path: impl AsRef<Path>, contents: &str) -> &Self {
     let path = path.as_ref();
     fs::create_dir_all(path.parent().unwrap()).unwrap();
@@ -0,0 +1,187 @@
+use sass_embedded::SassNumber;
+
+fn px(n: f64) -> SassNumber {
+  SassNumber::with_unit(n, "px")
+}
+
+mod construction {
+  use super::*;
+
+  #[test]
+  fn cancels_compatible_units() {
+    let number = SassNumber::with_units(1.0, ["in"], ["px"]);
+    assert!(!number.has_units());
+    assert_eq!(number.value(), 96.0);
+  }
+
+  #[test]
+  fn keeps_incompatible_units() {
+    let number = SassNumber::with_units(1.0, ["px"], ["s"]);
+    assert_eq!(number.numerator_units(), ["px"]);
+    assert_eq!(number.denominator_units(), ["s"]);
+  }
+}
+
+mod conversion {
+  use super::*;
+
+  #[test]
+  fn converts_compatible_units() {
+    let number = SassNumber::with_unit(1.0, "in");
+    assert_eq!(number.convert_value(&["px"], &[], None).unwrap(), 96.0);
+    assert_eq!(
+      SassNumber::with_unit(180.0, "deg")
+        .convert_value(&["rad"], &[], None)
+        .unwrap(),
+      std::f64::consts::PI
+    );
+    assert_eq!(
+      SassNumber::with_unit(1.0, "kHz")
+        .convert_value(&["Hz"], &[], None)
+        .unwrap(),
+      1000.0
+    );
+  }
+
+  #[test]
+  fn converts_denominator_units() {
+    let number = SassNumber::with_units(1.0, ["px"], ["ms"]);
+    let converted = number.convert(&["px"], &["s"], None).unwrap();
+    assert_eq!(converted.value(), 1000.0);
+    assert_eq!(converted.denominator_units(), ["s"]);
+  }
+
+  #[test]
+  fn throws_for_incompatible_units() {
+    let err = px(1.0).convert_value(&["s"], &[], Some("num")).unwrap_err();
+    assert_eq!(
+      err.message(),
+      "$num: Expected 1px to have a time unit (s, ms)."
+    );
+  }
+
+  #[test]
+  fn convert_requires_both_or_neither_to_have_units() {
+    let err = SassNumber::new(1.0)
+      .convert(&["px"], &[], None)
+      .unwrap_err();
+    assert_eq!(
+      err.message(),
+      "Expected 1 to have a length unit (in, cm, mm, q, pt, pc, px)."
+    );
+    let err = px(1.0)
+      .convert_to_match(&SassNumber::new(1.0), None, Some("other"))
+      .unwrap_err();
+    assert_eq!(
+      err.message(),
+      "1px and $other: 1 have incompatible units \
+       (one has units and the other doesn't)."
+    );
+  }
+
+  #[test]
+  fn coerce_treats_unitless_as_any_unit() {
+    let coerced = SassNumber::new(1.0).coerce(&["px"], &[], None).unwrap();
+    assert_eq!(coerced, px(1.0));
+    assert_eq!(px(1.0).coerce_value(&[], &[], None).unwrap(), 1.0);
+  }
+
+  #[test]
+  fn checks_compatibility() {
+    assert!(px(1.0).compatible_with_unit("in"));
+    assert!(!px(1.0).compatible_with_unit("em"));
+    assert!(px(1.0).is_comparable_to(&SassNumber::new(1.0)));
+    assert!(!px(1.0).is_comparable_to(&SassNumber::with_unit(1.0, "s")));
+  }
+}
+
+mod equality {
+  use super::*;
+
+  #[test]
+  fn compares_across_compatible_units() {
+    assert_eq!(SassNumber::with_unit(1.0, "in"), px(96.0));
+    assert_eq!(
+      SassNumber::with_unit(1.0, "s"),
+      SassNumber::with_unit(1000.0, "ms")
+    );
+    assert_ne!(px(1.0), SassNumber::with_unit(1.0, "em"));
+    assert_ne!(px(1.0), SassNumber::new(1.0));
+  }
+
+  #[test]
+  fn uses_fuzzy_precision() {
+    assert_eq!(px(1.0), px(1.0 + 1e-12));
+    assert_ne!(px(1.0), px(1.0 + 1e-10));
+  }
+}
+
+mod arithmetic {
+  use super::*;
+
+  #[test]
+  fn adds_and_subtracts_in_the_left_units() {
+    let sum = px(1.0).plus(&SassNumber::with_unit(1.0, "in")).unwrap();
+    assert_eq!(sum.value(), 97.0);
+    assert!(sum.has_unit("px"));
+    let difference = SassNumber::new(2.0).minus(&px(1.0)).unwrap();
+    assert_eq!(difference, px(1.0));
+  }
+
+  #[test]
+  fn throws_for_incompatible_units() {
+    let err = px(1.0).plus(&SassNumber::with_unit(1.0, "s")).unwrap_err();
+    assert_eq!(err.message(), "1px and 1s have incompatible units.");
+  }
+
+  #[test]
+  fn multiplies_and_divides_units() {
+    let product = px(2.0).times(&SassNumber::with_unit(3.0, "s")).unwrap();
+    assert_eq!(product.to_string(), "6px*s");
+    let quotient = px(96.0)
+      .divided_by(&SassNumber::with_unit(1.0, "in"))
+      .unwrap();
+    assert!(!quotient.has_units());
+    assert_eq!(quotient.value(), 1.0);
+  }
+
+  #[test]
+  fn modulo_takes_the_sign_of_the_divisor() {
+    assert_eq!(px(5.0).modulo(&px(3.0)).unwrap(), px(2.0));
+    assert_eq!(px(-5.0).modulo(&px(3.0)).unwrap(), px(1.0));
+    assert_eq!(px(5.0).modulo(&px(-3.0)).unwrap(), px(-1.0));
+  }
+
+  #[test]
+  fn compares_with_fuzzy_precision() {
+    assert!(px(1.0)
+      .less_than(&SassNumber::with_unit(1.0, "in"))
+      .unwrap());
+    assert!(!px(1.0).greater_than(&px(1.0 + 1e-12)).unwrap());
+    assert!(px(1.0).greater_than_or_equals(&px(1.0 + 1e-12)).unwrap());
+    assert!(px(1.0).less_than(&SassNumber::with_unit(1.0, "s")).is_err());
+  }
+}
+
+mod assertions {
+  use super::*;
+
+  #[test]
+  fn asserts_units() {
+    assert!(px(1.0).assert_unit("px", None).is_ok());
+    let err = px(1.0).assert_unit("em", Some("num")).unwrap_err();
+    assert_eq!(err.message(), "$num: Expected 1px to have unit \"em\".");
+    let err = px(1.0).assert_no_units(None).unwrap_err();
+    assert_eq!(err.message(), "Expected 1px to have no units.");
+  }
+
+  #[test]
+  fn asserts_ranges() {
+    assert_eq!(
+      px(1.0 + 1e-12).assert_in_range(0.0, 1.0, None).unwrap(),
+      1.0
+    );
+    let err = px(2.0).assert_in_range(0.0, 1.0, None).unwrap_err();
+    assert_eq!(err.message(), "Expected 2px to be within 0px and 1px.");
+  }
+}
@@ -0,0 +1,147 @@
+#[path = "helpers.rs"]
+mod helpers;
+
+use std::{sync::Arc, thread, time::Duration};
+
+use helpers::exe_path;
+use sass_embedded::{
+  EmbeddedPool, PoolOptions, PoolOptionsBuilder, StringOptions,
+};
+
+#[test]
+fn rejects_invalid_sizes() {
+  let err = EmbeddedPool::new(
+    exe_path(),
+    PoolOptionsBuilder::default().max_size(0).build(),
+  )
+  .unwrap_err();
+  assert!(err.message().starts_with("Invalid pool size"));
+  assert!(EmbeddedPool::new(
+    exe_path(),
+    PoolOptionsBuilder::default()
+      .min_size(3)
+      .max_size(2)
+      .build(),
+  )
+  .is_err());
+}
+
+#[test]
+fn starts_min_size_compilers() {
+  let pool = EmbeddedPool::new(
+    exe_path(),
+    PoolOptionsBuilder::default()
+      .min_size(2)
+      .max_size(4)
+      .build(),
+  )
+  .unwrap();
+  assert_eq!(pool.size(), 2);
+}
+
+#[test]
+fn compiles_in_parallel() {
+  let pool = Arc::new(
+    EmbeddedPool::new(
+      exe_path(),
+      PoolOptionsBuilder::default()
+        .min_size(0)
+        .max_size(3)
+        .build(),
+    )
+    .unwrap(),
+  );
+  let handles: Vec<_> = (0..12)
+    .map(|i| {
+      let pool = Arc::clone(&pool);
+      thread::spawn(move || {
+        pool
+          .compile_string(format!("a {{b: {i}}}"), StringOptions::default())
+          .unwrap()
+          .css
+      })
+    })
+    .collect();
+  for (i, handle) in handles.into_iter().enumerate() {
+    assert_eq!(handle.join().unwrap(), format!("a {{\n  b: {i};\n}}"));
+  }
+  assert!(pool.size() >= 1 && pool.size() <= 3);
+}
+
+#[test]
+fn reuses_an_idle_compiler() {
+  let pool = EmbeddedPool::new(
+    exe_path(),
+    PoolOptionsBuilder::default()
+      .min_size(0)
+      .max_size(4)
+      .build(),
+  )
+  .unwrap();
+  for _ in 0..3 {
+    pool
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap();
+  }
+  assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn shuts_down_idle_compilers() {
+  let pool = EmbeddedPool::new(
+    exe_path(),
+    PoolOptions {
+      min_size: 1,
+      max_size: 2,
+      idle_timeout: Some(Duration::from_millis(50)),
+    },
+  )
+  .unwrap();
+  thread::scope(|s| {
+    for _ in 0..2 {
+      s.spawn(|| {
+        pool
+          .compile_string("a {b: c}", StringOptions::default())
+          .unwrap()
+      });
+    }
+  });
+  thread::sleep(Duration::from_millis(200));
+  assert_eq!(pool.size(), 1);
+  pool
+    .compile_string("a {b: c}", StringOptions::default())
+    .unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn starts_compilers_without_blocking_the_pool() {
+  use std::{os::unix::fs::PermissionsExt, time::Instant};
+
+  use helpers::Sandbox;
+  use sass_embedded::CompilerCommand;
+
+  let sandbox = Sandbox::default();
+  let path = sandbox.path().join("compiler.sh");
+  // Takes a while to fail the handshake.
+  sandbox.write(&path, "#!/bin/sh\nsleep 1\n");
+  std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+    .unwrap();
+  let pool = EmbeddedPool::with_command(
+    CompilerCommand::new(path).handshake(),
+    PoolOptionsBuilder::default()
+      .min_size(0)
+      .max_size(2)
+      .build(),
+  )
+  .unwrap();
+  thread::scope(|s| {
+    let compile =
+      s.spawn(|| pool.compile_string("a {b: c}", StringOptions::default()));
+    thread::sleep(Duration::from_millis(200));
+    let start = Instant::now();
+    assert_eq!(pool.size(), 0);
+    assert!(start.elapsed() < Duration::from_millis(500));
+    assert!(compile.join().unwrap().is_err());
+  });
+}
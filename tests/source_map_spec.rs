@@ -0,0 +1,615 @@
+use sass_embedded::{CompileResult, Mapping, OriginalLocation, SourceMap, Url};
+
+/// The source map Dart Sass generates for
+///
+/// ```scss
+/// a {
+///   b: c;
+///   d: e;
+/// }
+/// ```
+const MAP: &str = r#"{"version":3,"sourceRoot":"","sources":["file:///project/styles/style.scss"],"names":[],"mappings":"AAAA;EACE;EACA","file":"style.css"}"#;
+
+fn original(source: usize, line: usize, column: usize) -> OriginalLocation {
+  OriginalLocation {
+    source,
+    line,
+    column,
+    name: None,
+  }
+}
+
+mod parse {
+  use super::*;
+
+  #[test]
+  fn reads_every_field() {
+    let map = SourceMap::parse(MAP).unwrap();
+    assert_eq!(map.file.as_deref(), Some("style.css"));
+    assert_eq!(map.source_root.as_deref(), Some(""));
+    assert_eq!(map.sources, ["file:///project/styles/style.scss"]);
+    assert!(map.sources_content.is_empty());
+    assert!(map.names.is_empty());
+    assert_eq!(map.mappings.len(), 3);
+    assert_eq!(
+      map.mappings[1],
+      [Mapping {
+        generated_column: 2,
+        original: Some(original(0, 1, 2)),
+      }]
+    );
+  }
+
+  #[test]
+  fn reads_sources_content_with_escapes() {
+    let map = SourceMap::parse(
+      r#"{"version":3,"sources":["a","b"],"sourcesContent":["a {\n  b: \"é😀\"\n}",null],"names":[],"mappings":""}"#,
+    )
+    .unwrap();
+    assert_eq!(map.source_content(0), Some("a {\n  b: \"é😀\"\n}"));
+    assert_eq!(map.source_content(1), None);
+  }
+
+  #[test]
+  fn reads_unicode_escapes() {
+    let map = SourceMap::parse(
+      r#"{"version":3,"sources":["\u00e9\ud83d\ude00\/"],"names":[],"mappings":""}"#,
+    )
+    .unwrap();
+    assert_eq!(map.sources, ["é😀/"]);
+    assert!(SourceMap::parse(
+      r#"{"version":3,"sources":["\ud83d"],"names":[],"mappings":""}"#
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn ignores_unknown_fields() {
+    let map = SourceMap::parse(
+      r#"{"version":3,"x_google_ignoreList":[0],"x":{"y":[true,false,null,-1.5e3]},"sources":[],"names":[],"mappings":""}"#,
+    )
+    .unwrap();
+    assert!(map.sources.is_empty());
+  }
+
+  #[test]
+  fn parses_from_a_compile_result() {
+    let res = CompileResult {
+      css: "a {\n  b: c;\n  d: e;\n}".to_string(),
+      loaded_urls: Vec::new(),
+      source_map: Some(MAP.to_string()),
+    };
+    assert_eq!(
+      res.parse_source_map().unwrap(),
+      Some(SourceMap::parse(MAP).unwrap())
+    );
+    let res = CompileResult {
+      source_map: None,
+      ..res
+    };
+    assert_eq!(res.parse_source_map().unwrap(), None);
+  }
+
+  #[test]
+  fn rejects_invalid_json() {
+    let err = SourceMap::parse(r#"{"version":3,"#).unwrap_err();
+    assert!(err.message().starts_with("Invalid source map:"));
+  }
+
+  #[test]
+  fn rejects_deeply_nested_json() {
+    let json = format!(r#"{{"version":3,"x":{}}}"#, "[".repeat(1_000_000));
+    let err = SourceMap::parse(&json).unwrap_err();
+    assert!(err.message().starts_with("Invalid source map:"));
+  }
+
+  #[test]
+  fn rejects_other_versions() {
+    let err = SourceMap::parse(r#"{"version":2,"sources":[],"mappings":""}"#)
+      .unwrap_err();
+    assert_eq!(err.message(), "Invalid source map: expected version 3");
+  }
+
+  #[test]
+  fn rejects_invalid_mappings() {
+    assert!(SourceMap::parse(
+      r#"{"version":3,"sources":[],"names":[],"mappings":"AA"}"#
+    )
+    .is_err());
+    assert!(SourceMap::parse(
+      r#"{"version":3,"sources":[],"names":[],"mappings":"A!AA"}"#
+    )
+    .is_err());
+  }
+}
+
+mod vlq {
+  use super::*;
+
+  #[test]
+  fn decodes_relative_fields() {
+    let mappings =
+      SourceMap::decode_mappings("AAAAA,SAAS;;GAAG,CACAC").unwrap();
+    assert_eq!(
+      mappings,
+      [
+        vec![
+          Mapping {
+            generated_column: 0,
+            original: Some(OriginalLocation {
+              name: Some(0),
+              ..original(0, 0, 0)
+            }),
+          },
+          Mapping {
+            generated_column: 9,
+            original: Some(original(0, 0, 9)),
+          },
+        ],
+        vec![],
+        vec![
+          Mapping {
+            generated_column: 3,
+            original: Some(original(0, 0, 12)),
+          },
+          Mapping {
+            generated_column: 4,
+            original: Some(OriginalLocation {
+              name: Some(1),
+              ..original(0, 1, 12)
+            }),
+          },
+        ],
+      ]
+    );
+  }
+
+  #[test]
+  fn decodes_negative_and_multi_digit_values() {
+    let mappings = SourceMap::decode_mappings("gCAAgC,CAAjB").unwrap();
+    assert_eq!(mappings[0][0].generated_column, 32);
+    assert_eq!(mappings[0][0].original, Some(original(0, 0, 32)));
+    assert_eq!(mappings[0][1].generated_column, 33);
+    assert_eq!(mappings[0][1].original, Some(original(0, 0, 15)));
+  }
+
+  #[test]
+  fn decodes_generated_only_segments() {
+    let mappings = SourceMap::decode_mappings("AAAA,E").unwrap();
+    assert_eq!(
+      mappings[0][1],
+      Mapping {
+        generated_column: 2,
+        original: None,
+      }
+    );
+  }
+
+  #[test]
+  fn round_trips() {
+    for mappings in [
+      "AAAAA,SAAS;;GAAG,CACAC",
+      "gCAAgC,CAAjB",
+      "AAAA;EACE;EACA",
+      "AAAA,E;",
+    ] {
+      let decoded = SourceMap::decode_mappings(mappings).unwrap();
+      assert_eq!(SourceMap::encode_mappings(&decoded), mappings);
+    }
+  }
+}
+
+mod lookup {
+  use super::*;
+
+  #[test]
+  fn finds_the_segment_that_covers_the_column() {
+    let map = SourceMap::parse(MAP).unwrap();
+    assert_eq!(map.lookup(0, 0), Some(&original(0, 0, 0)));
+    assert_eq!(map.lookup(0, 2), Some(&original(0, 0, 0)));
+    assert_eq!(map.lookup(2, 2), Some(&original(0, 2, 2)));
+    assert_eq!(map.lookup(2, 100), Some(&original(0, 2, 2)));
+  }
+
+  #[test]
+  fn returns_none_for_unmapped_positions() {
+    let map = SourceMap::parse(MAP).unwrap();
+    assert_eq!(map.lookup(1, 0), None);
+    assert_eq!(map.lookup(3, 0), None);
+    let map = SourceMap {
+      mappings: SourceMap::decode_mappings("AAAA,E").unwrap(),
+      ..map
+    };
+    assert_eq!(map.lookup(0, 3), None);
+  }
+}
+
+mod edit {
+  use super::*;
+
+  #[test]
+  fn rewrites_sources() {
+    let mut map = SourceMap::parse(MAP).unwrap();
+    map.rewrite_sources(|source| source.replace("styles", "scss"));
+    assert_eq!(map.sources, ["file:///project/scss/style.scss"]);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn makes_file_sources_relative() {
+    let mut map = SourceMap {
+      sources: vec![
+        "file:///project/styles/style.scss".to_string(),
+        "file:///project/node_modules/lib/_index.scss".to_string(),
+        "file:///elsewhere/a%20b.scss".to_string(),
+        "u:theme".to_string(),
+      ],
+      ..Default::default()
+    };
+    map.make_sources_relative("/project/styles");
+    assert_eq!(
+      map.sources,
+      [
+        "style.scss",
+        "../node_modules/lib/_index.scss",
+        "../../elsewhere/a%20b.scss",
+        "u:theme",
+      ]
+    );
+  }
+
+  #[test]
+  fn sets_source_content() {
+    let mut map = SourceMap::parse(MAP).unwrap();
+    assert!(map.set_source_content(0, Some("a {}".to_string())));
+    assert_eq!(map.sources_content, [Some("a {}".to_string())]);
+    assert!(map.set_source_content(0, None));
+    assert_eq!(map.source_content(0), None);
+  }
+
+  #[test]
+  fn ignores_source_content_of_missing_sources() {
+    let mut map = SourceMap::parse(MAP).unwrap();
+    assert!(!map.set_source_content(1, Some("a {}".to_string())));
+    assert!(map.sources_content.is_empty());
+  }
+
+  #[test]
+  fn offsets_lines() {
+    let mut map = SourceMap::parse(MAP).unwrap();
+    map.offset_lines(2);
+    assert_eq!(map.lookup(3, 2), Some(&original(0, 1, 2)));
+    assert_eq!(
+      SourceMap::encode_mappings(&map.mappings),
+      ";;AAAA;EACE;EACA"
+    );
+  }
+}
+
+mod to_json {
+  use super::*;
+
+  #[test]
+  fn round_trips() {
+    let mut map = SourceMap::parse(MAP).unwrap();
+    map.names.push("$var".to_string());
+    map.sources_content = vec![Some("a {\n\t\"b\": c\\d\u{1};\n}".to_string())];
+    let json = map.to_json();
+    assert_eq!(SourceMap::parse(&json).unwrap(), map);
+  }
+
+  #[test]
+  fn is_valid_json() {
+    let map = SourceMap::parse(MAP).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&map.to_json()).unwrap();
+    assert_eq!(json["version"], 3);
+    assert_eq!(json["file"], "style.css");
+    assert_eq!(json["sourceRoot"], "");
+    assert_eq!(json["sources"][0], "file:///project/styles/style.scss");
+    assert_eq!(json["mappings"], "AAAA;EACE;EACA");
+    assert!(json.get("sourcesContent").is_none());
+  }
+
+  #[test]
+  fn includes_urls_that_roundtrip_through_url() {
+    let map = SourceMap {
+      sources: vec![Url::parse("file:///a%20b.scss").unwrap().to_string()],
+      ..Default::default()
+    };
+    assert_eq!(
+      SourceMap::parse(&map.to_json()).unwrap().sources,
+      map.sources
+    );
+  }
+}
+
+#[cfg(feature = "testing")]
+mod output {
+  use std::path::{Path, PathBuf};
+
+  use base64::Engine;
+  use sass_embedded::{
+    testing::{MockCompiler, MockResponse},
+    Options, OptionsBuilder, Sass, SourceMapOutput, SourceMapUrl,
+  };
+
+  use super::*;
+
+  pub(super) const CSS: &str = "a {\n  b: c;\n  d: e;\n}";
+
+  /// A compiler that compiles every entrypoint to [CSS], with a source map
+  /// whose only source is the entrypoint if one is requested.
+  pub(super) fn compiler() -> Sass {
+    MockCompiler::new(|compilation| {
+      let url = Url::from_file_path(compilation.path().unwrap()).unwrap();
+      MockResponse::Success {
+        css: CSS.to_string(),
+        source_map: compilation.source_map().then(|| {
+          MAP.replace("file:///project/styles/style.scss", url.as_str())
+        }),
+        loaded_urls: vec![url],
+      }
+    })
+    .embedded()
+    .unwrap()
+  }
+
+  pub(super) fn project() -> PathBuf {
+    std::env::temp_dir().join("project")
+  }
+
+  fn compile(entry: &Path, output: SourceMapOutput) -> CompileResult {
+    compiler()
+      .compile(
+        entry,
+        OptionsBuilder::default()
+          .source_map(true)
+          .source_map_output(output)
+          .build(),
+      )
+      .unwrap()
+  }
+
+  #[test]
+  fn leaves_the_source_map_alone_by_default() {
+    let entry = project().join("styles/style.scss");
+    let res = compile(&entry, SourceMapOutput::default());
+    assert_eq!(res.css, CSS);
+    let map = res.parse_source_map().unwrap().unwrap();
+    assert_eq!(map.sources, [Url::from_file_path(&entry).unwrap().as_str()]);
+    assert_eq!(map.file.as_deref(), Some("style.css"));
+  }
+
+  #[test]
+  fn does_nothing_without_a_source_map() {
+    let res = compiler()
+      .compile(
+        project().join("styles/style.scss"),
+        Options {
+          source_map_output: SourceMapOutput {
+            out_file: Some(project().join("dist/style.css")),
+            ..Default::default()
+          },
+          ..Default::default()
+        },
+      )
+      .unwrap();
+    assert_eq!(res.css, CSS);
+    assert_eq!(res.source_map, None);
+  }
+
+  #[test]
+  fn generates_a_source_map_for_a_url() {
+    let res = compiler()
+      .compile(
+        project().join("styles/style.scss"),
+        Options {
+          source_map_output: SourceMapOutput {
+            url: Some(SourceMapUrl::Inline),
+            ..Default::default()
+          },
+          ..Default::default()
+        },
+      )
+      .unwrap();
+    assert!(res
+      .css
+      .contains("/*# sourceMappingURL=data:application/json;"));
+    assert!(res.source_map.is_some());
+  }
+
+  #[test]
+  fn points_to_an_external_file_relative_to_the_out_file() {
+    let res = compile(
+      &project().join("styles/style.scss"),
+      SourceMapOutput {
+        out_file: Some(project().join("dist/style.css")),
+        url: Some(SourceMapUrl::File(
+          project().join("dist/maps/style.css.map"),
+        )),
+        source_root: None,
+      },
+    );
+    assert_eq!(
+      res.css,
+      format!("{}\n\n/*# sourceMappingURL=maps/style.css.map */", CSS)
+    );
+    let map = res.parse_source_map().unwrap().unwrap();
+    assert_eq!(map.sources, ["../../styles/style.scss"]);
+    assert_eq!(map.file.as_deref(), Some("../style.css"));
+    assert_eq!(map.mappings, SourceMap::parse(MAP).unwrap().mappings);
+  }
+
+  #[test]
+  fn embeds_the_source_map_as_a_data_url() {
+    let res = compile(
+      &project().join("styles/style.scss"),
+      SourceMapOutput {
+        out_file: Some(project().join("style.css")),
+        url: Some(SourceMapUrl::Inline),
+        source_root: None,
+      },
+    );
+    let (css, comment) = res.css.split_once("\n\n").unwrap();
+    assert_eq!(css, CSS);
+    let data = comment
+      .strip_prefix(
+        "/*# sourceMappingURL=data:application/json;charset=utf-8;base64,",
+      )
+      .and_then(|data| data.strip_suffix(" */"))
+      .unwrap();
+    let json = base64::engine::general_purpose::STANDARD
+      .decode(data)
+      .unwrap();
+    assert_eq!(
+      String::from_utf8(json).unwrap(),
+      res.source_map.as_deref().unwrap()
+    );
+    let map = res.parse_source_map().unwrap().unwrap();
+    assert_eq!(map.sources, ["styles/style.scss"]);
+    assert_eq!(map.file.as_deref(), Some("style.css"));
+  }
+
+  #[test]
+  fn omits_the_comment_without_a_url() {
+    let res = compile(
+      &project().join("styles/style.scss"),
+      SourceMapOutput {
+        out_file: Some(project().join("dist/style.css")),
+        url: None,
+        source_root: Some("/src/".to_string()),
+      },
+    );
+    assert_eq!(res.css, CSS);
+    let map = res.parse_source_map().unwrap().unwrap();
+    assert_eq!(map.source_root.as_deref(), Some("/src/"));
+    assert_eq!(map.sources, ["../styles/style.scss"]);
+  }
+
+  #[test]
+  fn uses_the_map_path_as_is_without_an_out_file() {
+    let res = compile(
+      &project().join("styles/style.scss"),
+      SourceMapOutput {
+        url: Some(SourceMapUrl::File(PathBuf::from("style.css.map"))),
+        ..Default::default()
+      },
+    );
+    assert!(res
+      .css
+      .ends_with("\n\n/*# sourceMappingURL=style.css.map */"));
+  }
+
+  #[test]
+  fn percent_encodes_relative_urls() {
+    let res = compile(
+      &project().join("my styles/a#1.scss"),
+      SourceMapOutput {
+        out_file: Some(project().join("out dir/style.css")),
+        url: Some(SourceMapUrl::File(
+          project().join("out dir/maps/style?.css.map"),
+        )),
+        source_root: None,
+      },
+    );
+    assert_eq!(
+      res.css,
+      format!("{}\n\n/*# sourceMappingURL=maps/style%3F.css.map */", CSS)
+    );
+    let map = res.parse_source_map().unwrap().unwrap();
+    assert_eq!(map.sources, ["../../my%20styles/a%231.scss"]);
+    assert_eq!(map.file.as_deref(), Some("../style.css"));
+  }
+
+  #[test]
+  fn points_to_an_absolute_map_path_with_a_file_url() {
+    let map_file = project().join("dist/style.css.map");
+    let res = compile(
+      &project().join("styles/style.scss"),
+      SourceMapOutput {
+        url: Some(SourceMapUrl::File(map_file.clone())),
+        ..Default::default()
+      },
+    );
+    let url = Url::from_file_path(&map_file).unwrap();
+    assert!(url.as_str().starts_with("file:///"));
+    assert_eq!(
+      res.css,
+      format!("{}\n\n/*# sourceMappingURL={} */", CSS, url)
+    );
+  }
+}
+
+#[cfg(all(feature = "legacy", feature = "testing"))]
+mod legacy {
+  use sass_embedded::legacy::LegacyOptionsBuilder;
+
+  use super::output::{compiler, project, CSS};
+  use super::*;
+
+  fn render(builder: LegacyOptionsBuilder) -> (String, SourceMap) {
+    let res = compiler()
+      .render(
+        builder
+          .file(project().join("styles/style.scss"))
+          .source_map(true)
+          .build(),
+      )
+      .unwrap();
+    let map =
+      SourceMap::parse(&String::from_utf8(res.map.unwrap()).unwrap()).unwrap();
+    (String::from_utf8(res.css).unwrap(), map)
+  }
+
+  #[test]
+  fn points_to_the_map_next_to_the_out_file() {
+    let (css, map) = render(
+      LegacyOptionsBuilder::default().out_file(project().join("dist/out.css")),
+    );
+    assert_eq!(
+      css,
+      format!("{}\n\n/*# sourceMappingURL=out.css.map */", CSS)
+    );
+    assert_eq!(map.sources, ["../styles/style.scss"]);
+    assert_eq!(map.file.as_deref(), Some("out.css"));
+  }
+
+  #[test]
+  fn embeds_the_map() {
+    let (css, _) = render(
+      LegacyOptionsBuilder::default()
+        .out_file(project().join("dist/out.css"))
+        .source_map_embed(true),
+    );
+    assert!(css.contains(
+      "\n\n/*# sourceMappingURL=data:application/json;charset=utf-8;base64,"
+    ));
+  }
+
+  #[test]
+  fn omits_the_url_and_sets_the_root() {
+    let (css, map) = render(
+      LegacyOptionsBuilder::default()
+        .out_file(project().join("dist/out.css"))
+        .omit_source_map_url(true)
+        .source_map_root("../"),
+    );
+    assert_eq!(css, CSS);
+    assert_eq!(map.source_root.as_deref(), Some("../"));
+    assert_eq!(map.sources, ["../styles/style.scss"]);
+  }
+
+  #[test]
+  fn needs_the_source_map_option() {
+    let res = compiler()
+      .render(
+        LegacyOptionsBuilder::default()
+          .file(project().join("styles/style.scss"))
+          .out_file(project().join("dist/out.css"))
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(String::from_utf8(res.css).unwrap(), CSS);
+    assert!(res.map.is_none());
+  }
+}
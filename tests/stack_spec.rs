@@ -0,0 +1,98 @@
+use std::env;
+
+use sass_embedded::{StackFrame, Url};
+
+fn cwd_url(path: &str) -> Url {
+  Url::from_directory_path(env::current_dir().unwrap())
+    .unwrap()
+    .join(path)
+    .unwrap()
+}
+
+#[test]
+fn parses_a_string_without_a_url() {
+  assert_eq!(
+    StackFrame::parse_trace("- 1:7  root stylesheet\n"),
+    vec![StackFrame {
+      url: None,
+      line: 0,
+      column: 6,
+      member: "root stylesheet".to_string(),
+    }]
+  );
+}
+
+#[test]
+fn parses_aligned_frames() {
+  let frames = StackFrame::parse_trace(
+    "\
+_mixins.scss 3:5              @content
+dir with spaces/a.scss 10:3   foo()
+https://example.com/b.scss 1:1  root stylesheet
+",
+  );
+  assert_eq!(frames.len(), 3);
+  assert_eq!(frames[0].url, Some(cwd_url("_mixins.scss")));
+  assert_eq!((frames[0].line, frames[0].column), (2, 4));
+  assert_eq!(frames[0].member, "@content");
+  assert_eq!(frames[1].url, Some(cwd_url("dir%20with%20spaces/a.scss")));
+  assert_eq!(frames[1].member, "foo()");
+  assert_eq!(
+    frames[2].url,
+    Some(Url::parse("https://example.com/b.scss").unwrap())
+  );
+}
+
+#[cfg(unix)]
+#[test]
+fn parses_absolute_paths() {
+  let frames = StackFrame::parse_trace("/a/b.scss 2:1  root stylesheet");
+  assert_eq!(frames[0].url, Some(Url::parse("file:///a/b.scss").unwrap()));
+}
+
+#[test]
+fn normalizes_relative_paths() {
+  let frames = StackFrame::parse_trace("a/../b/./c.scss 1:1  root stylesheet");
+  assert_eq!(frames[0].url, Some(cwd_url("b/c.scss")));
+}
+
+#[test]
+fn skips_lines_that_are_not_frames() {
+  assert!(StackFrame::parse_trace("\nnot a frame\n- 0:1  x\n").is_empty());
+}
+
+#[test]
+fn displays_like_the_compiler() {
+  let frame = StackFrame {
+    url: None,
+    line: 0,
+    column: 6,
+    member: "root stylesheet".to_string(),
+  };
+  assert_eq!(frame.to_string(), "- 1:7  root stylesheet");
+}
+
+#[cfg(feature = "legacy")]
+mod legacy {
+  use super::*;
+
+  #[test]
+  fn filters_out_the_end_of_load_stylesheets() {
+    let frames = StackFrame::parse_trace(
+      "\
+sass-embedded-legacy-load-done:1234 1:1  @import
+- 2:1  root stylesheet
+",
+    );
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].url, None);
+  }
+
+  #[test]
+  fn resolves_legacy_importer_urls() {
+    let frames = StackFrame::parse_trace(
+      "legacy-importer:dir%2F_a.scss 1:1  root stylesheet",
+    );
+    assert_eq!(frames[0].url, Some(cwd_url("dir/_a.scss")));
+  }
+}
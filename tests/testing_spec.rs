@@ -0,0 +1,472 @@
+#![cfg(feature = "testing")]
+
+use std::{
+  io,
+  panic::{self, AssertUnwindSafe},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use sass_embedded::{
+  testing::{MockCompiler, MockImporter, MockResponse},
+  CompilerEvent, Exception, ExceptionKind, FileImporter, Importer,
+  ImporterOptions, ImporterResult, Logger, LoggerDebugOptions,
+  LoggerWarnOptions, ProtocolErrorType, RestartPolicy, Result, Sass,
+  SassNumber, SassString, StringOptions, StringOptionsBuilder, Syntax, Url,
+  Value,
+};
+
+#[derive(Debug)]
+struct MapImporter;
+
+impl Importer for MapImporter {
+  fn canonicalize(
+    &self,
+    url: &str,
+    options: &ImporterOptions,
+  ) -> Result<Option<Url>> {
+    match url {
+      "bad" => Err(Exception::new("this import is bad actually").into()),
+      "missing" => Ok(None),
+      _ => {
+        assert!(options.from_import);
+        Ok(Some(Url::parse(&format!("u:{}", url)).unwrap()))
+      }
+    }
+  }
+
+  fn load(&self, canonical_url: &Url) -> Result<Option<ImporterResult>> {
+    Ok(Some(ImporterResult {
+      contents: format!("a {{b: {}}}", canonical_url.path()),
+      source_map_url: None,
+      syntax: Syntax::Indented,
+    }))
+  }
+}
+
+#[derive(Debug)]
+struct FooFileImporter;
+
+impl FileImporter for FooFileImporter {
+  fn find_file_url(
+    &self,
+    url: &str,
+    _options: &ImporterOptions,
+  ) -> Result<Option<Url>> {
+    Ok(Some(Url::parse(&format!("file:///{}.scss", url)).unwrap()))
+  }
+}
+
+#[derive(Debug, Default)]
+struct RecordingLogger(Arc<Mutex<Vec<String>>>);
+
+impl Logger for RecordingLogger {
+  fn warn(&self, message: &str, options: &LoggerWarnOptions) {
+    let kind = if options.deprecation {
+      "deprecation"
+    } else {
+      "warn"
+    };
+    self.0.lock().push(format!("{}: {}", kind, message));
+  }
+
+  fn debug(&self, message: &str, _options: &LoggerDebugOptions) {
+    self.0.lock().push(format!("debug: {}", message));
+  }
+}
+
+#[test]
+fn compiles_with_the_response_of_the_script() {
+  let sass = MockCompiler::new(|compilation| {
+    assert_eq!(compilation.source(), Some("a {b: c}"));
+    assert_eq!(compilation.path(), None);
+    MockResponse::css("a {\n  b: c;\n}")
+  })
+  .embedded()
+  .unwrap();
+  let res = sass
+    .compile_string("a {b: c}", StringOptions::default())
+    .unwrap();
+  assert_eq!(res.css, "a {\n  b: c;\n}");
+}
+
+#[test]
+fn answers_version_requests() {
+  let sass = MockCompiler::new(|_| MockResponse::css(""))
+    .embedded()
+    .unwrap();
+  let version = sass.version().unwrap();
+  assert!(version.check_compatible().is_ok());
+  assert_eq!(version.implementation_name, "Mock Sass");
+}
+
+#[test]
+fn reports_compile_failures() {
+  let sass = MockCompiler::new(|_| MockResponse::failure("expected \";\"."))
+    .embedded()
+    .unwrap();
+  let err = sass
+    .compile_string(
+      "a {b: c",
+      StringOptionsBuilder::default()
+        .url(Url::parse("file:///a.scss").unwrap())
+        .build(),
+    )
+    .unwrap_err();
+  assert_eq!(err.kind(), ExceptionKind::CompileFailure);
+  assert_eq!(err.message(), "Error: expected \";\".");
+  let span = err.span().unwrap();
+  assert_eq!(span.url, Some(Url::parse("file:///a.scss").unwrap()));
+  assert_eq!(span.start.line, 0);
+}
+
+mod importers {
+  use super::*;
+
+  #[test]
+  fn canonicalizes_and_loads_with_an_importer() {
+    let sass = MockCompiler::new(|compilation| {
+      let url = compilation.canonicalize(0, "other", true).unwrap().unwrap();
+      assert_eq!(url.as_str(), "u:other");
+      let import = compilation.import(0, &url).unwrap().unwrap();
+      assert!(matches!(import.syntax, Syntax::Indented));
+      MockResponse::css(import.contents)
+    })
+    .embedded()
+    .unwrap();
+    let res = sass
+      .compile_string(
+        "@import 'other'",
+        StringOptionsBuilder::default()
+          .importer(MapImporter)
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "a {b: other}");
+  }
+
+  /// Signals `started`, then canonicalizes once `release` receives, or
+  /// fails after a while.
+  #[derive(Debug)]
+  struct BlockingImporter {
+    started: crossbeam_channel::Sender<()>,
+    release: crossbeam_channel::Receiver<()>,
+  }
+
+  impl Importer for BlockingImporter {
+    fn canonicalize(
+      &self,
+      url: &str,
+      _options: &ImporterOptions,
+    ) -> Result<Option<Url>> {
+      self.started.send(()).unwrap();
+      self
+        .release
+        .recv_timeout(Duration::from_secs(5))
+        .map_err(|_| Exception::new("never released"))?;
+      Ok(Some(Url::parse(&format!("u:{}", url)).unwrap()))
+    }
+
+    fn load(&self, _canonical_url: &Url) -> Result<Option<ImporterResult>> {
+      Ok(None)
+    }
+  }
+
+  #[test]
+  fn does_not_block_other_compilations() {
+    let sass = MockCompiler::new(|compilation| {
+      if compilation.source() == Some("@import 'slow'") {
+        let url = compilation.canonicalize(0, "slow", true).unwrap();
+        MockResponse::css(url.unwrap().to_string())
+      } else {
+        MockResponse::css("fast")
+      }
+    })
+    .embedded()
+    .unwrap();
+    let (started, has_started) = crossbeam_channel::bounded(1);
+    let (release, receiver) = crossbeam_channel::bounded(1);
+    thread::scope(|scope| {
+      let slow = scope.spawn(|| {
+        sass.compile_string(
+          "@import 'slow'",
+          StringOptionsBuilder::default()
+            .importer(BlockingImporter {
+              started,
+              release: receiver,
+            })
+            .build(),
+        )
+      });
+      has_started.recv().unwrap();
+      // Finishes while the importer of the other compilation is running.
+      let res = sass
+        .compile_string("a {b: c}", StringOptions::default())
+        .unwrap();
+      assert_eq!(res.css, "fast");
+      release.send(()).unwrap();
+      assert_eq!(slow.join().unwrap().unwrap().css, "u:slow");
+    });
+  }
+
+  #[test]
+  fn passes_through_null_and_errors() {
+    let sass = MockCompiler::new(|compilation| {
+      assert_eq!(compilation.canonicalize(0, "missing", false), Ok(None));
+      let err = compilation.canonicalize(0, "bad", false).unwrap_err();
+      MockResponse::failure(err)
+    })
+    .embedded()
+    .unwrap();
+    let err = sass
+      .compile_string(
+        "@use 'bad'",
+        StringOptionsBuilder::default()
+          .importer(MapImporter)
+          .build(),
+      )
+      .unwrap_err();
+    assert!(err.message().contains("this import is bad actually"));
+  }
+
+  #[test]
+  fn uses_the_input_importer() {
+    let sass = MockCompiler::new(|compilation| {
+      let url = compilation
+        .canonicalize(MockImporter::Input, "other", true)
+        .unwrap();
+      MockResponse::css(url.unwrap().to_string())
+    })
+    .embedded()
+    .unwrap();
+    let res = sass
+      .compile_string(
+        "@import 'other'",
+        StringOptionsBuilder::default()
+          .input_importer(MapImporter)
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "u:other");
+  }
+
+  #[test]
+  fn resolves_with_a_file_importer() {
+    let sass = MockCompiler::new(|compilation| {
+      let url = compilation.file_import(0, "foo", false).unwrap().unwrap();
+      MockResponse::css(url.to_string())
+    })
+    .embedded()
+    .unwrap();
+    let res = sass
+      .compile_string(
+        "@use 'foo'",
+        StringOptionsBuilder::default()
+          .file_importer(FooFileImporter)
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "file:///foo.scss");
+  }
+}
+
+mod functions {
+  use super::*;
+
+  #[test]
+  fn calls_a_custom_function() {
+    let sass = MockCompiler::new(|compilation| {
+      assert_eq!(compilation.functions(), ["double($n)"]);
+      let value = compilation
+        .call_function("double", vec![SassNumber::new(2.0).into()])
+        .unwrap();
+      MockResponse::css(format!(
+        "{:?}",
+        value.assert_number(None).unwrap().value()
+      ))
+    })
+    .embedded()
+    .unwrap();
+    let res = sass
+      .compile_string(
+        "a {b: double(2)}",
+        StringOptionsBuilder::default()
+          .function("double($n)", |args: &[Value]| {
+            let n = args[0].assert_number(None)?.value();
+            Ok(SassNumber::new(n * 2.0).into())
+          })
+          .build(),
+      )
+      .unwrap();
+    assert_eq!(res.css, "4.0");
+  }
+
+  #[test]
+  fn passes_through_function_errors() {
+    let sass = MockCompiler::new(|compilation| {
+      let err = compilation
+        .call_function("fail", vec![SassString::unquoted("x").into()])
+        .unwrap_err();
+      MockResponse::failure(err)
+    })
+    .embedded()
+    .unwrap();
+    let err = sass
+      .compile_string(
+        "a {b: fail(x)}",
+        StringOptionsBuilder::default()
+          .function("fail($arg)", |_: &[Value]| {
+            Err(Exception::new("heck").into())
+          })
+          .build(),
+      )
+      .unwrap_err();
+    assert_eq!(err.message(), "Error: heck");
+  }
+}
+
+#[test]
+fn emits_log_events() {
+  let sass = MockCompiler::new(|compilation| {
+    compilation.warn("careful", None);
+    compilation.deprecation("old", None);
+    compilation.debug("here", None);
+    MockResponse::css("")
+  })
+  .embedded()
+  .unwrap();
+  let logger = RecordingLogger::default();
+  let events = Arc::clone(&logger.0);
+  sass
+    .compile_string("", StringOptionsBuilder::default().logger(logger).build())
+    .unwrap();
+  assert_eq!(
+    *events.lock(),
+    ["warn: careful", "deprecation: old", "debug: here"]
+  );
+}
+
+mod compiler_failure {
+  use super::*;
+
+  #[test]
+  fn reports_a_protocol_error() {
+    let sass =
+      MockCompiler::new(|_| MockResponse::ProtocolError("nope".into()))
+        .embedded()
+        .unwrap();
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert_eq!(
+      err.kind(),
+      ExceptionKind::Protocol(ProtocolErrorType::Params)
+    );
+    assert!(err.message().contains("nope"));
+  }
+
+  #[test]
+  fn reports_a_panicking_script_as_an_internal_error() {
+    let sass = MockCompiler::new(|_| panic!("oops")).embedded().unwrap();
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert_eq!(
+      err.kind(),
+      ExceptionKind::Protocol(ProtocolErrorType::Internal)
+    );
+  }
+
+  #[test]
+  fn fails_requests_made_after_the_host_disconnected() {
+    let (done, panicked) = crossbeam_channel::unbounded();
+    let sass = MockCompiler::new(move |compilation| {
+      // Gives the host time to time out and close the connection.
+      thread::sleep(Duration::from_millis(200));
+      let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        compilation.canonicalize(0, "other", true)
+      }));
+      done.send(result.is_err()).unwrap();
+      MockResponse::css("")
+    })
+    .embedded()
+    .unwrap();
+    let err = sass
+      .compile_string(
+        "@import 'other'",
+        StringOptionsBuilder::default()
+          .importer(MapImporter)
+          .timeout(Duration::from_millis(50))
+          .build(),
+      )
+      .unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Timeout);
+    assert!(panicked.recv_timeout(Duration::from_secs(5)).unwrap());
+  }
+
+  #[test]
+  fn recovers_from_a_crash() {
+    let sass = MockCompiler::new(|compilation| match compilation.source() {
+      Some("crash") => MockResponse::Crash,
+      _ => MockResponse::css("ok"),
+    })
+    .embedded()
+    .unwrap();
+    let err = sass
+      .compile_string("crash", StringOptions::default())
+      .unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Io);
+    let res = sass.compile_string("", StringOptions::default()).unwrap();
+    assert_eq!(res.css, "ok");
+  }
+
+  #[test]
+  fn backs_off_without_blocking_other_calls() {
+    let compiler =
+      MockCompiler::new(|compilation| match compilation.source() {
+        Some("crash") => MockResponse::Crash,
+        _ => MockResponse::css("ok"),
+      });
+    let connects = Arc::new(AtomicUsize::new(0));
+    let (failed, has_failed) = crossbeam_channel::unbounded();
+    let sass = Sass::with_transport({
+      let connects = Arc::clone(&connects);
+      move || match connects.fetch_add(1, Ordering::SeqCst) {
+        // The first restart fails, so the second one backs off.
+        1 => Err(io::ErrorKind::ConnectionRefused.into()),
+        _ => Ok(compiler.connect()),
+      }
+    })
+    .unwrap()
+    .with_restart_policy(RestartPolicy {
+      initial_backoff: Duration::from_millis(500),
+      ..Default::default()
+    })
+    .on_compiler_event(move |event| {
+      if let CompilerEvent::RestartFailed { .. } = event {
+        failed.send(()).unwrap();
+      }
+    });
+    sass
+      .compile_string("crash", StringOptions::default())
+      .unwrap_err();
+    thread::scope(|scope| {
+      let restarting =
+        scope.spawn(|| sass.compile_string("", StringOptions::default()));
+      has_failed.recv_timeout(Duration::from_secs(5)).unwrap();
+      let start = Instant::now();
+      sass.stderr();
+      assert!(start.elapsed() < Duration::from_millis(250));
+      // Waits for the restart in progress instead of starting another one.
+      let res = sass.compile_string("", StringOptions::default()).unwrap();
+      assert_eq!(res.css, "ok");
+      assert_eq!(restarting.join().unwrap().unwrap().css, "ok");
+    });
+    assert_eq!(connects.load(Ordering::SeqCst), 3);
+  }
+}
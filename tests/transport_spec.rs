@@ -0,0 +1,217 @@
+use std::{
+  io::{self, Write},
+  net::TcpListener,
+  thread,
+  time::Duration,
+};
+
+use parking_lot::Mutex;
+use sass_embedded::{
+  ExceptionKind, MemoryTransport, Sass, StringOptions, TcpTransport, Transport,
+  Version,
+};
+
+/// Encodes a length-delimited protobuf field.
+fn field(tag: u8, bytes: &[u8]) -> Vec<u8> {
+  let mut field = vec![tag << 3 | 2, bytes.len() as u8];
+  field.extend_from_slice(bytes);
+  field
+}
+
+/// The id of an encoded `InboundMessage.VersionRequest`, or `None` for any
+/// other message.
+fn version_request_id(request: &[u8]) -> Option<u8> {
+  match request {
+    [0x3a, 0] => Some(0),
+    [0x3a, 2, 0x08, id] => Some(*id),
+    _ => None,
+  }
+}
+
+/// An encoded `OutboundMessage.VersionResponse`.
+fn version_response(id: u8) -> Vec<u8> {
+  let mut response = [
+    field(1, b"1.2.0"),
+    field(2, b"1.62.0"),
+    field(3, b"1.62.0"),
+    field(4, b"Dart Sass"),
+  ]
+  .concat();
+  if id > 0 {
+    response.extend_from_slice(&[5 << 3, id]);
+  }
+  field(8, &response)
+}
+
+/// Acts as a compiler that only answers version requests, until the
+/// connection is closed or it receives another request.
+fn serve_versions(compiler: impl Transport) {
+  while let Ok(request) = compiler.read() {
+    match version_request_id(&request) {
+      Some(id) => compiler.write(&version_response(id)).unwrap(),
+      None => break,
+    }
+  }
+  compiler.close();
+}
+
+/// A connector that hands out `transport` once.
+fn once<T: Transport + 'static>(
+  transport: T,
+) -> impl Fn() -> io::Result<T> + Send + Sync + 'static {
+  let transport = Mutex::new(Some(transport));
+  move || {
+    transport
+      .lock()
+      .take()
+      .ok_or_else(|| io::ErrorKind::NotConnected.into())
+  }
+}
+
+#[test]
+fn talks_over_an_in_memory_transport() {
+  let (host, compiler) = MemoryTransport::pair();
+  thread::spawn(move || serve_versions(compiler));
+  let sass = Sass::with_transport(once(host)).unwrap();
+  let version = sass.version().unwrap();
+  assert_eq!(version.protocol_version, Version::new(1, 2, 0));
+  assert_eq!(version.implementation_name, "Dart Sass");
+  assert_eq!(sass.info().unwrap(), "sass-embedded\t#1.62.0");
+}
+
+#[test]
+fn requests_the_version_once_per_compiler() {
+  let (host, compiler) = MemoryTransport::pair();
+  let (requested, requests) = crossbeam_channel::unbounded();
+  thread::spawn(move || {
+    while let Ok(request) = compiler.read() {
+      let Some(id) = version_request_id(&request) else {
+        break;
+      };
+      requested.send(()).unwrap();
+      compiler.write(&version_response(id)).unwrap();
+    }
+  });
+  let sass = Sass::with_transport(once(host)).unwrap();
+  for _ in 0..3 {
+    assert_eq!(sass.version().unwrap().implementation_name, "Dart Sass");
+  }
+  assert_eq!(requests.try_iter().count(), 1);
+}
+
+#[test]
+fn talks_over_tcp() {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+  thread::spawn(move || {
+    let (stream, _) = listener.accept().unwrap();
+    serve_versions(TcpTransport::new(stream).unwrap());
+  });
+  let sass = Sass::with_transport(move || TcpTransport::connect(addr)).unwrap();
+  assert_eq!(
+    sass.version().unwrap().compiler_version,
+    Version::new(1, 62, 0)
+  );
+  assert_eq!(
+    sass.version().unwrap().compiler_version,
+    Version::new(1, 62, 0)
+  );
+}
+
+#[cfg(unix)]
+#[test]
+fn talks_over_a_unix_socket() {
+  use std::os::unix::net::UnixListener;
+
+  use sass_embedded::UnixTransport;
+
+  let dir = tempfile::tempdir().unwrap();
+  let path = dir.path().join("sass.sock");
+  let listener = UnixListener::bind(&path).unwrap();
+  thread::spawn(move || {
+    let (stream, _) = listener.accept().unwrap();
+    serve_versions(UnixTransport::new(stream).unwrap());
+  });
+  let sass =
+    Sass::with_transport(move || UnixTransport::connect(&path)).unwrap();
+  assert_eq!(sass.version().unwrap().implementation_name, "Dart Sass");
+}
+
+#[test]
+fn fails_pending_compilations_once_the_transport_closes() {
+  let (host, compiler) = MemoryTransport::pair();
+  // Closes the connection on the compile request.
+  thread::spawn(move || serve_versions(compiler));
+  let sass = Sass::with_transport(once(host)).unwrap();
+  let err = sass
+    .compile_string("a {b: c}", StringOptions::default())
+    .unwrap_err();
+  assert_eq!(err.kind(), ExceptionKind::Io);
+  assert_eq!(
+    err.message(),
+    "The compiler exited unexpectedly: unexpected end of file"
+  );
+}
+
+/// Starts a compiler that answers the first request with `bytes`, and
+/// returns a compiler that connects to it.
+fn corrupt_compiler(bytes: &'static [u8]) -> Sass {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+  thread::spawn(move || {
+    let (mut stream, _) = listener.accept().unwrap();
+    let compiler = TcpTransport::new(stream.try_clone().unwrap()).unwrap();
+    compiler.read().unwrap();
+    stream.write_all(bytes).unwrap();
+    // Keeps the connection open, so only the corrupt message fails the read.
+    let _ = compiler.read();
+  });
+  Sass::with_transport(move || TcpTransport::connect(addr)).unwrap()
+}
+
+#[test]
+fn fails_pending_compilations_on_an_overlong_varint() {
+  let sass = corrupt_compiler(&[0xff; 12]);
+  let err = sass
+    .compile_string("a {b: c}", StringOptions::default())
+    .unwrap_err();
+  assert_eq!(err.kind(), ExceptionKind::Io);
+  assert_eq!(
+    err.message(),
+    "Failed to read from the compiler: varint is too long"
+  );
+}
+
+#[test]
+fn fails_pending_compilations_on_an_overlong_message() {
+  // A length of 2^35 bytes.
+  let sass = corrupt_compiler(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x01]);
+  let err = sass.version().unwrap_err();
+  assert_eq!(err.kind(), ExceptionKind::Io);
+  assert!(
+    err.message().contains("longer than the maximum"),
+    "{}",
+    err.message()
+  );
+}
+
+#[test]
+fn closes_the_transport_once_dropped() {
+  let (host, compiler) = MemoryTransport::pair();
+  let (done, is_done) = crossbeam_channel::bounded(1);
+  thread::spawn(move || {
+    serve_versions(compiler);
+    done.send(()).unwrap();
+  });
+  let sass = Sass::with_transport(once(host)).unwrap();
+  sass.version().unwrap();
+  drop(sass);
+  is_done.recv_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[test]
+fn fails_to_connect() {
+  let err =
+    Sass::with_transport(|| TcpTransport::connect("127.0.0.1:1")).unwrap_err();
+  assert_eq!(err.kind(), ExceptionKind::Spawn);
+}
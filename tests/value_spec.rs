@@ -0,0 +1,251 @@
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap, HashSet},
+  hash::{Hash, Hasher},
+};
+
+use sass_embedded::{
+  ListSeparator, SassArgumentList, SassColor, SassList, SassMap, SassNumber,
+  SassString, Value,
+};
+
+fn hash(value: &Value) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  value.hash(&mut hasher);
+  hasher.finish()
+}
+
+fn list(contents: Vec<Value>, separator: ListSeparator) -> Value {
+  SassList::new(contents, separator, false).into()
+}
+
+fn number(n: f64) -> Value {
+  SassNumber::new(n).into()
+}
+
+mod equality {
+  use super::*;
+
+  #[test]
+  fn strings_ignore_quotes() {
+    let quoted: Value = SassString::new("foo").into();
+    let unquoted: Value = SassString::unquoted("foo").into();
+    assert_eq!(quoted, unquoted);
+    assert_eq!(hash(&quoted), hash(&unquoted));
+  }
+
+  #[test]
+  fn numbers_use_fuzzy_precision() {
+    assert_eq!(number(1.0), number(1.0 + 1e-12));
+    assert_eq!(hash(&number(1.0)), hash(&number(1.0 + 1e-12)));
+    assert_ne!(number(1.0), number(1.0001));
+  }
+
+  #[test]
+  fn numbers_with_different_units_are_not_equal() {
+    let px: Value = SassNumber::with_unit(1.0, "px").into();
+    let em: Value = SassNumber::with_unit(1.0, "em").into();
+    assert_ne!(px, em);
+    assert_ne!(px, number(1.0));
+  }
+
+  #[test]
+  fn colors_compare_rgba_channels() {
+    let rgb: Value = SassColor::rgb(255, 0, 0, 1.0).into();
+    let hsl: Value = SassColor::hsl(0.0, 100.0, 50.0, 1.0).into();
+    let hwb: Value = SassColor::hwb(0.0, 0.0, 0.0, 1.0).into();
+    assert_eq!(rgb, hsl);
+    assert_eq!(rgb, hwb);
+    assert_eq!(hash(&rgb), hash(&hsl));
+    assert_ne!(rgb, SassColor::rgb(255, 0, 0, 0.5).into());
+  }
+
+  #[test]
+  fn lists_compare_separators_and_brackets() {
+    let comma = list(vec![number(1.0), number(2.0)], ListSeparator::Comma);
+    let space = list(vec![number(1.0), number(2.0)], ListSeparator::Space);
+    let bracketed: Value =
+      SassList::new(vec![number(1.0), number(2.0)], ListSeparator::Comma, true)
+        .into();
+    assert_eq!(
+      comma,
+      list(vec![number(1.0), number(2.0)], ListSeparator::Comma)
+    );
+    assert_ne!(comma, space);
+    assert_ne!(comma, bracketed);
+  }
+
+  #[test]
+  fn maps_ignore_order() {
+    let a: Value = SassMap::new([
+      (SassString::new("a").into(), number(1.0)),
+      (SassString::new("b").into(), number(2.0)),
+    ])
+    .into();
+    let b: Value = SassMap::new([
+      (SassString::new("b").into(), number(2.0)),
+      (SassString::new("a").into(), number(1.0)),
+    ])
+    .into();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+  }
+
+  #[test]
+  fn empty_maps_equal_empty_lists() {
+    let map: Value = SassMap::empty().into();
+    let list = list(Vec::new(), ListSeparator::Undecided);
+    assert_eq!(map, list);
+    assert_eq!(hash(&map), hash(&list));
+  }
+
+  #[test]
+  fn argument_lists_equal_lists() {
+    let args: Value = SassArgumentList::new(
+      vec![number(1.0), number(2.0)],
+      HashMap::new(),
+      ListSeparator::Comma,
+    )
+    .into();
+    let list = list(vec![number(1.0), number(2.0)], ListSeparator::Comma);
+    assert_eq!(args, list);
+    assert_eq!(hash(&args), hash(&list));
+  }
+
+  #[test]
+  #[allow(clippy::mutable_key_type)]
+  fn values_can_be_hash_set_members() {
+    let set: HashSet<Value> = [
+      SassString::new("a").into(),
+      SassString::unquoted("a").into(),
+      number(1.0),
+      Value::Null,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.len(), 3);
+  }
+}
+
+mod map {
+  use super::*;
+
+  #[test]
+  fn looks_up_keys_with_sass_equality() {
+    let map = SassMap::new([(SassString::new("a").into(), number(1.0))]);
+    assert_eq!(
+      map.get(&SassString::unquoted("a").into()),
+      Some(&number(1.0))
+    );
+  }
+
+  #[test]
+  fn keeps_the_last_value_for_duplicate_keys() {
+    let map = SassMap::new([
+      (SassString::new("a").into(), number(1.0)),
+      (SassString::new("b").into(), number(2.0)),
+      (SassString::new("a").into(), number(3.0)),
+    ]);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.contents()[0].1, number(3.0));
+  }
+}
+
+mod assertions {
+  use super::*;
+
+  #[test]
+  fn returns_the_typed_value() {
+    assert_eq!(number(1.0).assert_number(None).unwrap().value(), 1.0);
+    assert_eq!(
+      Value::from(SassString::new("a"))
+        .assert_string(None)
+        .unwrap()
+        .text(),
+      "a"
+    );
+    assert!(Value::Boolean(true).assert_boolean(None).unwrap());
+  }
+
+  #[test]
+  fn throws_for_the_wrong_type() {
+    let err = Value::Null.assert_number(None).unwrap_err();
+    assert_eq!(err.message(), "null is not a number.");
+  }
+
+  #[test]
+  fn includes_the_argument_name() {
+    let err = number(1.0).assert_string(Some("name")).unwrap_err();
+    assert_eq!(err.message(), "$name: 1 is not a string.");
+  }
+
+  #[test]
+  fn treats_an_empty_list_as_a_map() {
+    let empty = list(Vec::new(), ListSeparator::Comma);
+    assert!(empty.assert_map(None).unwrap().is_empty());
+    assert!(number(1.0).assert_map(None).is_err());
+  }
+}
+
+mod list_behavior {
+  use super::*;
+
+  #[test]
+  fn treats_a_single_value_as_a_list() {
+    assert_eq!(number(1.0).as_list(), vec![number(1.0)]);
+    assert_eq!(number(1.0).separator(), ListSeparator::Undecided);
+  }
+
+  #[test]
+  fn converts_sass_indices() {
+    let l = list(
+      vec![number(1.0), number(2.0), number(3.0)],
+      ListSeparator::Comma,
+    );
+    assert_eq!(l.sass_index_to_list_index(&number(1.0), None).unwrap(), 0);
+    assert_eq!(l.sass_index_to_list_index(&number(-1.0), None).unwrap(), 2);
+    assert!(l.sass_index_to_list_index(&number(0.0), None).is_err());
+    assert!(l.sass_index_to_list_index(&number(4.0), None).is_err());
+    assert!(l.sass_index_to_list_index(&number(1.5), None).is_err());
+  }
+
+  #[test]
+  fn truthiness() {
+    assert!(!Value::Null.is_truthy());
+    assert!(!Value::Boolean(false).is_truthy());
+    assert!(number(0.0).is_truthy());
+  }
+}
+
+mod display {
+  use super::*;
+
+  #[test]
+  fn formats_like_sass_inspect() {
+    assert_eq!(number(1.5).to_string(), "1.5");
+    assert_eq!(
+      Value::from(SassNumber::with_units(1.0, ["px"], ["s"])).to_string(),
+      "1px/s"
+    );
+    assert_eq!(Value::from(SassString::new("a")).to_string(), "\"a\"");
+    assert_eq!(
+      Value::from(SassColor::rgb(255, 0, 0, 1.0)).to_string(),
+      "#ff0000"
+    );
+    assert_eq!(
+      list(vec![number(1.0), number(2.0)], ListSeparator::Space).to_string(),
+      "1 2"
+    );
+    assert_eq!(
+      list(vec![number(1.0)], ListSeparator::Comma).to_string(),
+      "(1,)"
+    );
+    assert_eq!(
+      Value::from(SassMap::new([(
+        SassString::unquoted("a").into(),
+        number(1.0)
+      )]))
+      .to_string(),
+      "(a: 1)"
+    );
+  }
+}
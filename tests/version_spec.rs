@@ -0,0 +1,148 @@
+#[path = "helpers.rs"]
+mod helpers;
+
+use helpers::exe_path;
+use sass_embedded::{
+  CompilerCommand, ExceptionKind, Sass, Version, VersionInfo, PROTOCOL_VERSION,
+};
+
+fn info(protocol_version: &str) -> VersionInfo {
+  VersionInfo {
+    protocol_version: Version::parse(protocol_version).unwrap(),
+    compiler_version: Version::new(1, 62, 0),
+    implementation_version: Version::new(1, 62, 0),
+    implementation_name: "Dart Sass".to_string(),
+  }
+}
+
+mod check_compatible {
+  use super::*;
+
+  #[test]
+  fn accepts_the_generated_version() {
+    assert!(info(PROTOCOL_VERSION).check_compatible().is_ok());
+  }
+
+  #[test]
+  fn accepts_newer_minor_versions() {
+    assert!(info("1.3.0").check_compatible().is_ok());
+    assert!(info("1.2.1").check_compatible().is_ok());
+  }
+
+  #[test]
+  fn rejects_older_versions() {
+    let err = info("1.0.0").check_compatible().unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Version);
+    assert!(err
+      .message()
+      .contains("version 1.0.0 of the embedded protocol"));
+  }
+
+  #[test]
+  fn rejects_other_major_versions() {
+    let err = info("2.0.0").check_compatible().unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Version);
+  }
+}
+
+#[test]
+fn reports_the_compiler_versions() {
+  let sass = Sass::new(exe_path()).unwrap();
+  let version = sass.version().unwrap();
+  assert_eq!(version.implementation_name, "Dart Sass");
+  assert!(
+    version.protocol_version >= Version::parse(PROTOCOL_VERSION).unwrap()
+  );
+}
+
+#[test]
+fn handshakes_with_a_compatible_compiler() {
+  let command = CompilerCommand::new(exe_path()).handshake();
+  assert!(Sass::with_command(command).is_ok());
+}
+
+#[cfg(unix)]
+mod handshake {
+  use std::{fs, os::unix::fs::PermissionsExt, thread, time::Duration};
+
+  use helpers::Sandbox;
+  use sass_embedded::StringOptions;
+
+  use super::*;
+
+  /// Encodes a length-delimited protobuf field.
+  fn field(tag: u8, bytes: &[u8]) -> Vec<u8> {
+    let mut field = vec![tag << 3 | 2, bytes.len() as u8];
+    field.extend_from_slice(bytes);
+    field
+  }
+
+  /// An `OutboundMessage.VersionResponse` to the first request, with its
+  /// length prefix.
+  fn version_response(protocol_version: &str) -> Vec<u8> {
+    let response = [
+      field(1, protocol_version.as_bytes()),
+      field(2, b"1.62.0"),
+      field(3, b"1.62.0"),
+      field(4, b"Dart Sass"),
+    ]
+    .concat();
+    let message = field(8, &response);
+    [vec![message.len() as u8], message].concat()
+  }
+
+  /// A compiler that answers the version request of its first start with
+  /// `first`, and of every restart with `then`, and exits.
+  fn compiler(sandbox: &Sandbox, first: &str, then: &str) -> CompilerCommand {
+    let dir = sandbox.path();
+    fs::write(dir.join("first.bin"), version_response(first)).unwrap();
+    fs::write(dir.join("then.bin"), version_response(then)).unwrap();
+    let path = dir.join("compiler.sh");
+    sandbox.write(
+      &path,
+      &format!(
+        "#!/bin/sh\ncd '{}'\nhead -c 3 >/dev/null\n\
+         if [ -e started ]; then cat then.bin; \
+         else touch started; cat first.bin; fi\n",
+        dir.display()
+      ),
+    );
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    CompilerCommand::new(path).handshake()
+  }
+
+  #[test]
+  fn rejects_an_incompatible_compiler() {
+    let sandbox = Sandbox::default();
+    let command = compiler(&sandbox, "1.0.0", "1.0.0");
+    let err = Sass::with_command(command).unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Version);
+  }
+
+  #[test]
+  fn checks_a_restarted_compiler_again() {
+    let sandbox = Sandbox::default();
+    let sass =
+      Sass::with_command(compiler(&sandbox, PROTOCOL_VERSION, "1.0.0"))
+        .unwrap();
+    // Lets the compiler exit after the handshake.
+    thread::sleep(Duration::from_millis(500));
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Version);
+  }
+
+  #[cfg(feature = "async")]
+  #[tokio::test]
+  async fn rejects_an_incompatible_async_compiler() {
+    let sandbox = Sandbox::default();
+    let command = compiler(&sandbox, "1.0.0", "1.0.0");
+    let sass = sass_embedded::AsyncEmbedded::with_command(command).unwrap();
+    let err = sass
+      .compile_string("a {b: c}", StringOptions::default())
+      .await
+      .unwrap_err();
+    assert_eq!(err.kind(), ExceptionKind::Version);
+  }
+}
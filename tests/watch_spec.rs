@@ -0,0 +1,190 @@
+#![cfg(all(feature = "watch", feature = "testing"))]
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  thread::{self, JoinHandle},
+  time::Duration,
+};
+
+use crossbeam_channel::Receiver;
+use sass_embedded::{
+  testing::{MockCompiler, MockResponse},
+  CancellationToken, Options, Result, Url, WatchOptions,
+};
+
+/// A compiler whose stylesheets list the files they consist of, one per
+/// line, and compile to the contents of those files.
+fn concat_compiler() -> MockCompiler {
+  MockCompiler::new(|compilation| {
+    let entry = compilation.path().unwrap();
+    let dir = entry.parent().unwrap();
+    let mut css = String::new();
+    let mut loaded_urls = vec![Url::from_file_path(entry).unwrap()];
+    for name in fs::read_to_string(entry).unwrap().lines() {
+      let path = dir.join(name);
+      match fs::read_to_string(&path) {
+        Ok(contents) => css.push_str(&contents),
+        Err(_) => return MockResponse::failure(format!("{} is missing", name)),
+      }
+      loaded_urls.push(Url::from_file_path(path).unwrap());
+    }
+    MockResponse::Success {
+      css,
+      source_map: None,
+      loaded_urls,
+    }
+  })
+}
+
+struct Watch {
+  token: CancellationToken,
+  events: Receiver<(String, std::result::Result<String, String>)>,
+  handle: JoinHandle<Result<()>>,
+}
+
+impl Watch {
+  fn start(entries: Vec<PathBuf>) -> Self {
+    let token = CancellationToken::new();
+    let (sender, events) = crossbeam_channel::unbounded();
+    let options = WatchOptions {
+      debounce: Duration::from_millis(100),
+      cancellation_token: Some(token.clone()),
+    };
+    let handle = thread::spawn(move || {
+      let sass = concat_compiler().embedded().unwrap();
+      sass.watch_with(
+        entries,
+        |_| Options::default(),
+        |entry, result| {
+          let name = entry.file_name().unwrap().to_string_lossy().into_owned();
+          let result = result.map(|res| res.css).map_err(|e| e.to_string());
+          sender.send((name, result)).unwrap();
+        },
+        &options,
+      )
+    });
+    Self {
+      token,
+      events,
+      handle,
+    }
+  }
+
+  fn next(&self) -> (String, std::result::Result<String, String>) {
+    self.events.recv_timeout(Duration::from_secs(5)).unwrap()
+  }
+
+  fn assert_idle(&self) {
+    let event = self.events.recv_timeout(Duration::from_millis(400));
+    assert!(event.is_err(), "unexpected {:?}", event);
+  }
+
+  fn stop(self) {
+    self.token.cancel();
+    self.handle.join().unwrap().unwrap();
+  }
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+  let path = dir.join(name);
+  fs::write(&path, contents).unwrap();
+  path
+}
+
+#[test]
+fn compiles_every_entry_at_first() {
+  let dir = tempfile::tempdir().unwrap();
+  write(dir.path(), "_a.scss", "a {}");
+  let a = write(dir.path(), "a.scss", "_a.scss");
+  let b = write(dir.path(), "b.scss", "");
+  let watch = Watch::start(vec![a, b]);
+  assert_eq!(watch.next(), ("a.scss".into(), Ok("a {}".into())));
+  assert_eq!(watch.next(), ("b.scss".into(), Ok("".into())));
+  watch.stop();
+}
+
+#[test]
+fn recompiles_only_the_affected_entries() {
+  let dir = tempfile::tempdir().unwrap();
+  write(dir.path(), "_shared.scss", "shared {}");
+  write(dir.path(), "_b.scss", "b {}");
+  let a = write(dir.path(), "a.scss", "_shared.scss");
+  let b = write(dir.path(), "b.scss", "_shared.scss\n_b.scss");
+  let watch = Watch::start(vec![a, b]);
+  watch.next().1.unwrap();
+  watch.next().1.unwrap();
+  watch.assert_idle();
+
+  write(dir.path(), "_b.scss", "b {c: d}");
+  assert_eq!(
+    watch.next(),
+    ("b.scss".into(), Ok("shared {}b {c: d}".into()))
+  );
+  watch.assert_idle();
+
+  write(dir.path(), "_shared.scss", "shared {c: d}");
+  assert_eq!(watch.next().0, "a.scss");
+  assert_eq!(watch.next().0, "b.scss");
+  watch.assert_idle();
+
+  write(dir.path(), "_unrelated.scss", "");
+  watch.assert_idle();
+  watch.stop();
+}
+
+#[test]
+fn debounces_bursts_of_changes() {
+  let dir = tempfile::tempdir().unwrap();
+  write(dir.path(), "_a.scss", "");
+  let a = write(dir.path(), "a.scss", "_a.scss");
+  let watch = Watch::start(vec![a]);
+  watch.next().1.unwrap();
+  watch.assert_idle();
+
+  for i in 0..5 {
+    write(dir.path(), "_a.scss", &i.to_string());
+    thread::sleep(Duration::from_millis(10));
+  }
+  assert_eq!(watch.next(), ("a.scss".into(), Ok("4".into())));
+  watch.assert_idle();
+  watch.stop();
+}
+
+#[test]
+fn keeps_watching_after_an_error() {
+  let dir = tempfile::tempdir().unwrap();
+  write(dir.path(), "_a.scss", "a {}");
+  let a = write(dir.path(), "a.scss", "_a.scss");
+  let watch = Watch::start(vec![a]);
+  watch.next().1.unwrap();
+  watch.assert_idle();
+
+  fs::remove_file(dir.path().join("_a.scss")).unwrap();
+  let (entry, result) = watch.next();
+  assert_eq!(entry, "a.scss");
+  assert!(result.unwrap_err().contains("_a.scss is missing"));
+
+  write(dir.path(), "_a.scss", "a {b: c}");
+  assert_eq!(watch.next(), ("a.scss".into(), Ok("a {b: c}".into())));
+  watch.stop();
+}
+
+#[test]
+fn watches_new_dependencies() {
+  let dir = tempfile::tempdir().unwrap();
+  fs::create_dir(dir.path().join("lib")).unwrap();
+  write(&dir.path().join("lib"), "_a.scss", "a {}");
+  let a = write(dir.path(), "a.scss", "");
+  let watch = Watch::start(vec![a]);
+  watch.next().1.unwrap();
+  watch.assert_idle();
+
+  write(dir.path(), "a.scss", "lib/_a.scss");
+  assert_eq!(watch.next(), ("a.scss".into(), Ok("a {}".into())));
+  watch.assert_idle();
+
+  write(&dir.path().join("lib"), "_a.scss", "a {b: c}");
+  assert_eq!(watch.next(), ("a.scss".into(), Ok("a {b: c}".into())));
+  watch.stop();
+}